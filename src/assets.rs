@@ -2,16 +2,38 @@ use std::path::Path;
 use gltf::{material::NormalTexture, mesh::util::{ReadIndices, ReadTexCoords}, texture::Info};
 use nalgebra::{Vector3, Vector4};
 
-use crate::{image_texture::ImageTexture, mesh::Mesh};
+use crate::{error::{GfxError, GfxResult}, image_texture::ImageTexture, mesh::Mesh};
 
 pub struct AssetLoader;
 
 impl AssetLoader {
+    /// Loads all meshes of a gltf file.
+    /// Prints the error and returns an empty list if the file cannot be loaded.
     pub fn load_gltf(file: &str) -> Vec<Mesh> {
+        match AssetLoader::try_load_gltf(file) {
+            Ok(meshes) => {
+                return meshes;
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                return Vec::new();
+            }
+        }
+    }
+
+    /// Loads all meshes of a gltf file.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::Gltf` if the file cannot be imported.
+    /// - Returns an image error if one of the referenced textures cannot be loaded.
+    pub fn try_load_gltf(file: &str) -> GfxResult<Vec<Mesh>> {
         let path = Path::new(file);
-        let parent_path = path.parent().unwrap();
+        let parent_path = path.parent().and_then(|p| p.to_str()).unwrap_or("");
 
-        let (gltf, buffers, _) = gltf::import(file).unwrap();
+        let (gltf, buffers, _) = gltf::import(file).map_err(|e| GfxError::Gltf {
+            path: file.to_string(),
+            reason: e.to_string(),
+        })?;
         let mut meshes = Vec::<Mesh>::new();
 
         for scene in gltf.scenes() {
@@ -32,31 +54,31 @@ impl AssetLoader {
 
                         //Load Basecolor
                         let color_factor = primitive.material().pbr_metallic_roughness().base_color_factor();
-                        gfx_mesh.material.base_color_texture = AssetLoader::extract_texture(
+                        gfx_mesh.material.base_color_texture = AssetLoader::try_extract_texture(
                             primitive
                                 .material()
                                 .pbr_metallic_roughness()
                                 .base_color_texture(),
-                            parent_path.to_str().unwrap(),
-                        );
+                            parent_path,
+                        )?;
                         gfx_mesh.material.base_color_friction = Vector4::new(color_factor[0], color_factor[1], color_factor[2], color_factor[3]);
 
                         //Load Metallic_Roughness
-                        gfx_mesh.material.metallic_roughness_texture = AssetLoader::extract_texture(
+                        gfx_mesh.material.metallic_roughness_texture = AssetLoader::try_extract_texture(
                             primitive
                                 .material()
                                 .pbr_metallic_roughness()
                                 .metallic_roughness_texture(),
-                            parent_path.to_str().unwrap(),
-                        );
+                            parent_path,
+                        )?;
                         gfx_mesh.material.metallic_factor = primitive.material().pbr_metallic_roughness().metallic_factor();
                         gfx_mesh.material.roughness_factor = primitive.material().pbr_metallic_roughness().roughness_factor();
 
                         //Load Normal Map
-                        gfx_mesh.material.normal_map = AssetLoader::extract_normal_map(
+                        gfx_mesh.material.normal_map = AssetLoader::try_extract_normal_map(
                             primitive.material().normal_texture(),
-                            parent_path.to_str().unwrap(),
-                        );
+                            parent_path,
+                        )?;
 
                         let r = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
                         if let Some(ReadIndices::U16(gltf::accessor::Iter::Standard(iter))) =
@@ -95,43 +117,66 @@ impl AssetLoader {
                 }
             }
         }
-        return meshes;
+        return Ok(meshes);
     }
 
     pub fn extract_texture(texture_info: Option<Info>, base_path: &str) -> Option<ImageTexture> {
-        if let Some(texture_info) = texture_info {
-            let texture = texture_info.texture();
-            let source = texture.source().source();
-            match source {
-                gltf::image::Source::Uri { uri, mime_type: _ } => {
-                    let texture_path = base_path.to_owned() + "/" + uri;
-                    return Some(ImageTexture::load_from_file(&texture_path, false));
-                }
-                _ => {
-                    eprintln!("Not Supportet yet!");
-                }
-            };
+        match AssetLoader::try_extract_texture(texture_info, base_path) {
+            Ok(texture) => texture,
+            Err(e) => {
+                eprintln!("{}", e);
+                None
+            }
         }
-        return None;
     }
 
     pub fn extract_normal_map(
         normal_map: Option<NormalTexture>,
         base_path: &str,
     ) -> Option<ImageTexture> {
+        match AssetLoader::try_extract_normal_map(normal_map, base_path) {
+            Ok(texture) => texture,
+            Err(e) => {
+                eprintln!("{}", e);
+                None
+            }
+        }
+    }
+
+    /// Loads the texture referenced by the texture info.
+    ///
+    /// # Errors
+    /// - Returns an error if the texture cannot be loaded or is embedded into a buffer.
+    pub fn try_extract_texture(texture_info: Option<Info>, base_path: &str) -> GfxResult<Option<ImageTexture>> {
+        if let Some(texture_info) = texture_info {
+            return AssetLoader::load_texture_source(texture_info.texture().source().source(), base_path).map(Some);
+        }
+        return Ok(None);
+    }
+
+    /// Loads the texture referenced by the normal texture info.
+    ///
+    /// # Errors
+    /// - Returns an error if the texture cannot be loaded or is embedded into a buffer.
+    pub fn try_extract_normal_map(
+        normal_map: Option<NormalTexture>,
+        base_path: &str,
+    ) -> GfxResult<Option<ImageTexture>> {
         if let Some(normal) = normal_map {
-            let texture = normal.texture();
-            let source = texture.source().source();
-            match source {
-                gltf::image::Source::Uri { uri, mime_type: _ } => {
-                    let texture_path = base_path.to_owned() + "/" + uri;
-                    return Some(ImageTexture::load_from_file(&texture_path, false));
-                }
-                _ => {
-                    eprintln!("Not Supportet yet!");
-                }
+            return AssetLoader::load_texture_source(normal.texture().source().source(), base_path).map(Some);
+        }
+        return Ok(None);
+    }
+
+    fn load_texture_source(source: gltf::image::Source, base_path: &str) -> GfxResult<ImageTexture> {
+        match source {
+            gltf::image::Source::Uri { uri, mime_type: _ } => {
+                let texture_path = base_path.to_owned() + "/" + uri;
+                return ImageTexture::try_load_from_file(&texture_path, false);
+            }
+            _ => {
+                return Err(GfxError::Unsupported(String::from("textures embedded into gltf buffers")));
             }
         }
-        return None;
     }
 }
@@ -0,0 +1,87 @@
+use std::fmt;
+
+use crate::shader::ShaderStage;
+
+/// Represents the errors which can occur while loading, building or drawing resources.
+#[derive(Debug)]
+pub enum GfxError {
+    /// A file could not be read or written.
+    Io {
+        path: String,
+        source: std::io::Error
+    },
+    /// An image could not be decoded.
+    ImageLoad {
+        path: String,
+        reason: String
+    },
+    /// A gltf file could not be imported.
+    Gltf {
+        path: String,
+        reason: String
+    },
+    /// A font could not be loaded.
+    Font {
+        path: String,
+        reason: String
+    },
+    /// A shader could not be compiled.
+    ShaderCompile {
+        stage: ShaderStage,
+        /// The full info log of the driver.
+        log: String,
+        /// The source lines referenced by the info log, including some context.
+        source_lines: String
+    },
+    /// A shader source could not be preprocessed, e.g. because of an unresolved include.
+    ShaderPreprocess(String),
+    /// A shader program could not be linked. Contains the full info log of the driver.
+    ShaderLink(String),
+    /// A shader program whose last build failed was used. Contains the error of the build.
+    ShaderBuildFailed(String),
+    /// A framebuffer is not complete. Contains the status returned by `glCheckFramebufferStatus`.
+    IncompleteFramebuffer(u32),
+    /// An object is in the wrong state for the requested operation, e.g. drawing an unloaded texture.
+    InvalidState(String),
+    /// A resource of the render device is missing, e.g. a render shape.
+    MissingResource(String),
+    /// The requested operation or format is not supported.
+    Unsupported(String),
+}
+
+/// A result with a `GfxError` as error type.
+pub type GfxResult<T> = Result<T, GfxError>;
+
+impl fmt::Display for GfxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GfxError::Io { path, source } => write!(f, "Unable to access the file {}: {}", path, source),
+            GfxError::ImageLoad { path, reason } => write!(f, "Unable to load the image {}: {}", path, reason),
+            GfxError::Gltf { path, reason } => write!(f, "Unable to load the gltf file {}: {}", path, reason),
+            GfxError::Font { path, reason } => write!(f, "Unable to load the font {}: {}", path, reason),
+            GfxError::ShaderCompile { stage, log, source_lines } => {
+                write!(f, "Unable to compile the {} shader:\n{}", stage, log.trim_end())?;
+                if !source_lines.is_empty() {
+                    write!(f, "\n{}", source_lines.trim_end())?;
+                }
+                Ok(())
+            }
+            GfxError::ShaderPreprocess(reason) => write!(f, "Unable to preprocess the shader: {}", reason),
+            GfxError::ShaderLink(log) => write!(f, "Unable to link the shader program:\n{}", log.trim_end()),
+            GfxError::ShaderBuildFailed(error) => write!(f, "The shader program failed to build: {}", error),
+            GfxError::IncompleteFramebuffer(status) => write!(f, "The framebuffer is incomplete (status 0x{:X})", status),
+            GfxError::InvalidState(reason) => write!(f, "Invalid state: {}", reason),
+            GfxError::MissingResource(name) => write!(f, "Missing resource: {}", name),
+            GfxError::Unsupported(reason) => write!(f, "Not supported: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for GfxError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GfxError::Io { path: _, source } => Some(source),
+            _ => None
+        }
+    }
+}
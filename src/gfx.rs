@@ -17,6 +17,12 @@ pub struct FrameStatsRecorder {
     pub last_frame: u128,
 }
 
+impl Default for FrameStatsRecorder {
+    fn default() -> Self {
+        FrameStatsRecorder::new()
+    }
+}
+
 impl FrameStatsRecorder {
     /// Creates a new `FrameStatsRecorder` instance.
     ///
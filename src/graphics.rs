@@ -1,18 +1,28 @@
-use nalgebra::{Matrix4, Vector4};
+use std::ops::Range;
+
+use nalgebra::{Matrix4, Vector2, Vector3, Vector4};
 
 extern crate gl;
 extern crate glfw;
 extern crate nalgebra;
 
 pub mod camera;
+pub mod cubemap;
 pub mod game_window;
+pub mod headless_context;
 pub mod image_texture;
+pub mod sprite_batcher;
 pub mod sprite_sheet;
 pub mod material;
 pub mod mesh;
+pub mod post_process;
 pub mod render_device;
+pub mod render_state;
 pub mod render_target;
+pub mod sampler;
 pub mod shapes;
+pub mod texture_array;
+pub mod uniform_buffer;
 pub mod viewport;
 pub mod font;
 pub mod light;
@@ -42,13 +52,44 @@ pub struct RenderData {
     pub index_count: u32,
 }
 
+/// The z distance between two neighbouring 2D layers.
+///
+/// With an `OrthographicCamera` at z = 1 and a near plane of 0.1, the layers up to 900 lie in front of the near plane.
+pub const LAYER_DEPTH_STEP : f32 = 0.001;
+
+/// Returns the model matrix moved to the depth of the given 2D layer.
+pub fn apply_layer(model_matrix : Matrix4<f32>, layer : f32) -> Matrix4<f32> {
+    if layer == 0.0 {
+        return model_matrix;
+    }
+    return Matrix4::new_translation(&Vector3::new(0.0, 0.0, layer * LAYER_DEPTH_STEP)) * model_matrix;
+}
+
+/// The flag within `extras.y` of the instance data, which flips the texture horizontally.
+pub const TEXTURE2D_FLIP_X : u32 = 1;
+/// The flag within `extras.y` of the instance data, which flips the texture vertically.
+pub const TEXTURE2D_FLIP_Y : u32 = 2;
+
 /// Represents an instance of a 2D texture with its transformation matrix, color, and UV transformation.
 #[derive(Default, Clone, Copy)]
 pub struct Texture2DInstance {
     pub transform: Matrix4<f32>,
     pub color: Vector4<f32>,
     pub uv_transform: Vector4<f32>,
-    pub visible : bool
+    pub visible : bool,
+    /// The point of the quad which is placed at the origin of the transform, relative to the center of the quad
+    /// in local units. (-0.5, -0.5) is the bottom left corner, (0.0, 0.0) the center.
+    pub pivot : Vector2<f32>,
+    /// Mirrors the texture horizontally.
+    pub flip_x : bool,
+    /// Mirrors the texture vertically.
+    pub flip_y : bool,
+    /// The 2D layer of the instance. Higher layers are drawn in front of lower layers, see `LAYER_DEPTH_STEP`.
+    pub layer : f32,
+    /// User defined data, which custom batch shaders can read from the attribute at location 9.
+    pub custom : Vector4<f32>,
+    /// The layer of the texture array the instance is drawn with, see `RenderDevice::draw_texture2d_array_batch`.
+    pub texture_index : u32,
 }
 
 
@@ -65,16 +106,79 @@ impl Texture2DInstance {
     /// - A new `Texture2DInstance` object.
     pub fn new(transform : Matrix4<f32>, color : Vector4<f32>, uv_transform : Vector4<f32>, visible : bool) -> Texture2DInstance {
         Texture2DInstance{
-            transform, color, uv_transform, visible
+            transform, color, uv_transform, visible,
+            ..Default::default()
         }
     }
 
+    /// Sets the pivot of the instance, see `Texture2DInstance::pivot`.
+    pub fn with_pivot(mut self, pivot : Vector2<f32>) -> Texture2DInstance {
+        self.pivot = pivot;
+        return self;
+    }
+
+    /// Sets whether the texture is mirrored horizontally and vertically.
+    pub fn with_flip(mut self, flip_x : bool, flip_y : bool) -> Texture2DInstance {
+        self.flip_x = flip_x;
+        self.flip_y = flip_y;
+        return self;
+    }
+
+    /// Sets the depth layer of the instance.
+    pub fn with_layer(mut self, layer : f32) -> Texture2DInstance {
+        self.layer = layer;
+        return self;
+    }
+
+    /// Sets the layer of the texture array the instance is drawn with.
+    pub fn with_texture_index(mut self, texture_index : u32) -> Texture2DInstance {
+        self.texture_index = texture_index;
+        return self;
+    }
+
+    /// Sets the user defined data of the instance.
+    pub fn with_custom(mut self, custom : Vector4<f32>) -> Texture2DInstance {
+        self.custom = custom;
+        return self;
+    }
+
+    /// Creates the extras vector of the instance data.
+    ///
+    /// # Returns
+    /// - x = visible (0.0 or 1.0), y = the flip flags `TEXTURE2D_FLIP_X` and `TEXTURE2D_FLIP_Y`, z = layer, w = texture index.
     pub fn create_extras_vec4(&self) -> Vector4<f32> {
         let visible = self.visible as i32;
-        let extras = Vector4::new(visible as f32, 0.0, 0.0, 0.0);
+        let mut flags = 0;
+        if self.flip_x {
+            flags |= TEXTURE2D_FLIP_X;
+        }
+        if self.flip_y {
+            flags |= TEXTURE2D_FLIP_Y;
+        }
+        let extras = Vector4::new(visible as f32, flags as f32, self.layer, self.texture_index as f32);
         return extras;
     }
 
+    /// Returns the transform of the instance with the pivot and the layer applied.
+    pub fn create_model_matrix(&self) -> Matrix4<f32> {
+        let mut model_matrix = self.transform;
+        if self.pivot != Vector2::zeros() {
+            model_matrix *= Matrix4::new_translation(&Vector3::new(-self.pivot.x, -self.pivot.y, 0.0));
+        }
+        return apply_layer(model_matrix, self.layer);
+    }
+
+    /// Creates the data of the instance as it is stored within the instance buffer of a loaded batch.
+    pub fn create_instance_data(&self) -> Texture2DInstanceData {
+        return Texture2DInstanceData {
+            transform: self.create_model_matrix(),
+            color: self.color,
+            uv_transform: self.uv_transform,
+            extras: self.create_extras_vec4(),
+            custom: self.custom
+        };
+    }
+
     /// Provides a default UV transformation vector.
     ///
     /// # Returns
@@ -85,27 +189,353 @@ impl Texture2DInstance {
 
 }
 
+/// The interleaved per instance data of a `Texture2DBatch`, as it is stored within its instance buffer.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Texture2DInstanceData {
+    pub transform : Matrix4<f32>,
+    pub color : Vector4<f32>,
+    pub uv_transform : Vector4<f32>,
+    /// x = visible, y = flip flags, z = layer, w = texture index
+    pub extras : Vector4<f32>,
+    pub custom : Vector4<f32>,
+}
+
+/// A stable handle to an instance within a `Texture2DBatch`.
+///
+/// Unlike an index, a handle keeps referring to the same instance while other instances are added, removed or reordered.
+/// A handle becomes invalid once its instance has been removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Texture2DInstanceHandle {
+    slot : u32,
+    generation : u32,
+}
+
+/// The slot of a handle, pointing to the index of its instance.
+#[derive(Clone, Copy)]
+struct InstanceSlot {
+    index : Option<usize>,
+    generation : u32,
+}
+
+/// The instances of a `Texture2DBatch` in draw order, addressable by stable handles.
+///
+/// ### Notes:
+/// - Every modification marks the range of changed indices as dirty. The render device uploads the dirty range
+///   with a single call before the batch is drawn.
+#[derive(Clone, Default)]
+pub struct Texture2DInstances {
+    instances : Vec<Texture2DInstance>,
+    /// The slot of each instance, parallel to `instances`.
+    instance_slots : Vec<u32>,
+    slots : Vec<InstanceSlot>,
+    free_slots : Vec<u32>,
+    /// The range of indices which changed since the last upload.
+    dirty : Option<Range<usize>>,
+}
+
+impl Texture2DInstances {
+
+    /// Creates an empty instance list.
+    pub fn new() -> Texture2DInstances {
+        return Texture2DInstances::default();
+    }
+
+    /// Appends an instance and returns its handle.
+    pub fn insert(&mut self, instance : Texture2DInstance) -> Texture2DInstanceHandle {
+        let index = self.instances.len();
+        let slot = match self.free_slots.pop() {
+            Some(slot) => slot,
+            None => {
+                self.slots.push(InstanceSlot { index: None, generation: 0 });
+                self.slots.len() as u32 - 1
+            }
+        };
+        self.slots[slot as usize].index = Some(index);
+        self.instances.push(instance);
+        self.instance_slots.push(slot);
+        self.mark_range_dirty(index, index + 1);
+        return Texture2DInstanceHandle { slot, generation: self.slots[slot as usize].generation };
+    }
+
+    /// Removes an instance in constant time. The last instance takes the place of the removed one.
+    ///
+    /// # Returns
+    /// - The removed instance, or `None` if the handle is invalid.
+    pub fn remove(&mut self, handle : Texture2DInstanceHandle) -> Option<Texture2DInstance> {
+        let index = self.index_of(handle)?;
+        let instance = self.instances.swap_remove(index);
+        self.instance_slots.swap_remove(index);
+        if index < self.instances.len() {
+            self.slots[self.instance_slots[index] as usize].index = Some(index);
+            self.mark_range_dirty(index, index + 1);
+        }
+        self.free_slot(handle.slot);
+        return Some(instance);
+    }
+
+    /// Removes an instance and keeps the order of the remaining instances. Takes linear time.
+    ///
+    /// # Returns
+    /// - The removed instance, or `None` if the handle is invalid.
+    pub fn remove_ordered(&mut self, handle : Texture2DInstanceHandle) -> Option<Texture2DInstance> {
+        let index = self.index_of(handle)?;
+        let instance = self.instances.remove(index);
+        self.instance_slots.remove(index);
+        self.reindex(index, self.instances.len());
+        self.free_slot(handle.slot);
+        return Some(instance);
+    }
+
+    /// Removes all instances. All handles become invalid.
+    pub fn clear(&mut self) {
+        for slot in std::mem::take(&mut self.instance_slots) {
+            self.free_slot(slot);
+        }
+        self.instances.clear();
+    }
+
+    /// Returns the current index of the instance within the draw order, or `None` if the handle is invalid.
+    pub fn index_of(&self, handle : Texture2DInstanceHandle) -> Option<usize> {
+        let slot = self.slots.get(handle.slot as usize)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        return slot.index;
+    }
+
+    /// Returns the handle of the instance at the given index.
+    pub fn handle_at(&self, index : usize) -> Option<Texture2DInstanceHandle> {
+        let slot = *self.instance_slots.get(index)?;
+        return Some(Texture2DInstanceHandle { slot, generation: self.slots[slot as usize].generation });
+    }
+
+    /// Returns true if the handle refers to an instance of this list.
+    pub fn contains(&self, handle : Texture2DInstanceHandle) -> bool {
+        return self.index_of(handle).is_some();
+    }
+
+    /// Returns the instance of the handle.
+    pub fn get(&self, handle : Texture2DInstanceHandle) -> Option<&Texture2DInstance> {
+        let index = self.index_of(handle)?;
+        return self.instances.get(index);
+    }
+
+    /// Returns the instance of the handle for modification and marks the instances as dirty.
+    pub fn get_mut(&mut self, handle : Texture2DInstanceHandle) -> Option<&mut Texture2DInstance> {
+        let index = self.index_of(handle)?;
+        self.mark_range_dirty(index, index + 1);
+        return self.instances.get_mut(index);
+    }
+
+    /// Replaces the instance of the handle.
+    ///
+    /// # Returns
+    /// - False if the handle is invalid.
+    pub fn set(&mut self, handle : Texture2DInstanceHandle, instance : Texture2DInstance) -> bool {
+        match self.get_mut(handle) {
+            Some(target) => {
+                *target = instance;
+                return true;
+            }
+            None => {
+                return false;
+            }
+        }
+    }
+
+    /// Moves an instance to the given index within the draw order. The index is clamped to the last position.
+    ///
+    /// # Returns
+    /// - False if the handle is invalid.
+    pub fn move_to(&mut self, handle : Texture2DInstanceHandle, index : usize) -> bool {
+        let Some(current) = self.index_of(handle) else {
+            return false;
+        };
+        let index = index.min(self.instances.len() - 1);
+        if current < index {
+            self.instances[current ..= index].rotate_left(1);
+            self.instance_slots[current ..= index].rotate_left(1);
+        }
+        else if index < current {
+            self.instances[index ..= current].rotate_right(1);
+            self.instance_slots[index ..= current].rotate_right(1);
+        }
+        self.reindex(current.min(index), current.max(index) + 1);
+        return true;
+    }
+
+    /// Swaps the positions of two instances within the draw order.
+    ///
+    /// # Returns
+    /// - False if one of the handles is invalid.
+    pub fn swap(&mut self, a : Texture2DInstanceHandle, b : Texture2DInstanceHandle) -> bool {
+        let (Some(index_a), Some(index_b)) = (self.index_of(a), self.index_of(b)) else {
+            return false;
+        };
+        self.instances.swap(index_a, index_b);
+        self.instance_slots.swap(index_a, index_b);
+        self.slots[a.slot as usize].index = Some(index_b);
+        self.slots[b.slot as usize].index = Some(index_a);
+        self.mark_range_dirty(index_a, index_a + 1);
+        self.mark_range_dirty(index_b, index_b + 1);
+        return true;
+    }
+
+    /// Sorts the instances back to front by their layer. Instances within the same layer keep their order.
+    ///
+    /// # Returns
+    /// - True if the order changed.
+    pub fn sort_by_layer(&mut self) -> bool {
+        let Some(order) = self.layer_order() else {
+            return false;
+        };
+        self.instances = order.iter().map(|index| self.instances[*index]).collect();
+        self.instance_slots = order.iter().map(|index| self.instance_slots[*index]).collect();
+        self.reindex(0, self.instances.len());
+        return true;
+    }
+
+    /// Returns the indices of the instances sorted back to front by their layer, without changing the stored order.
+    ///
+    /// # Returns
+    /// - The indices in draw order, or `None` if the instances are already in layer order.
+    pub fn layer_order(&self) -> Option<Vec<usize>> {
+        if self.instances.windows(2).all(|pair| pair[0].layer <= pair[1].layer) {
+            return None;
+        }
+        let mut order : Vec<usize> = (0 .. self.instances.len()).collect();
+        order.sort_by(|a, b| self.instances[*a].layer.total_cmp(&self.instances[*b].layer));
+        return Some(order);
+    }
+
+    /// Returns the number of instances.
+    pub fn len(&self) -> usize {
+        return self.instances.len();
+    }
+
+    /// Returns true if the list contains no instances.
+    pub fn is_empty(&self) -> bool {
+        return self.instances.is_empty();
+    }
+
+    /// Returns the instances in draw order.
+    pub fn as_slice(&self) -> &[Texture2DInstance] {
+        return &self.instances;
+    }
+
+    /// Iterates over the handles and instances in draw order.
+    pub fn iter(&self) -> impl Iterator<Item = (Texture2DInstanceHandle, &Texture2DInstance)> {
+        return (0 .. self.instances.len()).map(|index| (self.handle_at(index).unwrap(), &self.instances[index]));
+    }
+
+    /// Returns the instances in draw order for modification and marks all of them as dirty.
+    pub fn as_mut_slice(&mut self) -> &mut [Texture2DInstance] {
+        self.mark_range_dirty(0, self.instances.len());
+        return &mut self.instances;
+    }
+
+    /// Replaces the instances starting at the given index with one bulk update.
+    ///
+    /// # Returns
+    /// - False if the range exceeds the number of instances.
+    pub fn set_range(&mut self, start : usize, instances : &[Texture2DInstance]) -> bool {
+        let end = start + instances.len();
+        if end > self.instances.len() {
+            return false;
+        }
+        self.instances[start .. end].copy_from_slice(instances);
+        self.mark_range_dirty(start, end);
+        return true;
+    }
+
+    /// Returns true if the instances changed since they were last uploaded.
+    pub fn is_dirty(&self) -> bool {
+        return self.dirty.is_some();
+    }
+
+    /// Returns the range of indices which changed since the last upload, limited to the current number of instances.
+    pub fn dirty_range(&self) -> Option<Range<usize>> {
+        let dirty = self.dirty.as_ref()?;
+        return Some(dirty.start.min(self.instances.len()) .. dirty.end.min(self.instances.len()));
+    }
+
+    pub(crate) fn mark_dirty(&mut self) {
+        self.mark_range_dirty(0, self.instances.len());
+    }
+
+    /// Returns the dirty range like `dirty_range` and marks the instances as uploaded.
+    pub fn take_dirty_range(&mut self) -> Option<Range<usize>> {
+        let dirty_range = self.dirty_range();
+        self.dirty = None;
+        return dirty_range;
+    }
+
+    /// Extends the dirty range by the given range.
+    fn mark_range_dirty(&mut self, start : usize, end : usize) {
+        self.dirty = match self.dirty.take() {
+            Some(dirty) => Some(dirty.start.min(start) .. dirty.end.max(end)),
+            None => Some(start .. end)
+        };
+    }
+
+    /// Updates the slots of the instances within the given index range.
+    fn reindex(&mut self, start : usize, end : usize) {
+        for index in start .. end {
+            self.slots[self.instance_slots[index] as usize].index = Some(index);
+        }
+        self.mark_range_dirty(start, end);
+    }
+
+    fn free_slot(&mut self, slot : u32) {
+        let entry = &mut self.slots[slot as usize];
+        entry.index = None;
+        entry.generation = entry.generation.wrapping_add(1);
+        self.free_slots.push(slot);
+    }
+}
+
+impl From<Vec<Texture2DInstance>> for Texture2DInstances {
+    fn from(instances : Vec<Texture2DInstance>) -> Self {
+        let mut list = Texture2DInstances::new();
+        for instance in instances {
+            list.insert(instance);
+        }
+        return list;
+    }
+}
+
 /// Represents a batch of 2D textures, which can be in either a preloaded or loaded state.
+///
+/// Instances can be added, removed and reordered in every state. A loaded batch stores its instances within a single
+/// interleaved instance buffer, which grows or shrinks when the changed instances are uploaded right before the batch is drawn.
 #[derive(Clone)]
 pub enum Texture2DBatch {
     /// Preloaded state, where texture instances are stored but no GPU buffers are allocated yet.
     PreLoad {
-        instances: Vec<Texture2DInstance>,
+        instances: Texture2DInstances,
     },
-    /// Loaded state, where GPU buffers are allocated for texture instances.
+    /// Loaded state, where the GPU buffer is allocated for texture instances.
     Loaded {
-        instances: Vec<Texture2DInstance>,
-        mbo: u32,
-        cbo: u32,
-        uvto: u32,
-        exbo: u32
+        instances: Texture2DInstances,
+        /// The buffer with the interleaved `Texture2DInstanceData`.
+        instance_buffer: u32,
+        /// The number of instances the instance buffer can hold.
+        capacity: usize,
+        /// True if the instance buffer holds the instances sorted by layer instead of their stored order.
+        sorted: bool
     },
     /// Disposed state
     Disposed {
-        instances: Vec<Texture2DInstance>
+        instances: Texture2DInstances
     }
 }
 
+impl Default for Texture2DBatch {
+    fn default() -> Self {
+        Texture2DBatch::new()
+    }
+}
 
 impl Texture2DBatch {
 
@@ -115,11 +545,11 @@ impl Texture2DBatch {
     /// - A new `Texture2DBatch` object in the preloaded state.
     pub fn new() -> Self {
         Texture2DBatch::PreLoad {
-            instances: Vec::new(),
+            instances: Texture2DInstances::new(),
         }
     }
 
-    /// Adds a new texture instance to the batch in the `PreLoad` state.
+    /// Adds a new texture instance to the batch.
     ///
     /// # Arguments
     /// - `transform`: The transformation matrix for the new instance.
@@ -127,45 +557,88 @@ impl Texture2DBatch {
     /// - `uv_transform`: The UV transformation vector for the new instance.
     ///
     /// # Returns
-    /// - The number of instances in the batch after adding, or -1 if the batch is not in the `PreLoad` state.
+    /// - The index of the new instance, or -1 if the batch is disposed.
+    ///
+    /// ### Notes:
+    /// - Indices change when instances are removed or reordered. Use `insert_instance` to get a stable handle.
     pub fn add_instance(&mut self, transform : Matrix4<f32>, color : Vector4<f32>, uv_transform : Vector4<f32>, visible : bool) -> i32 {
-        match self {
-            Texture2DBatch::PreLoad { instances } => {
-                instances.push(Texture2DInstance::new(transform, color, uv_transform, visible));
-                return instances.len() as i32 - 1;
+        match self.insert_instance(Texture2DInstance::new(transform, color, uv_transform, visible)) {
+            Some(_) => {
+                return self.instances().len() as i32 - 1;
             }
-            _ => {
+            None => {
                 return -1;
             }
         }
     }
 
-    /// Returns an instance from the Texture2DBatch at the given index
-    pub fn get_instance(&mut self, index : i32) -> Texture2DInstance {
+    /// Adds an instance to the end of the batch.
+    ///
+    /// # Returns
+    /// - The handle of the new instance, or `None` if the batch is disposed.
+    pub fn insert_instance(&mut self, instance : Texture2DInstance) -> Option<Texture2DInstanceHandle> {
         match self {
-            Texture2DBatch::PreLoad { instances } => {
-                return instances[index as usize];
-            }
-            Texture2DBatch::Loaded { instances, mbo:_, cbo:_, uvto:_, exbo:_ } => {
-                return instances[index as usize]; 
+            Texture2DBatch::Disposed { instances: _ } => {
+                return None;
             }
-            Texture2DBatch::Disposed { instances } => {
-                return instances[index as usize];
+            _ => {
+                return Some(self.instances_mut().insert(instance));
             }
         }
     }
 
+    /// Removes an instance from the batch. The last instance takes the place of the removed one,
+    /// use `instances_mut().remove_ordered` to keep the draw order.
+    ///
+    /// # Returns
+    /// - The removed instance, or `None` if the handle is invalid.
+    pub fn remove_instance(&mut self, handle : Texture2DInstanceHandle) -> Option<Texture2DInstance> {
+        return self.instances_mut().remove(handle);
+    }
+
+    /// Replaces the instance of the handle. A loaded batch uploads the change before it is drawn the next time.
+    ///
+    /// # Returns
+    /// - False if the handle is invalid.
+    pub fn set_instance(&mut self, handle : Texture2DInstanceHandle, instance : Texture2DInstance) -> bool {
+        return self.instances_mut().set(handle, instance);
+    }
+
+    /// Returns the instances of the batch.
+    pub fn instances(&self) -> &Texture2DInstances {
+        match self {
+            Texture2DBatch::PreLoad { instances } => instances,
+            Texture2DBatch::Loaded { instances, .. } => instances,
+            Texture2DBatch::Disposed { instances } => instances
+        }
+    }
+
+    /// Returns the instances of the batch for modification.
+    pub fn instances_mut(&mut self) -> &mut Texture2DInstances {
+        match self {
+            Texture2DBatch::PreLoad { instances } => instances,
+            Texture2DBatch::Loaded { instances, .. } => instances,
+            Texture2DBatch::Disposed { instances } => instances
+        }
+    }
+
+    /// Returns an instance from the Texture2DBatch at the given index
+    pub fn get_instance(&mut self, index : i32) -> Texture2DInstance {
+        return self.instances().as_slice()[index as usize];
+    }
+
     /// Generates GPU-ready buffers for transformations, colors, and UV transformations.
     ///
     /// # Arguments
-    /// - `instances`: A mutable reference to the vector of `Texture2DInstance` objects.
+    /// - `instances`: The `Texture2DInstance` objects in draw order.
     ///
     /// # Returns
     /// - A tuple of three `Vec<f32>` buffers:
     ///   - Transform buffer: Contains the transformation matrices.
     ///   - Color buffer: Contains the RGBA color values.
     ///   - UV transform buffer: Contains the UV transformation vectors.
-    pub fn create_buffers(instances: &mut Vec<Texture2DInstance>) -> (Vec<f32>, Vec<f32>, Vec<f32>, Vec<f32>) {
+    #[deprecated(note = "Loaded batches use one interleaved buffer, use `create_instance_buffer` instead")]
+    pub fn create_buffers(instances: &[Texture2DInstance]) -> (Vec<f32>, Vec<f32>, Vec<f32>, Vec<f32>) {
         let mut transform_buffer = Vec::new();
         let mut color_buffer = Vec::new();
         let mut uv_transform_buffer = Vec::new();
@@ -181,5 +654,9 @@ impl Texture2DBatch {
 
         (transform_buffer, color_buffer, uv_transform_buffer, extras_buffer)
     }
-}
 
+    /// Generates the interleaved instance data for the given instances.
+    pub fn create_instance_buffer(instances: &[Texture2DInstance]) -> Vec<Texture2DInstanceData> {
+        return instances.iter().map(Texture2DInstance::create_instance_data).collect();
+    }
+}
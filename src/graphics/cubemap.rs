@@ -0,0 +1,230 @@
+use std::f32::consts::PI;
+
+use nalgebra::Vector3;
+
+use crate::error::{GfxError, GfxResult};
+
+use super::image_texture::{ColorMode, ImageTexture};
+
+/// The faces of a cubemap in the order of `GL_TEXTURE_CUBE_MAP_POSITIVE_X` and the following targets.
+pub const CUBEMAP_FACES : [CubemapFace; 6] = [
+    CubemapFace::PositiveX,
+    CubemapFace::NegativeX,
+    CubemapFace::PositiveY,
+    CubemapFace::NegativeY,
+    CubemapFace::PositiveZ,
+    CubemapFace::NegativeZ
+];
+
+/// A face of a cubemap.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CubemapFace {
+    /// The right face.
+    PositiveX = gl::TEXTURE_CUBE_MAP_POSITIVE_X as isize,
+    /// The left face.
+    NegativeX = gl::TEXTURE_CUBE_MAP_NEGATIVE_X as isize,
+    /// The top face.
+    PositiveY = gl::TEXTURE_CUBE_MAP_POSITIVE_Y as isize,
+    /// The bottom face.
+    NegativeY = gl::TEXTURE_CUBE_MAP_NEGATIVE_Y as isize,
+    /// The front face.
+    PositiveZ = gl::TEXTURE_CUBE_MAP_POSITIVE_Z as isize,
+    /// The back face.
+    NegativeZ = gl::TEXTURE_CUBE_MAP_NEGATIVE_Z as isize
+}
+
+impl CubemapFace {
+
+    /// Returns the texture target of the face.
+    pub fn target(self) -> u32 {
+        return self as u32;
+    }
+
+    /// Returns the direction through a point of the face, following the cubemap conventions of OpenGL.
+    ///
+    /// # Arguments
+    /// - `s`: The horizontal position on the face between -1 (left) and 1 (right).
+    /// - `t`: The vertical position on the face between -1 (top row) and 1 (bottom row).
+    pub fn direction(self, s : f32, t : f32) -> Vector3<f32> {
+        match self {
+            CubemapFace::PositiveX => Vector3::new(1.0, -t, -s),
+            CubemapFace::NegativeX => Vector3::new(-1.0, -t, s),
+            CubemapFace::PositiveY => Vector3::new(s, 1.0, t),
+            CubemapFace::NegativeY => Vector3::new(s, -1.0, -t),
+            CubemapFace::PositiveZ => Vector3::new(s, -t, 1.0),
+            CubemapFace::NegativeZ => Vector3::new(-s, -t, -1.0)
+        }
+    }
+}
+
+/// A `GL_TEXTURE_CUBE_MAP` with six square faces, e.g. for a skybox, see `RenderDevice::draw_skybox`.
+///
+/// # Example
+/// ```ignore
+/// let mut sky = Cubemap::try_load_from_files(&["right.png", "left.png", "top.png", "bottom.png", "front.png", "back.png"])?;
+/// render_device.load_cubemap(&mut sky);
+///
+/// render_device.set_camera(&mut camera);
+/// //draw the meshes
+/// render_device.draw_skybox(&sky, Vector4::new(1.0, 1.0, 1.0, 1.0));
+/// ```
+#[derive(Clone)]
+pub enum Cubemap {
+    PreLoad {
+        /// The width and height of each face.
+        size: u32,
+        mode: ColorMode,
+        /// The data of the faces in the order of `CUBEMAP_FACES`.
+        faces: Vec<Vec<u8>>
+    },
+    Loaded {
+        id: u32,
+        size: u32
+    },
+    Disposed
+}
+
+impl Cubemap {
+
+    /// Creates a cubemap from six preloaded images in the order of `CUBEMAP_FACES`: right, left, top, bottom, front and back.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if there are not six images, an image is not preloaded or not square,
+    ///   or the images differ in size or color mode.
+    pub fn from_images(images : &[ImageTexture]) -> GfxResult<Cubemap> {
+        if images.len() != CUBEMAP_FACES.len() {
+            return Err(GfxError::InvalidState(format!("A cubemap requires 6 images, got {}.", images.len())));
+        }
+        let mut cubemap : Option<(u32, ColorMode)> = None;
+        let mut faces = Vec::with_capacity(images.len());
+        for image in images {
+            let ImageTexture::PreLoad { path: _, dimensions, data, mode } = image else {
+                return Err(GfxError::InvalidState(String::from("Only preloaded images can be added to a cubemap.")));
+            };
+            if dimensions.x != dimensions.y {
+                return Err(GfxError::InvalidState(format!("The faces of a cubemap must be square, got {}x{} pixels.", dimensions.x, dimensions.y)));
+            }
+            let (size, face_mode) = *cubemap.get_or_insert((dimensions.x, *mode));
+            if dimensions.x != size || *mode != face_mode {
+                return Err(GfxError::InvalidState(format!("The face with {}x{} pixels doesn't match the cubemap with {}x{} pixels and the same color mode.",
+                    dimensions.x, dimensions.y, size, size)));
+            }
+            faces.push(data.clone());
+        }
+        let (size, mode) = cubemap.unwrap_or_default();
+        return Ok(Cubemap::PreLoad { size, mode, faces });
+    }
+
+    /// Loads the six faces of a cubemap from image files, see `Cubemap::from_images` for the order.
+    ///
+    /// # Errors
+    /// - Returns the errors of `ImageTexture::try_load_from_file` and `Cubemap::from_images`.
+    pub fn try_load_from_files(files : &[&str]) -> GfxResult<Cubemap> {
+        let mut images = Vec::new();
+        for file in files {
+            //the faces of a cubemap start with the top row
+            images.push(ImageTexture::try_load_from_file(file, false)?);
+        }
+        return Cubemap::from_images(&images);
+    }
+
+    /// Projects a preloaded equirectangular (latitude-longitude) panorama onto the faces of a cubemap.
+    ///
+    /// The center of the panorama faces -Z, the forward direction of the cameras. The faces keep the color mode of
+    /// the panorama, so `.hdr` panoramas result in float cubemaps.
+    ///
+    /// # Arguments
+    /// - `image`: The panorama, usually twice as wide as high.
+    /// - `face_size`: The width and height of each face, e.g. a quarter of the panorama width.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the image is not preloaded or the face size is 0.
+    pub fn from_equirectangular(image : &ImageTexture, face_size : u32) -> GfxResult<Cubemap> {
+        let ImageTexture::PreLoad { path: _, dimensions, data, mode } = image else {
+            return Err(GfxError::InvalidState(String::from("Only preloaded images can be converted into a cubemap.")));
+        };
+        if face_size == 0 || dimensions.x == 0 || dimensions.y == 0 {
+            return Err(GfxError::InvalidState(String::from("A cubemap requires a face size and a panorama with at least one pixel.")));
+        }
+
+        let size = face_size as usize;
+        let mut faces = Vec::with_capacity(CUBEMAP_FACES.len());
+        for face in CUBEMAP_FACES {
+            let mut face_data = Vec::with_capacity(size * size * mode.bytes_per_pixel());
+            for y in 0 .. size {
+                for x in 0 .. size {
+                    let s = (x as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+                    let t = (y as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+                    let direction = face.direction(s, t).normalize();
+                    let u = 0.5 + direction.x.atan2(-direction.z) / (2.0 * PI);
+                    let v = 0.5 - direction.y.clamp(-1.0, 1.0).asin() / PI;
+                    let pixel = sample_bilinear(data, dimensions.x as usize, dimensions.y as usize, *mode, u, v);
+                    for value in pixel.iter().take(mode.channels()) {
+                        write_channel(&mut face_data, *mode, *value);
+                    }
+                }
+            }
+            faces.push(face_data);
+        }
+        return Ok(Cubemap::PreLoad { size: face_size, mode: *mode, faces });
+    }
+
+    /// Loads an equirectangular panorama, e.g. an `.hdr` file, and projects it onto a cubemap.
+    ///
+    /// # Errors
+    /// - Returns the errors of `ImageTexture::try_load_from_file` and `Cubemap::from_equirectangular`.
+    pub fn try_load_from_equirectangular_file(file : &str, face_size : u32) -> GfxResult<Cubemap> {
+        let image = ImageTexture::try_load_from_file(file, false)?;
+        return Cubemap::from_equirectangular(&image, face_size);
+    }
+
+    /// Returns the width and height of each face.
+    pub fn size(&self) -> u32 {
+        match self {
+            Cubemap::PreLoad { size, .. } => *size,
+            Cubemap::Loaded { size, .. } => *size,
+            Cubemap::Disposed => 0
+        }
+    }
+}
+
+/// Reads a channel of the texture data as float, 8 and 16 bit channels are normalized to [0, 1].
+fn read_channel(data : &[u8], mode : ColorMode, offset : usize) -> f32 {
+    let value = &data[offset .. offset + mode.bytes_per_channel()];
+    match value.len() {
+        2 => u16::from_ne_bytes([value[0], value[1]]) as f32 / 65535.0,
+        4 => f32::from_ne_bytes([value[0], value[1], value[2], value[3]]),
+        _ => value[0] as f32 / 255.0
+    }
+}
+
+/// Appends a channel in the format of the color mode.
+fn write_channel(data : &mut Vec<u8>, mode : ColorMode, value : f32) {
+    match mode.bytes_per_channel() {
+        2 => data.extend_from_slice(&((value.clamp(0.0, 1.0) * 65535.0).round() as u16).to_ne_bytes()),
+        4 => data.extend_from_slice(&value.to_ne_bytes()),
+        _ => data.push((value.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+}
+
+/// Samples the image at the given texture coordinate, wrapping horizontally and clamping vertically.
+fn sample_bilinear(data : &[u8], width : usize, height : usize, mode : ColorMode, u : f32, v : f32) -> [f32; 4] {
+    let x = u * width as f32 - 0.5;
+    let y = (v * height as f32 - 0.5).clamp(0.0, (height - 1) as f32);
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let (fx, fy) = (x - x0, y - y0);
+    let columns = [(x0 as isize).rem_euclid(width as isize) as usize, (x0 as isize + 1).rem_euclid(width as isize) as usize];
+    let rows = [y0 as usize, (y0 as usize + 1).min(height - 1)];
+
+    let mut pixel = [0.0; 4];
+    for (row, weight_y) in [(rows[0], 1.0 - fy), (rows[1], fy)] {
+        for (column, weight_x) in [(columns[0], 1.0 - fx), (columns[1], fx)] {
+            let offset = (row * width + column) * mode.bytes_per_pixel();
+            for (channel, value) in pixel.iter_mut().enumerate().take(mode.channels()) {
+                *value += read_channel(data, mode, offset + channel * mode.bytes_per_channel()) * weight_x * weight_y;
+            }
+        }
+    }
+    return pixel;
+}
@@ -16,7 +16,16 @@ pub struct Window {
 
 impl Window {
     pub fn new(width: u32, height: u32, title: &str, vsync: bool) -> Self {
+        return Self::with_samples(width, height, title, vsync, 0);
+    }
+
+    /// Creates a window whose default framebuffer has the given number of samples per pixel, e.g. 4 for 4x MSAA.
+    /// 0 disables multisampling. The driver may choose a different sample count, see `RenderDevice::get_samples`.
+    pub fn with_samples(width: u32, height: u32, title: &str, vsync: bool, samples: u32) -> Self {
         let mut glfw = glfw::init(glfw::fail_on_errors).unwrap();
+        if samples > 0 {
+            glfw.window_hint(glfw::WindowHint::Samples(Some(samples)));
+        }
         let (mut handle, events) = glfw
             .create_window(
                 width,
@@ -0,0 +1,102 @@
+use glfw::{Context, ContextCreationApi, WindowHint};
+use nalgebra::Vector2;
+
+use crate::error::{GfxError, GfxResult};
+
+use super::{
+    render_device::RenderDevice,
+    viewport::Viewport,
+};
+
+/// Represents the api which is used to create the headless OpenGL context.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HeadlessBackend {
+    /// The native context api of the platform (GLX, WGL, NSGL) with a hidden window.
+    Native,
+    /// An EGL context, e.g. for Mesa drivers.
+    Egl,
+    /// An OSMesa context which renders fully on the cpu (requires a glfw build with OSMesa support).
+    OsMesa
+}
+
+impl HeadlessBackend {
+    fn as_context_creation_api(self) -> ContextCreationApi {
+        match self {
+            HeadlessBackend::Native => ContextCreationApi::Native,
+            HeadlessBackend::Egl => ContextCreationApi::Egl,
+            HeadlessBackend::OsMesa => ContextCreationApi::OsMesa
+        }
+    }
+}
+
+/// An OpenGL context without a visible window.
+///
+/// The context is backed by a hidden glfw window, so it can be used on CI machines or for server side
+/// rendering jobs. Rendering should be done into a `RenderTarget`, since the default framebuffer of a
+/// hidden window is not guaranteed to be backed by pixels.
+///
+/// ### Notes:
+/// - On Linux glfw still needs a display connection for the `Native` and `Egl` backends. Use a virtual
+///   display like `xvfb-run` together with `LIBGL_ALWAYS_SOFTWARE=1` to render on Mesa llvmpipe.
+/// - Contexts created outside of glfw (e.g. EGL surfaceless) can use `RenderDevice::init_with_loader` instead.
+pub struct HeadlessContext {
+    pub glfw: glfw::Glfw,
+    pub handle : glfw::PWindow,
+    pub render_device: RenderDevice,
+    pub viewport : Viewport
+}
+
+impl HeadlessContext {
+
+    /// Creates a new headless context with the given size and backend.
+    ///
+    /// # Returns
+    /// - `Some(HeadlessContext)` with an initialized `RenderDevice`, or `None` if glfw or the
+    ///   context could not be created. The error is printed.
+    pub fn new(width : u32, height : u32, backend : HeadlessBackend) -> Option<HeadlessContext> {
+        match Self::try_new(width, height, backend) {
+            Ok(context) => {
+                return Some(context);
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                return None;
+            }
+        }
+    }
+
+    /// Creates a new headless context with the given size and backend.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::Unsupported` if glfw cannot be initialized or the backend cannot create the context.
+    pub fn try_new(width : u32, height : u32, backend : HeadlessBackend) -> GfxResult<HeadlessContext> {
+        let mut glfw = glfw::init(glfw::log_errors)
+            .map_err(|e| GfxError::Unsupported(format!("Unable to initialize glfw for the headless context: {:?}", e)))?;
+
+        glfw.window_hint(WindowHint::Visible(false));
+        glfw.window_hint(WindowHint::ContextCreationApi(backend.as_context_creation_api()));
+        let Some((mut handle, _events)) = glfw.create_window(width, height, "", glfw::WindowMode::Windowed) else {
+            return Err(GfxError::Unsupported(format!("Unable to create the headless context with the {:?} backend", backend)));
+        };
+
+        handle.make_current();
+        let mut render_device = RenderDevice::default();
+        render_device.init(&mut handle);
+        let viewport = Viewport {
+            size : Vector2::new(width, height)
+        };
+        render_device.set_viewport(viewport);
+
+        Ok(Self { glfw, handle, render_device, viewport })
+    }
+
+    /// Makes the OpenGL context of the headless context current on the calling thread.
+    pub fn make_current(&mut self) {
+        self.handle.make_current();
+    }
+
+    /// Returns the viewport of the headless context.
+    pub fn get_viewport(&self) -> Viewport {
+        self.viewport
+    }
+}
@@ -1,32 +1,194 @@
+use std::{ffi::{c_int, c_void, CString}, fs::File, io::{self, BufWriter}};
+
 use gl::types::GLint;
 use nalgebra::{Vector2, Vector4};
 use stb_image::image::{self, LoadResult};
 
-use crate::{math::Rect, utils};
+use crate::{error::{GfxError, GfxResult}, math::Rect, utils};
 
-#[derive(Clone, Copy)]
+/// The pixel format of the texture data.
+///
+/// The data of 16 bit and float modes is stored with native endianness. The `R` and `RG` modes are sampled
+/// as grayscale and grayscale with alpha, like the single and dual channel images they are loaded from.
+///
+/// The value of each mode is an OpenGL internal format: the unsized `GL_RGB` and `GL_RGBA` for the original
+/// 8 bit modes and the sized format of `ColorMode::internal_format` for all others.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
 pub enum ColorMode {
+    R = gl::R8 as isize,
+    RG = gl::RG8 as isize,
+    RGB = gl::RGB as isize,
+    #[default]
     RGBA = gl::RGBA as isize,
-    RGB = gl::RGB as isize
-}
-
-impl Default for ColorMode {
-    fn default() -> Self {
-        ColorMode::RGBA
-    }
+    R16 = gl::R16 as isize,
+    RG16 = gl::RG16 as isize,
+    RGB16 = gl::RGB16 as isize,
+    RGBA16 = gl::RGBA16 as isize,
+    R32F = gl::R32F as isize,
+    RG32F = gl::RG32F as isize,
+    RGB32F = gl::RGB32F as isize,
+    RGBA32F = gl::RGBA32F as isize,
+    /// RGB in the sRGB color space, which is converted to linear colors when sampled. Use it for albedo textures.
+    SRGB = gl::SRGB8 as isize,
+    /// RGBA in the sRGB color space, the alpha channel is linear.
+    SRGBA = gl::SRGB8_ALPHA8 as isize
 }
 
 impl ColorMode {
+    #[deprecated(note = "Use `internal_format` instead")]
     pub fn as_i32(self) -> i32 {
-        self as i32 
+        self as i32
     }
 
+    #[deprecated(note = "Use `internal_format` instead")]
     pub fn as_glint(self) -> GLint {
         self as GLint
     }
 
+    #[deprecated(note = "Use `pixel_format` instead")]
     pub fn as_u32(self) -> u32 {
-        self as u32
+        self.pixel_format()
+    }
+
+    /// Returns the mode for the given number of channels and bytes per channel, where 4 bytes are floats.
+    pub fn from_channels(channels : usize, bytes_per_channel : usize) -> Option<ColorMode> {
+        let mode = match (channels, bytes_per_channel) {
+            (1, 1) => ColorMode::R,
+            (2, 1) => ColorMode::RG,
+            (3, 1) => ColorMode::RGB,
+            (4, 1) => ColorMode::RGBA,
+            (1, 2) => ColorMode::R16,
+            (2, 2) => ColorMode::RG16,
+            (3, 2) => ColorMode::RGB16,
+            (4, 2) => ColorMode::RGBA16,
+            (1, 4) => ColorMode::R32F,
+            (2, 4) => ColorMode::RG32F,
+            (3, 4) => ColorMode::RGB32F,
+            (4, 4) => ColorMode::RGBA32F,
+            _ => return None
+        };
+        return Some(mode);
+    }
+
+    /// Returns the number of channels.
+    pub fn channels(self) -> usize {
+        match self {
+            ColorMode::R | ColorMode::R16 | ColorMode::R32F => 1,
+            ColorMode::RG | ColorMode::RG16 | ColorMode::RG32F => 2,
+            ColorMode::RGB | ColorMode::RGB16 | ColorMode::RGB32F | ColorMode::SRGB => 3,
+            ColorMode::RGBA | ColorMode::RGBA16 | ColorMode::RGBA32F | ColorMode::SRGBA => 4
+        }
+    }
+
+    /// Returns the number of bytes which are used for one channel.
+    pub fn bytes_per_channel(self) -> usize {
+        match self {
+            ColorMode::R16 | ColorMode::RG16 | ColorMode::RGB16 | ColorMode::RGBA16 => 2,
+            ColorMode::R32F | ColorMode::RG32F | ColorMode::RGB32F | ColorMode::RGBA32F => 4,
+            _ => 1
+        }
+    }
+
+    /// Returns the number of bytes which are used for one pixel.
+    pub fn bytes_per_pixel(self) -> usize {
+        return self.channels() * self.bytes_per_channel();
+    }
+
+    /// Returns true if the channels are floats which are not clamped to [0, 1], e.g. for HDR images.
+    pub fn is_float(self) -> bool {
+        return self.bytes_per_channel() == 4;
+    }
+
+    /// Returns true if the color channels are in the sRGB color space.
+    pub fn is_srgb(self) -> bool {
+        return matches!(self, ColorMode::SRGB | ColorMode::SRGBA);
+    }
+
+    /// Returns the mode with or without the sRGB color space.
+    ///
+    /// ### Notes:
+    /// - OpenGL only supports sRGB for 8 bit RGB and RGBA data, other modes are returned unchanged.
+    pub fn with_srgb(self, srgb : bool) -> ColorMode {
+        match (self, srgb) {
+            (ColorMode::RGB, true) => ColorMode::SRGB,
+            (ColorMode::RGBA, true) => ColorMode::SRGBA,
+            (ColorMode::SRGB, false) => ColorMode::RGB,
+            (ColorMode::SRGBA, false) => ColorMode::RGBA,
+            _ => self
+        }
+    }
+
+    /// Returns the internal format of the texture.
+    pub fn internal_format(self) -> u32 {
+        match self {
+            ColorMode::R => gl::R8,
+            ColorMode::RG => gl::RG8,
+            ColorMode::RGB => gl::RGB8,
+            ColorMode::RGBA => gl::RGBA8,
+            ColorMode::R16 => gl::R16,
+            ColorMode::RG16 => gl::RG16,
+            ColorMode::RGB16 => gl::RGB16,
+            ColorMode::RGBA16 => gl::RGBA16,
+            ColorMode::R32F => gl::R32F,
+            ColorMode::RG32F => gl::RG32F,
+            ColorMode::RGB32F => gl::RGB32F,
+            ColorMode::RGBA32F => gl::RGBA32F,
+            ColorMode::SRGB => gl::SRGB8,
+            ColorMode::SRGBA => gl::SRGB8_ALPHA8
+        }
+    }
+
+    /// Returns the color mode of a texture with the given OpenGL internal format, e.g. as reported by `GL_TEXTURE_INTERNAL_FORMAT`.
+    ///
+    /// # Returns
+    /// - The color mode, or `None` if the internal format is not one of `ColorMode::internal_format`.
+    pub fn from_internal_format(internal_format : u32) -> Option<ColorMode> {
+        let mode = match internal_format {
+            gl::R8 => ColorMode::R,
+            gl::RG8 => ColorMode::RG,
+            gl::RGB8 => ColorMode::RGB,
+            gl::RGBA8 => ColorMode::RGBA,
+            gl::R16 => ColorMode::R16,
+            gl::RG16 => ColorMode::RG16,
+            gl::RGB16 => ColorMode::RGB16,
+            gl::RGBA16 => ColorMode::RGBA16,
+            gl::R32F => ColorMode::R32F,
+            gl::RG32F => ColorMode::RG32F,
+            gl::RGB32F => ColorMode::RGB32F,
+            gl::RGBA32F => ColorMode::RGBA32F,
+            gl::SRGB8 => ColorMode::SRGB,
+            gl::SRGB8_ALPHA8 => ColorMode::SRGBA,
+            _ => return None
+        };
+        return Some(mode);
+    }
+
+    /// Returns the pixel format of the data.
+    pub fn pixel_format(self) -> u32 {
+        match self.channels() {
+            1 => gl::RED,
+            2 => gl::RG,
+            3 => gl::RGB,
+            _ => gl::RGBA
+        }
+    }
+
+    /// Returns the type of the channels within the data.
+    pub fn pixel_type(self) -> u32 {
+        match self.bytes_per_channel() {
+            2 => gl::UNSIGNED_SHORT,
+            4 => gl::FLOAT,
+            _ => gl::UNSIGNED_BYTE
+        }
+    }
+
+    /// Returns the swizzle which samples single and dual channel data as grayscale and grayscale with alpha.
+    pub fn swizzle(self) -> Option<[GLint; 4]> {
+        match self.channels() {
+            1 => Some([gl::RED as GLint, gl::RED as GLint, gl::RED as GLint, gl::ONE as GLint]),
+            2 => Some([gl::RED as GLint, gl::RED as GLint, gl::RED as GLint, gl::GREEN as GLint]),
+            _ => None
+        }
     }
 }
 
@@ -49,52 +211,180 @@ pub enum ImageTexture {
 impl ImageTexture {
     
     /// Loads an image texture from a file.
+    /// Prints the error and returns `ImageTexture::Corrupted` if the image cannot be loaded.
     pub fn load_from_file(file: &str, flip_vertically : bool) -> ImageTexture {
+        match ImageTexture::try_load_from_file(file, flip_vertically) {
+            Ok(image_texture) => {
+                return image_texture;
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                return ImageTexture::Corrupted;
+            }
+        }
+    }
+
+    /// Loads an image texture from a file.
+    ///
+    /// Single and dual channel images are loaded as `ColorMode::R` and `ColorMode::RG`, 16 bit pngs keep their
+    /// precision and `.hdr` files are loaded as floats.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::ImageLoad` if the image cannot be decoded.
+    /// - Returns `GfxError::Unsupported` if the image has an unsupported number of channels.
+    pub fn try_load_from_file(file: &str, flip_vertically : bool) -> GfxResult<ImageTexture> {
         unsafe {
             stb_image::stb_image::stbi_set_flip_vertically_on_load_thread(flip_vertically as i32);
         }
+        let Ok(c_file) = CString::new(file) else {
+            return Err(GfxError::ImageLoad { path: file.to_string(), reason: String::from("path contains null character") });
+        };
+        if unsafe { stb_image::stb_image::stbi_is_16_bit(c_file.as_ptr()) } != 0 {
+            return ImageTexture::try_load_16_bit(file, &c_file);
+        }
         let load_result = image::load(file);
 
-        match load_result {
+        let (width, height, channels, bytes_per_channel, data) = match load_result {
             LoadResult::Error(e) => {
-                println!("Error loading image: {}", e);
-                return ImageTexture::Corrupted;
+                return Err(GfxError::ImageLoad { path: file.to_string(), reason: e });
             }
-            LoadResult::ImageU8(e) => {
-                
-                let mode = match e.depth {
-                    3 => ColorMode::RGB,
-                    4 => ColorMode::RGBA,
-                    _ => {
-                        println!("Unsupported number of channels: {}", e.depth);
-                        ColorMode::default()
-                    }
-                };
-
-                return ImageTexture::PreLoad {
-                    path: file.to_string(),
-                    dimensions: Vector2::new(e.width as u32, e.height as u32),
-                    data: e.data,
-                    mode: mode
-                }
-            }
-            _ => {
-                println!("Unsupported image format.");
-                return  ImageTexture::Corrupted;
+            LoadResult::ImageU8(e) => (e.width, e.height, e.depth, 1, e.data),
+            LoadResult::ImageF32(e) => (e.width, e.height, e.depth, 4, e.data.iter().flat_map(|value| value.to_ne_bytes()).collect())
+        };
+        let Some(mode) = ColorMode::from_channels(channels, bytes_per_channel) else {
+            return Err(GfxError::Unsupported(format!("{} channels in the image {}", channels, file)));
+        };
+
+        return Ok(ImageTexture::PreLoad {
+            path: file.to_string(),
+            dimensions: Vector2::new(width as u32, height as u32),
+            data: data,
+            mode: mode
+        });
+    }
+
+    /// Loads an image with 16 bit per channel, the stb wrapper only loads 8 bit and float images.
+    fn try_load_16_bit(file : &str, c_file : &CString) -> GfxResult<ImageTexture> {
+        let mut width : c_int = 0;
+        let mut height : c_int = 0;
+        let mut channels : c_int = 0;
+        let data = unsafe {
+            let buffer = stb_image::stb_image::stbi_load_16(c_file.as_ptr(), &mut width, &mut height, &mut channels, 0);
+            if buffer.is_null() {
+                return Err(GfxError::ImageLoad { path: file.to_string(), reason: String::from("stbi_load_16 failed") });
             }
-        }
+            let values = std::slice::from_raw_parts(buffer, width as usize * height as usize * channels as usize);
+            let data : Vec<u8> = values.iter().flat_map(|value| value.to_ne_bytes()).collect();
+            stb_image::stb_image::stbi_image_free(buffer as *mut c_void);
+            data
+        };
+        let Some(mode) = ColorMode::from_channels(channels as usize, 2) else {
+            return Err(GfxError::Unsupported(format!("{} channels in the image {}", channels, file)));
+        };
+
+        return Ok(ImageTexture::PreLoad {
+            path: file.to_string(),
+            dimensions: Vector2::new(width as u32, height as u32),
+            data: data,
+            mode: mode
+        });
     }
 
     /// Loads an image texture from a vector of bytes and dimensions.
     pub fn load_from_data(data : Vec<u8>, dimensions : Vector2<u32>) -> ImageTexture {
-        return ImageTexture::PreLoad { 
-            path: String::new(), 
-            dimensions: dimensions, 
+        return ImageTexture::load_from_data_with_mode(data, dimensions, ColorMode::default());
+    }
+
+    /// Loads an image texture from a vector of bytes in the given color mode and dimensions.
+    ///
+    /// ### Notes:
+    /// - The size of the data is checked when the texture is loaded, use `try_load_from_data_with_mode` to check it right away.
+    pub fn load_from_data_with_mode(data : Vec<u8>, dimensions : Vector2<u32>, mode : ColorMode) -> ImageTexture {
+        return ImageTexture::PreLoad {
+            path: String::new(),
+            dimensions: dimensions,
             data: data,
-            mode: ColorMode::default()
+            mode: mode
         };
     }
 
+    /// Loads an image texture from a vector of bytes in the given color mode and dimensions.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the data doesn't contain `bytes_per_pixel` bytes for every pixel.
+    pub fn try_load_from_data_with_mode(data : Vec<u8>, dimensions : Vector2<u32>, mode : ColorMode) -> GfxResult<ImageTexture> {
+        let expected_size = dimensions.x as usize * dimensions.y as usize * mode.bytes_per_pixel();
+        if data.len() != expected_size {
+            return Err(GfxError::InvalidState(format!("The texture data contains {} bytes, expected {} bytes.", data.len(), expected_size)));
+        }
+        return Ok(ImageTexture::load_from_data_with_mode(data, dimensions, mode));
+    }
+
+    /// Marks the color channels of a preloaded texture as sRGB, e.g. for albedo textures. The colors are converted
+    /// to linear space when they are sampled, so lighting is calculated gamma-correct.
+    ///
+    /// ### Notes:
+    /// - Only 8 bit RGB and RGBA textures support sRGB, see `ColorMode::with_srgb`.
+    pub fn with_srgb(mut self, srgb : bool) -> ImageTexture {
+        if let ImageTexture::PreLoad { mode, .. } = &mut self {
+            *mode = mode.with_srgb(srgb);
+        }
+        return self;
+    }
+
+    /// Flips the rows of a preloaded texture, so the first row becomes the last one.
+    /// This is used to convert between the bottom-up row order of OpenGL and the top-down order of image files.
+    pub fn flip_vertically(&mut self) {
+        if let ImageTexture::PreLoad { path: _, dimensions, data, mode } = self {
+            let row_size = dimensions.x as usize * mode.bytes_per_pixel();
+            let rows = dimensions.y as usize;
+            for row in 0 .. rows / 2 {
+                let (top, bottom) = data.split_at_mut((rows - 1 - row) * row_size);
+                top[row * row_size .. (row + 1) * row_size].swap_with_slice(&mut bottom[.. row_size]);
+            }
+        }
+    }
+
+    /// Saves a preloaded texture as png file.
+    ///
+    /// # Errors
+    /// - Returns an error if the texture is not in the `PreLoad` state or the file cannot be written.
+    /// - Returns `GfxError::Unsupported` for float textures.
+    pub fn save_png(&self, file : &str) -> GfxResult<()> {
+        match self {
+            ImageTexture::PreLoad { path: _, dimensions, data, mode } => {
+                if mode.is_float() {
+                    return Err(GfxError::Unsupported(format!("saving float textures as png file {}", file)));
+                }
+                let io_error = |e : io::Error| GfxError::Io { path: file.to_string(), source: e };
+                let writer = BufWriter::new(File::create(file).map_err(io_error)?);
+                let mut encoder = png::Encoder::new(writer, dimensions.x, dimensions.y);
+                encoder.set_color(match mode.channels() {
+                    1 => png::ColorType::Grayscale,
+                    2 => png::ColorType::GrayscaleAlpha,
+                    3 => png::ColorType::Rgb,
+                    _ => png::ColorType::Rgba
+                });
+                if mode.bytes_per_channel() == 2 {
+                    encoder.set_depth(png::BitDepth::Sixteen);
+                    let mut writer = encoder.write_header().map_err(|e| io_error(e.into()))?;
+                    //png stores 16 bit values big endian
+                    let big_endian : Vec<u8> = data.chunks_exact(2).flat_map(|value| u16::from_ne_bytes([value[0], value[1]]).to_be_bytes()).collect();
+                    writer.write_image_data(&big_endian).map_err(|e| io_error(e.into()))?;
+                }
+                else {
+                    encoder.set_depth(png::BitDepth::Eight);
+                    let mut writer = encoder.write_header().map_err(|e| io_error(e.into()))?;
+                    writer.write_image_data(data).map_err(|e| io_error(e.into()))?;
+                }
+                return Ok(());
+            }
+            _ => {
+                return Err(GfxError::InvalidState(String::from("Only preloaded textures can be saved. Read the texture back from the gpu first.")));
+            }
+        }
+    }
+
     /// Calculates the UV transformation for the texture based on the given rectangle.
     pub fn calculate_uv_transform(&mut self, rect : Rect<f32>) -> Vector4<f32> {
         return utils::calculate_uv_transform_from_texture(self, rect);
@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+
+use nalgebra::Vector2;
+
+use crate::{error::{GfxError, GfxResult}, shader::{prebuild_shader::{post_process_shader_program, BloomCompositeShader, BloomThresholdShader, ChromaticAberrationShader, ColorGradingShader, CopyShader, FxaaShader, GaussianBlurShader, PrebuildShaderProgram, VignetteShader}, uniform::Uniform, Shader, ShaderProgram}};
+
+use super::{image_texture::ImageTexture, render_target::{RenderTarget, RenderTargetFormat}};
+
+/// A 3D lookup table which maps every input color to a graded color, see `PostProcessEffect::ColorGrading`.
+///
+/// The data is stored as RGB8 with red varying fastest, followed by green and blue.
+#[derive(Clone)]
+pub enum ColorLut {
+    PreLoad {
+        size: u32,
+        data: Vec<u8>
+    },
+    Loaded {
+        id: u32,
+        size: u32
+    },
+    Disposed
+}
+
+impl ColorLut {
+
+    /// Creates a lookup table which maps every color to itself. Useful as a starting point for generated tables.
+    pub fn identity(size : u32) -> ColorLut {
+        let mut data = Vec::with_capacity(size as usize * size as usize * size as usize * 3);
+        let max = size.saturating_sub(1).max(1) as f32;
+        for blue in 0 .. size {
+            for green in 0 .. size {
+                for red in 0 .. size {
+                    data.push((red as f32 / max * 255.0).round() as u8);
+                    data.push((green as f32 / max * 255.0).round() as u8);
+                    data.push((blue as f32 / max * 255.0).round() as u8);
+                }
+            }
+        }
+        return ColorLut::PreLoad { size, data };
+    }
+
+    /// Creates a lookup table from a preloaded image in the common strip layout: the image is `size * size` pixels wide
+    /// and `size` pixels high and contains one slice per blue value from left to right. Red increases to the right and green downwards
+    /// within each slice.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the image is not preloaded, has no 8 bit RGB data or has not the strip layout.
+    pub fn from_image(image : &ImageTexture) -> GfxResult<ColorLut> {
+        let ImageTexture::PreLoad { path: _, dimensions, data, mode } = image else {
+            return Err(GfxError::InvalidState(String::from("Only preloaded images can be converted into a color lut.")));
+        };
+        if mode.channels() < 3 || mode.bytes_per_channel() != 1 {
+            return Err(GfxError::InvalidState(format!("A color lut image requires 8 bit RGB or RGBA data, got {:?}.", mode)));
+        }
+        let size = dimensions.y;
+        if size == 0 || dimensions.x != size * size {
+            return Err(GfxError::InvalidState(format!("A color lut image requires a width of height * height pixels, got {}x{} pixels.", dimensions.x, dimensions.y)));
+        }
+
+        let size = size as usize;
+        let bytes_per_pixel = mode.bytes_per_pixel();
+        let mut lut_data = Vec::with_capacity(size * size * size * 3);
+        for blue in 0 .. size {
+            for green in 0 .. size {
+                for red in 0 .. size {
+                    let pixel = (green * size * size + blue * size + red) * bytes_per_pixel;
+                    lut_data.extend_from_slice(&data[pixel .. pixel + 3]);
+                }
+            }
+        }
+        return Ok(ColorLut::PreLoad { size: size as u32, data: lut_data });
+    }
+
+    /// Loads a lookup table from an image file in the strip layout, see `ColorLut::from_image`.
+    ///
+    /// # Errors
+    /// - Returns the errors of `ImageTexture::try_load_from_file` and `ColorLut::from_image`.
+    pub fn try_load_from_file(file : &str) -> GfxResult<ColorLut> {
+        let image = ImageTexture::try_load_from_file(file, false)?;
+        return ColorLut::from_image(&image);
+    }
+
+    /// Returns the number of entries along each axis.
+    pub fn size(&self) -> u32 {
+        match self {
+            ColorLut::PreLoad { size, .. } => *size,
+            ColorLut::Loaded { size, .. } => *size,
+            ColorLut::Disposed => 0
+        }
+    }
+}
+
+/// A post processing pass with a user-written fragment shader.
+///
+/// The fragment shader receives the texture coordinate `in vec2 texCoord`, the output of the previous effect as
+/// `uniform sampler2D textureSampler` and the size of one texel as `uniform vec2 texelSize`. The `GfxFrame` block
+/// can be included with `#include <gfx/frame.glsl>`, e.g. for animated effects.
+///
+/// # Example
+/// ```ignore
+/// let mut grayscale = CustomPostProcess::new(Shader::from_file("shaders/grayscale.frag")?);
+/// grayscale.set_uniform("amount", 0.8);
+/// chain.add_effect(PostProcessEffect::custom(grayscale));
+/// ```
+pub struct CustomPostProcess {
+    pub shader_program : ShaderProgram,
+    pub(crate) uniforms : Vec<(String, Box<dyn Uniform>)>,
+}
+
+impl CustomPostProcess {
+
+    /// Creates a pass from a fragment shader. The program is built when the chain is loaded or drawn.
+    pub fn new(fragment_shader : Shader) -> CustomPostProcess {
+        return CustomPostProcess {
+            shader_program: post_process_shader_program(fragment_shader),
+            uniforms: Vec::new()
+        };
+    }
+
+    /// Sets a uniform which is assigned whenever the pass is drawn. Replaces a value with the same name.
+    pub fn set_uniform<U : Uniform + 'static>(&mut self, name : &str, value : U) {
+        self.uniforms.retain(|(uniform_name, _)| uniform_name != name);
+        self.uniforms.push((String::from(name), Box::new(value)));
+    }
+}
+
+/// An effect of a `PostProcessChain`.
+pub enum PostProcessEffect {
+    /// Blurs the image with a separable gaussian kernel.
+    /// - `radius`: The blur radius in pixels.
+    GaussianBlur {
+        radius : f32
+    },
+    /// Adds a blurred glow around the bright parts of the image. Works best with float render targets.
+    /// - `threshold`: The brightness above which pixels glow, e.g. 1.0 for HDR or 0.8 for LDR images.
+    /// - `intensity`: The strength of the glow.
+    /// - `radius`: The blur radius of the glow in pixels of the half resolution bloom targets.
+    Bloom {
+        threshold : f32,
+        intensity : f32,
+        radius : f32
+    },
+    /// Smooths aliased edges with fast approximate anti-aliasing. Should be one of the last effects.
+    Fxaa,
+    /// Darkens the corners of the image.
+    /// - `intensity`: The darkening in the corners, between 0 and 1.
+    /// - `smoothness`: The part of the image the vignette fades over, between 0 and 1.
+    Vignette {
+        intensity : f32,
+        smoothness : f32
+    },
+    /// Maps the colors with a 3D lookup table. The table is loaded together with the chain.
+    /// - `intensity`: The blend between the original and the graded colors, between 0 and 1.
+    ColorGrading {
+        lut : ColorLut,
+        intensity : f32
+    },
+    /// Shifts the red and blue channels apart towards the edges of the image.
+    /// - `offset`: The shift in the corners in pixels.
+    ChromaticAberration {
+        offset : f32
+    },
+    /// A pass with a user-written fragment shader, see `PostProcessEffect::custom`.
+    Custom(Box<CustomPostProcess>)
+}
+
+impl PostProcessEffect {
+    /// Creates an effect from a custom pass.
+    pub fn custom(custom : CustomPostProcess) -> PostProcessEffect {
+        return PostProcessEffect::Custom(Box::new(custom));
+    }
+
+    /// Returns the built-in programs the effect requires.
+    pub(crate) fn passes(&self) -> &'static [PostProcessPass] {
+        match self {
+            PostProcessEffect::GaussianBlur { .. } => &[PostProcessPass::GaussianBlur],
+            PostProcessEffect::Bloom { .. } => &[PostProcessPass::BloomThreshold, PostProcessPass::GaussianBlur, PostProcessPass::BloomComposite],
+            PostProcessEffect::Fxaa => &[PostProcessPass::Fxaa],
+            PostProcessEffect::Vignette { .. } => &[PostProcessPass::Vignette],
+            PostProcessEffect::ColorGrading { .. } => &[PostProcessPass::ColorGrading],
+            PostProcessEffect::ChromaticAberration { .. } => &[PostProcessPass::ChromaticAberration],
+            PostProcessEffect::Custom(_) => &[]
+        }
+    }
+}
+
+/// The built-in programs of the post processing effects.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(crate) enum PostProcessPass {
+    Copy,
+    GaussianBlur,
+    BloomThreshold,
+    BloomComposite,
+    Fxaa,
+    Vignette,
+    ColorGrading,
+    ChromaticAberration
+}
+
+impl PostProcessPass {
+    pub(crate) fn build_shader_program(self) -> ShaderProgram {
+        match self {
+            PostProcessPass::Copy => CopyShader::build_shader_program(),
+            PostProcessPass::GaussianBlur => GaussianBlurShader::build_shader_program(),
+            PostProcessPass::BloomThreshold => BloomThresholdShader::build_shader_program(),
+            PostProcessPass::BloomComposite => BloomCompositeShader::build_shader_program(),
+            PostProcessPass::Fxaa => FxaaShader::build_shader_program(),
+            PostProcessPass::Vignette => VignetteShader::build_shader_program(),
+            PostProcessPass::ColorGrading => ColorGradingShader::build_shader_program(),
+            PostProcessPass::ChromaticAberration => ChromaticAberrationShader::build_shader_program()
+        }
+    }
+}
+
+/// Applies a list of effects to the content of a render target, see `RenderDevice::draw_post_process_chain`.
+///
+/// The effects ping-pong between two internal render targets, the last effect is drawn into the framebuffer which
+/// was bound before. The programs, render targets and lookup tables are created by `RenderDevice::load_post_process_chain`
+/// and for effects which are added later when the chain is drawn.
+///
+/// # Example
+/// ```ignore
+/// let mut chain = PostProcessChain::new(RenderTargetFormat::RGBA16F)
+///     .with_effect(PostProcessEffect::Bloom { threshold: 1.0, intensity: 0.8, radius: 8.0 })
+///     .with_effect(PostProcessEffect::Vignette { intensity: 0.4, smoothness: 0.6 })
+///     .with_effect(PostProcessEffect::Fxaa);
+/// render_device.load_post_process_chain(&mut chain, 1280, 720);
+///
+/// render_device.bind_render_target(scene_target);
+/// //draw the scene
+/// render_device.unbind_render_target();
+/// render_device.draw_post_process_chain(&mut chain, &scene_target);
+/// ```
+pub struct PostProcessChain {
+    pub effects : Vec<PostProcessEffect>,
+    pub(crate) format : RenderTargetFormat,
+    pub(crate) size : Vector2<u32>,
+    pub(crate) targets : [RenderTarget; 2],
+    /// The half resolution targets of the bloom effect, created on demand.
+    pub(crate) bloom_targets : [RenderTarget; 2],
+    pub(crate) programs : HashMap<PostProcessPass, ShaderProgram>,
+}
+
+impl PostProcessChain {
+
+    /// Creates an empty chain whose internal render targets use the given format.
+    pub fn new(format : RenderTargetFormat) -> PostProcessChain {
+        return PostProcessChain {
+            effects: Vec::new(),
+            format,
+            size: Vector2::new(0, 0),
+            targets: [RenderTarget::default(); 2],
+            bloom_targets: [RenderTarget::default(); 2],
+            programs: HashMap::new()
+        };
+    }
+
+    /// Returns the chain with the effect appended.
+    pub fn with_effect(mut self, effect : PostProcessEffect) -> PostProcessChain {
+        self.effects.push(effect);
+        return self;
+    }
+
+    /// Appends an effect to the chain.
+    ///
+    /// # Returns
+    /// - The index of the effect.
+    pub fn add_effect(&mut self, effect : PostProcessEffect) -> usize {
+        self.effects.push(effect);
+        return self.effects.len() - 1;
+    }
+
+    /// Returns the format of the internal render targets.
+    pub fn format(&self) -> RenderTargetFormat {
+        return self.format;
+    }
+
+    /// Returns the size of the internal render targets.
+    pub fn size(&self) -> Vector2<u32> {
+        return self.size;
+    }
+
+    /// Returns the id of a built-in program, 0 if it has not been built.
+    pub(crate) fn program_id(&self, pass : PostProcessPass) -> u32 {
+        match self.programs.get(&pass) {
+            Some(ShaderProgram::Builded { program_id, .. }) => *program_id,
+            _ => 0
+        }
+    }
+
+    /// Returns true if the internal render targets have been created.
+    pub fn is_loaded(&self) -> bool {
+        return self.targets[0].framebuffer_id != 0;
+    }
+}
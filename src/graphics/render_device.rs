@@ -1,10 +1,10 @@
-use std::{collections::HashMap, ffi::CString};
+use std::{collections::{HashMap, HashSet}, ffi::{c_void, CString}};
 use freetype::{face::LoadFlag, Library};
 use gl::types::*;
 use glfw::PWindow;
 use nalgebra::{Matrix4, Vector2, Vector4};
-use crate::{core::transform::{ITransform, Transform3D}, shader::ShaderProgram};
-use super::{camera::ICamera, font::{Character, Font}, image_texture::ImageTexture, material::Material, mesh::Mesh, render_target::RenderTarget, shapes::{FramebufferShape, RectShape, Shape, TextureShape}, viewport::Viewport, RenderData, TextAlignment, Texture2DBatch, Texture2DInstance};
+use crate::{core::transform::{ITransform, Transform3D}, error::{GfxError, GfxResult}, math::Rect, shader::{diagnostics, prebuild_shader::{PrebuildShaderProgram, SkyboxShader, SpriteBatchShader, Texture2DShader}, preprocessor::ShaderPreprocessor, reflection::{gl_type_name, ShaderReflection}, uniform::Uniform, Shader, ShaderDefines, ShaderProgram, ShaderStage, ShaderVariants}};
+use super::{camera::ICamera, cubemap::{Cubemap, CUBEMAP_FACES}, font::{Character, Font}, image_texture::{ColorMode, ImageTexture}, material::Material, mesh::Mesh, light::ILight, post_process::{ColorLut, PostProcessChain, PostProcessEffect, PostProcessPass}, render_state::{BlendMode, ColorWriteMask, CullMode, DepthFunction, MaskMode, RenderState}, render_target::{DepthAttachment, RenderTarget, RenderTargetDescriptor, RenderTargetFormat, MAX_COLOR_ATTACHMENTS}, sampler::{query_max_anisotropy, SamplerDescriptor, TextureWrap}, texture_array::TextureArray, shapes::{FramebufferShape, RectShape, Shape, SkyboxShape, TextureShape}, sprite_batcher::{SpriteBatchStats, SpriteBatcher, SpriteInstance}, uniform_buffer::{FrameUniforms, LightUniforms, UniformBuffer, FRAME_BLOCK_BINDING, FRAME_BLOCK_NAME, LIGHTS_BLOCK_BINDING, LIGHTS_BLOCK_NAME}, viewport::Viewport, RenderData, TextAlignment, Texture2DBatch, Texture2DInstance, Texture2DInstanceData, apply_layer};
 
 ///Represents texture slots for the render device
 pub enum TextureSlot {
@@ -42,14 +42,36 @@ pub enum TextureSlot {
     Texture31 = gl::TEXTURE31 as isize
 }
 
-
 #[derive(Default)]
 pub struct RenderDevice {
     viewport : Viewport,
     view_matrix : Matrix4<f32>,
     projection_matrix : Matrix4<f32>,
     render_shapes : HashMap<String, RenderData>,
-    shader_program: u32
+    shader_program: u32,
+    shader_preprocessor : ShaderPreprocessor,
+    uniform_locations : HashMap<u32, HashMap<String, i32>>,
+    shader_reflections : HashMap<u32, ShaderReflection>,
+    uniform_warnings : HashSet<(u32, String)>,
+    frame_uniforms : FrameUniforms,
+    frame_buffer : UniformBuffer,
+    frame_dirty : bool,
+    lights_buffer : UniformBuffer,
+    sprite_batcher : Option<SpriteBatcher>,
+    sprite_batch_programs : HashSet<u32>,
+    failed_program_bound : bool,
+    layer : f32,
+    render_state : RenderState,
+    render_state_stack : Vec<RenderState>,
+    clip_stack : Vec<Rect<i32>>,
+    render_target_size : Option<Vector2<u32>>,
+    mask_writing : bool,
+    mask_restore_alpha_cutoff : f32,
+    sprite_sorting : bool,
+    texture2d_batch_sorting : bool,
+    default_sampler : SamplerDescriptor,
+    max_anisotropy : f32,
+    skybox_program : Option<ShaderProgram>
 }
 
 impl RenderDevice {
@@ -59,13 +81,17 @@ impl RenderDevice {
     /// and initializes default shapes used for rendering.
     pub fn init(&mut self, window: &mut PWindow) {
         //initial opengl with the glfw window
-        gl::load_with(|s| window.get_proc_address(s) as *const _);
-        unsafe {
-            gl::Enable(gl::DEPTH_TEST);
-            gl::DepthFunc(gl::LESS);
-            gl::Enable(gl::BLEND);
-            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
-        }
+        self.init_with_loader(|s| window.get_proc_address(s) as *const _);
+    }
+
+    /// Initializes the render device with a custom OpenGL function loader.
+    /// Use this for contexts which are not created through a glfw window, e.g. an EGL surfaceless
+    /// or OSMesa context created by the host application. The context must be current on the calling thread.
+    pub fn init_with_loader<F>(&mut self, loader : F) where F : FnMut(&'static str) -> *const c_void {
+        gl::load_with(loader);
+        self.max_anisotropy = query_max_anisotropy();
+        self.render_state = RenderState::default();
+        self.apply_render_state();
 
         //initial the render_shapes
         let framebuffer_shape = self.init_shape(FramebufferShape);
@@ -76,6 +102,315 @@ impl RenderDevice {
         self.render_shapes.insert(String::from("texture_batch_shape"), texture_batch_shape);
         let rect_shape = self.init_shape(RectShape);
         self.render_shapes.insert(String::from("rect_shape"), rect_shape);
+        let skybox_shape = self.init_shape(SkyboxShape);
+        self.render_shapes.insert(String::from("skybox_shape"), skybox_shape);
+
+        //initial the shared uniform blocks
+        self.frame_buffer = self.create_uniform_buffer(FRAME_BLOCK_BINDING, std::mem::size_of::<FrameUniforms>());
+        self.frame_dirty = true;
+        self.lights_buffer = self.create_uniform_buffer(LIGHTS_BLOCK_BINDING, std::mem::size_of::<LightUniforms>());
+        let lights_buffer = self.lights_buffer;
+        self.update_uniform_buffer(&lights_buffer, 0, &[LightUniforms::default()]);
+    }
+
+    /// Starts a new frame and updates the time values of the `GfxFrame` uniform block.
+    /// Call this once per frame, before the first draw call.
+    ///
+    /// # Arguments
+    /// - `time`: The time since the start of the application in seconds.
+    /// - `delta_time`: The time since the last frame in seconds.
+    pub fn begin_frame(&mut self, time : f32, delta_time : f32) {
+        self.flush_sprite_batch();
+        let frame_index = self.frame_uniforms.time.z + 1.0;
+        self.frame_uniforms.time = Vector4::new(time, delta_time, frame_index, 0.0);
+        self.frame_dirty = true;
+        if let Some(sprite_batcher) = &mut self.sprite_batcher {
+            sprite_batcher.stats = SpriteBatchStats::default();
+        }
+    }
+
+    /// Ends the current frame and draws all pending batched sprites.
+    /// Call this once per frame, before swapping the buffers.
+    pub fn end_frame(&mut self) {
+        self.flush_sprite_batch();
+    }
+
+    /// Enables the automatic batching of immediate-mode texture draws.
+    /// Errors are printed and batching stays disabled.
+    pub fn enable_sprite_batching(&mut self) {
+        if let Err(e) = self.try_enable_sprite_batching() {
+            eprintln!("Unable to enable sprite batching: {}", e);
+        }
+    }
+
+    /// Enables the automatic batching of immediate-mode texture draws.
+    ///
+    /// While batching is enabled, `draw_texture2d`, `draw_sub_texture2d`, `draw_texture2d_uvt`, `draw_texture2di` and
+    /// `draw_texture2drt` don't draw immediately. The sprites are collected into a streaming instance buffer and drawn
+    /// with one instanced draw call, when the texture changes, another shader is bound, a state like the camera, viewport
+    /// or render target changes, a non batched draw call is issued or the frame ends with `end_frame`.
+    ///
+    /// ### Notes:
+    /// - Batched sprites are drawn with the built-in `SpriteBatchShader`, which matches `Texture2DShader`. Sprites are
+    ///   only batched while no program or a `Texture2DShader` is bound, with any other program, including one which failed
+    ///   to build, they are drawn immediately.
+    ///
+    /// # Errors
+    /// - Returns an error if the batch shader cannot be built.
+    pub fn try_enable_sprite_batching(&mut self) -> GfxResult<()> {
+        if self.sprite_batcher.is_some() {
+            return Ok(());
+        }
+
+        let mut shader_program = SpriteBatchShader::build_shader_program();
+        self.try_build_shader_program(&mut shader_program)?;
+        let ShaderProgram::Builded { program_id, .. } = &shader_program else {
+            return Err(GfxError::InvalidState(String::from("The sprite batch shader is not built.")));
+        };
+        let program_id = *program_id;
+        let sampler_location = self.get_uniform_location(program_id, "textureSampler");
+        let render_data = self.init_shape(TextureShape);
+
+        let mut instance_buffer = 0;
+        unsafe {
+            //the sampler never changes
+            gl::UseProgram(program_id);
+            gl::Uniform1i(sampler_location, 0);
+            gl::UseProgram(self.shader_program);
+
+            gl::GenBuffers(1, &mut instance_buffer);
+            gl::BindVertexArray(render_data.vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, instance_buffer);
+            enable_instance_attributes(std::mem::size_of::<SpriteInstance>(), 0);
+            gl::BindVertexArray(0);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+
+        let mut sprite_batcher = SpriteBatcher::new(shader_program, render_data, instance_buffer);
+        sprite_batcher.sorting = self.sprite_sorting;
+        self.sprite_batcher = Some(sprite_batcher);
+        return Ok(());
+    }
+
+    /// Draws the pending sprites and disables the automatic batching of texture draws.
+    pub fn disable_sprite_batching(&mut self) {
+        self.flush_sprite_batch();
+        if let Some(mut sprite_batcher) = self.sprite_batcher.take() {
+            unsafe {
+                gl::DeleteBuffers(1, &sprite_batcher.instance_buffer);
+            }
+            self.dispose_render_data(&mut sprite_batcher.render_data);
+            self.dispose_shader_program(&mut sprite_batcher.shader_program);
+        }
+    }
+
+    /// Returns true if immediate-mode texture draws are batched.
+    pub fn is_sprite_batching_enabled(&self) -> bool {
+        return self.sprite_batcher.is_some();
+    }
+
+    /// Returns the number of batched sprites and draw calls since the last `begin_frame`.
+    pub fn get_sprite_batch_stats(&self) -> SpriteBatchStats {
+        return self.sprite_batcher.as_ref().map(|sprite_batcher| sprite_batcher.stats).unwrap_or_default();
+    }
+
+    /// Enables the back to front sorting of batched sprites by their layer. Takes effect while sprite batching is enabled,
+    /// see `enable_sprite_batching`.
+    ///
+    /// While sorting is enabled, sprites with different textures are collected together until the batch is flushed.
+    /// Sprites within the same layer keep the order they were submitted in, which is what translucent sprites require.
+    pub fn enable_sprite_sorting(&mut self) {
+        self.sprite_sorting = true;
+        if let Some(sprite_batcher) = &mut self.sprite_batcher {
+            sprite_batcher.sorting = true;
+        }
+    }
+
+    /// Disables the sorting of batched sprites.
+    pub fn disable_sprite_sorting(&mut self) {
+        self.flush_sprite_batch();
+        self.sprite_sorting = false;
+        if let Some(sprite_batcher) = &mut self.sprite_batcher {
+            sprite_batcher.sorting = false;
+        }
+    }
+
+    /// Returns true if batched sprites are sorted by their layer.
+    pub fn is_sprite_sorting_enabled(&self) -> bool {
+        return self.sprite_sorting;
+    }
+
+    /// Enables the back to front drawing of `Texture2DBatch` instances by their layer.
+    ///
+    /// ### Notes:
+    /// - Only the instance buffer is sorted, the order of the instances within the batch and their indices stay unchanged.
+    ///   Use `Texture2DInstances::sort_by_layer` to reorder the instances themselves.
+    pub fn enable_texture2d_batch_sorting(&mut self) {
+        self.texture2d_batch_sorting = true;
+    }
+
+    /// Disables the sorting of `Texture2DBatch` instances, they are drawn in their stored order.
+    pub fn disable_texture2d_batch_sorting(&mut self) {
+        self.texture2d_batch_sorting = false;
+    }
+
+    /// Returns true if `Texture2DBatch` instances are drawn back to front by their layer.
+    pub fn is_texture2d_batch_sorting_enabled(&self) -> bool {
+        return self.texture2d_batch_sorting;
+    }
+
+    /// Draws all pending batched sprites with one instanced draw call per texture.
+    /// Called automatically whenever the batch has to be drawn to keep the draw order.
+    pub fn flush_sprite_batch(&mut self) {
+        let pending = self.sprite_batcher.as_ref().is_some_and(|sprite_batcher| !sprite_batcher.instances.is_empty());
+        if !pending {
+            return;
+        }
+        self.upload_frame_uniforms();
+
+        let bound_program = self.shader_program;
+        let Some(sprite_batcher) = &mut self.sprite_batcher else {
+            return;
+        };
+        let ShaderProgram::Builded { program_id, .. } = &sprite_batcher.shader_program else {
+            sprite_batcher.clear();
+            return;
+        };
+        let program_id = *program_id;
+
+        sprite_batcher.sort();
+        let stride = std::mem::size_of::<SpriteInstance>();
+        let count = sprite_batcher.instances.len();
+        let runs = sprite_batcher.texture_runs();
+        unsafe {
+            gl::UseProgram(program_id);
+            gl::BindBuffer(gl::ARRAY_BUFFER, sprite_batcher.instance_buffer);
+            //orphan the buffer, so the driver doesn't have to wait for the previous flush
+            sprite_batcher.capacity = sprite_batcher.capacity.max(count.next_power_of_two());
+            gl::BufferData(gl::ARRAY_BUFFER, (sprite_batcher.capacity * stride) as GLsizeiptr, std::ptr::null(), gl::STREAM_DRAW);
+            gl::BufferSubData(gl::ARRAY_BUFFER, 0, (count * stride) as GLsizeiptr, sprite_batcher.instances.as_ptr() as *const c_void);
+
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindVertexArray(sprite_batcher.render_data.vao);
+            for (texture_id, range) in &runs {
+                //point the instance attributes to the first sprite of the run
+                enable_instance_attributes(stride, range.start * stride);
+                gl::BindTexture(gl::TEXTURE_2D, *texture_id);
+                gl::DrawElementsInstanced(gl::TRIANGLES, sprite_batcher.render_data.index_count as i32, gl::UNSIGNED_INT, std::ptr::null(), range.len() as i32);
+            }
+            gl::BindVertexArray(0);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::UseProgram(bound_program);
+        }
+        sprite_batcher.stats.draw_calls += runs.len();
+        sprite_batcher.stats.sprites += count;
+        sprite_batcher.clear();
+    }
+
+    /// Returns the current values of the `GfxFrame` uniform block.
+    pub fn get_frame_uniforms(&self) -> FrameUniforms {
+        return self.frame_uniforms;
+    }
+
+    /// Uploads the `GfxFrame` uniform block if one of its values has changed since the last upload.
+    /// Called by the draw functions of the device, custom draw code should call it before issuing draw calls.
+    pub fn upload_frame_uniforms(&mut self) {
+        if !self.frame_dirty || self.frame_buffer.id == 0 {
+            return;
+        }
+        self.frame_uniforms.projection_matrix = self.projection_matrix;
+        self.frame_uniforms.view_matrix = self.view_matrix;
+        let frame_buffer = self.frame_buffer;
+        self.update_uniform_buffer(&frame_buffer, 0, &[self.frame_uniforms]);
+        self.frame_dirty = false;
+    }
+
+    /// Assigns the camera matrices to the `p_mat` and `v_mat` uniforms of the bound program, if it doesn't declare
+    /// the `GfxFrame` uniform block. Keeps custom shaders working which were written before the frame block existed.
+    fn set_legacy_camera_uniforms(&mut self) {
+        let has_frame_block = self.shader_reflections.get(&self.shader_program)
+            .is_some_and(|reflection| reflection.get_uniform_block(FRAME_BLOCK_NAME).is_some());
+        if has_frame_block {
+            return;
+        }
+        self.set_uniform_internal("p_mat", self.projection_matrix);
+        self.set_uniform_internal("v_mat", self.view_matrix);
+    }
+
+    /// Updates the `GfxLights` uniform block with the given lights.
+    /// Only the first `MAX_LIGHTS` lights are used.
+    pub fn set_lights<T : ILight>(&mut self, lights : &mut [T]) {
+        let uniforms = LightUniforms::from_lights(lights);
+        let lights_buffer = self.lights_buffer;
+        self.update_uniform_buffer(&lights_buffer, 0, &[uniforms]);
+    }
+
+    /// Creates a uniform buffer with the given size and binds it to the binding point.
+    ///
+    /// ### Notes:
+    /// - The binding points `FRAME_BLOCK_BINDING` and `LIGHTS_BLOCK_BINDING` are reserved by the render device.
+    pub fn create_uniform_buffer(&mut self, binding : u32, size : usize) -> UniformBuffer {
+        let mut id = 0;
+        unsafe {
+            gl::GenBuffers(1, &mut id);
+            gl::BindBuffer(gl::UNIFORM_BUFFER, id);
+            gl::BufferData(gl::UNIFORM_BUFFER, size as GLsizeiptr, std::ptr::null(), gl::DYNAMIC_DRAW);
+            gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
+            gl::BindBufferBase(gl::UNIFORM_BUFFER, binding, id);
+        }
+        return UniformBuffer { id, binding, size };
+    }
+
+    /// Writes the data into the uniform buffer, starting at the byte offset.
+    /// The data must match the std140 layout of the uniform block.
+    pub fn update_uniform_buffer<T>(&mut self, uniform_buffer : &UniformBuffer, offset : usize, data : &[T]) {
+        let size = std::mem::size_of_val(data);
+        if offset + size > uniform_buffer.size {
+            eprintln!("Unable to update the uniform buffer {}: {} bytes at offset {} exceed the size of {} bytes", uniform_buffer.id, size, offset, uniform_buffer.size);
+            return;
+        }
+        unsafe {
+            gl::BindBuffer(gl::UNIFORM_BUFFER, uniform_buffer.id);
+            gl::BufferSubData(gl::UNIFORM_BUFFER, offset as GLintptr, size as GLsizeiptr, data.as_ptr() as *const c_void);
+            gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
+        }
+    }
+
+    /// Binds the uniform buffer to its binding point again, e.g. after the binding point was used by other code.
+    pub fn bind_uniform_buffer(&mut self, uniform_buffer : &UniformBuffer) {
+        unsafe {
+            gl::BindBufferBase(gl::UNIFORM_BUFFER, uniform_buffer.binding, uniform_buffer.id);
+        }
+    }
+
+    /// Assigns a uniform block of a built program to a binding point.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the program is not built.
+    /// - Returns `GfxError::MissingResource` if the program has no active block with the name.
+    pub fn bind_uniform_block(&mut self, shader_program : &mut ShaderProgram, block_name : &str, binding : u32) -> GfxResult<()> {
+        let ShaderProgram::Builded { program_id, reflection, .. } = shader_program else {
+            return Err(GfxError::InvalidState(String::from("Uniform blocks can only be bound for built shader programs.")));
+        };
+        let Some(block) = reflection.uniform_blocks.iter_mut().find(|block| block.name == block_name) else {
+            return Err(GfxError::MissingResource(format!("uniform block {} of the shader program {}", block_name, program_id)));
+        };
+        unsafe {
+            gl::UniformBlockBinding(*program_id, block.index, binding);
+        }
+        block.binding = binding;
+        if let Some(device_reflection) = self.shader_reflections.get_mut(program_id) {
+            *device_reflection = reflection.clone();
+        }
+        return Ok(());
+    }
+
+    /// Disposes of a uniform buffer by deleting its OpenGL buffer.
+    pub fn dispose_uniform_buffer(&mut self, uniform_buffer : &mut UniformBuffer) {
+        unsafe {
+            gl::DeleteBuffers(1, &uniform_buffer.id);
+        }
+        *uniform_buffer = UniformBuffer::default();
     }
 
     /// Sets the clear color for the OpenGL context.
@@ -85,242 +420,959 @@ impl RenderDevice {
         }
     }
 
-    /// Clears the screen using the current clear color and clears the depth buffer.
+    /// Clears the screen using the current clear color and clears the depth and the stencil buffer.
+    /// The color write mask and depth writes of the render state don't affect the clear, the clip rectangle does.
     pub fn clear(&mut self) {
+        self.flush_sprite_batch();
         unsafe {
-            
-            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+            //the masks apply to clears as well
+            gl::ColorMask(gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE);
+            gl::DepthMask(gl::TRUE);
+            gl::StencilMask(0xFF);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+            if !self.mask_writing {
+                gl::StencilMask(0x00);
+            }
+        }
+        self.apply_render_state();
+    }
+
+    /// Disables depth testing in OpenGL.
+    pub fn disable_depth_test(&mut self) {
+        self.set_render_state(self.render_state.with_depth_test(false));
+    }
+
+    /// Enables depth testing in OpenGL.
+    pub fn enable_depth_test(&mut self) {
+        self.set_render_state(self.render_state.with_depth_test(true));
+    }
+
+    /// Sets the comparison of the depth test. The default is `DepthFunction::Less`.
+    ///
+    /// ### Notes:
+    /// - With `Less`, a 2D draw is hidden by an earlier draw at the same layer.
+    ///   Use `LessEqual` to draw sprites of the same layer in the order they are submitted.
+    pub fn set_depth_function(&mut self, depth_function : DepthFunction) {
+        self.set_render_state(self.render_state.with_depth_function(depth_function));
+    }
+
+    /// Sets the blend mode of the following draws.
+    pub fn set_blend_mode(&mut self, blend_mode : BlendMode) {
+        self.set_render_state(self.render_state.with_blend_mode(blend_mode));
+    }
+
+    /// Returns the current render state.
+    pub fn get_render_state(&self) -> RenderState {
+        return self.render_state;
+    }
+
+    /// Replaces the current render state. Pending batched sprites are drawn with the previous state first.
+    pub fn set_render_state(&mut self, render_state : RenderState) {
+        if render_state == self.render_state {
+            return;
+        }
+        self.flush_sprite_batch();
+        self.render_state = render_state;
+        self.apply_render_state();
+    }
+
+    /// Applies the current render state. While a mask is written, color and depth writes stay disabled.
+    fn apply_render_state(&self) {
+        if self.mask_writing {
+            self.render_state.with_color_write_mask(ColorWriteMask::NONE).with_depth_write(false).apply();
+        }
+        else {
+            self.render_state.apply();
+        }
+    }
+
+    /// Sets the alpha below which the prebuild shaders discard fragments. 0.0 disables the alpha test.
+    pub fn set_alpha_cutoff(&mut self, alpha_cutoff : f32) {
+        if self.frame_uniforms.render_params.x == alpha_cutoff {
+            return;
+        }
+        self.flush_sprite_batch();
+        self.frame_uniforms.render_params.x = alpha_cutoff;
+        self.frame_dirty = true;
+    }
+
+    /// Returns the alpha below which the prebuild shaders discard fragments.
+    pub fn get_alpha_cutoff(&self) -> f32 {
+        return self.frame_uniforms.render_params.x;
+    }
+
+    /// Starts writing a new stencil mask. The following draws only write their shape into the stencil buffer,
+    /// until `end_mask` is called. Any draw can be used as mask, e.g. sprites, rects or text.
+    ///
+    /// # Arguments
+    /// - `alpha_cutoff`: Fragments with a lower alpha don't become part of the mask, e.g. 0.5 for the transparent corners of a round sprite.
+    ///
+    /// ### Notes:
+    /// - The stencil buffer is cleared within the current clip rectangle.
+    /// - Render targets have a stencil buffer, the default framebuffer requires a window with stencil bits.
+    pub fn begin_mask(&mut self, alpha_cutoff : f32) {
+        self.flush_sprite_batch();
+        if !self.mask_writing {
+            self.mask_restore_alpha_cutoff = self.get_alpha_cutoff();
+        }
+        self.mask_writing = true;
+        unsafe {
+            gl::Enable(gl::STENCIL_TEST);
+            gl::StencilMask(0xFF);
+            gl::ClearStencil(0);
+            gl::Clear(gl::STENCIL_BUFFER_BIT);
+            gl::StencilFunc(gl::ALWAYS, 1, 0xFF);
+            //the mask is written even where the depth test fails
+            gl::StencilOp(gl::KEEP, gl::REPLACE, gl::REPLACE);
+        }
+        self.apply_render_state();
+        self.set_alpha_cutoff(alpha_cutoff);
+    }
+
+    /// Stops writing the mask and clips the following draws to the inside or the outside of the mask.
+    pub fn end_mask(&mut self, mask_mode : MaskMode) {
+        self.flush_sprite_batch();
+        if self.mask_writing {
+            self.mask_writing = false;
+            self.apply_render_state();
+            self.set_alpha_cutoff(self.mask_restore_alpha_cutoff);
+        }
+        self.set_mask_mode(mask_mode);
+    }
+
+    /// Switches between drawing inside and outside of the current mask.
+    pub fn set_mask_mode(&mut self, mask_mode : MaskMode) {
+        self.flush_sprite_batch();
+        unsafe {
+            gl::Enable(gl::STENCIL_TEST);
+            gl::StencilMask(0x00);
+            gl::StencilOp(gl::KEEP, gl::KEEP, gl::KEEP);
+            match mask_mode {
+                MaskMode::Inside => {
+                    gl::StencilFunc(gl::EQUAL, 1, 0xFF);
+                }
+                MaskMode::Outside => {
+                    gl::StencilFunc(gl::NOTEQUAL, 1, 0xFF);
+                }
+            }
+        }
+    }
+
+    /// Disables the mask, so the following draws are no longer clipped.
+    pub fn disable_mask(&mut self) {
+        self.flush_sprite_batch();
+        if self.mask_writing {
+            self.mask_writing = false;
+            self.apply_render_state();
+            self.set_alpha_cutoff(self.mask_restore_alpha_cutoff);
+        }
+        unsafe {
+            gl::Disable(gl::STENCIL_TEST);
+        }
+    }
+
+    /// Saves the current render state on the stack and applies the given state.
+    /// Restore the previous state with `pop_render_state`.
+    pub fn push_render_state(&mut self, render_state : RenderState) {
+        self.render_state_stack.push(self.render_state);
+        self.set_render_state(render_state);
+    }
+
+    /// Restores the render state which was active before the last `push_render_state`.
+    ///
+    /// ### Notes:
+    /// - Prints an error and keeps the current state if the stack is empty.
+    pub fn pop_render_state(&mut self) {
+        match self.render_state_stack.pop() {
+            Some(render_state) => {
+                self.set_render_state(render_state);
+            }
+            None => {
+                eprintln!("pop_render_state called without a matching push_render_state");
+            }
+        }
+    }
+
+    /// Restricts the following draws and clears to the given rectangle, intersected with the current clip rectangle.
+    /// Restore the previous clip rectangle with `pop_clip_rect`.
+    ///
+    /// # Arguments
+    /// - `rect`: The rectangle in pixels, with the origin in the top left corner of the bound render target or the viewport.
+    pub fn push_clip_rect(&mut self, rect : Rect<f32>) {
+        self.flush_sprite_batch();
+        let x = rect.x.floor() as i32;
+        let y = rect.y.floor() as i32;
+        let mut clip_rect = Rect {
+            x,
+            y,
+            widht: (rect.x + rect.widht).ceil() as i32 - x,
+            height: (rect.y + rect.height).ceil() as i32 - y
+        };
+        if let Some(parent) = self.clip_stack.last() {
+            clip_rect = clip_rect.intersection(parent);
+        }
+        self.clip_stack.push(clip_rect);
+        self.apply_clip_rect();
+    }
+
+    /// Restores the clip rectangle which was active before the last `push_clip_rect`.
+    /// Clipping is disabled once the stack is empty.
+    ///
+    /// ### Notes:
+    /// - Prints an error if the stack is empty.
+    pub fn pop_clip_rect(&mut self) {
+        self.flush_sprite_batch();
+        if self.clip_stack.pop().is_none() {
+            eprintln!("pop_clip_rect called without a matching push_clip_rect");
+        }
+        self.apply_clip_rect();
+    }
+
+    /// Returns the current clip rectangle in pixels, with the origin in the top left corner, or `None` if clipping is disabled.
+    pub fn get_clip_rect(&self) -> Option<Rect<i32>> {
+        return self.clip_stack.last().copied();
+    }
+
+    /// Applies the top of the clip stack as scissor rectangle. The scissor origin is the bottom left corner,
+    /// so the rectangle is flipped with the height of the bound render target or the viewport.
+    fn apply_clip_rect(&self) {
+        unsafe {
+            match self.clip_stack.last() {
+                Some(clip_rect) => {
+                    let target_height = self.render_target_size.unwrap_or(self.viewport.size).y as i32;
+                    gl::Enable(gl::SCISSOR_TEST);
+                    gl::Scissor(clip_rect.x, target_height - clip_rect.y - clip_rect.height, clip_rect.widht, clip_rect.height);
+                }
+                None => {
+                    gl::Disable(gl::SCISSOR_TEST);
+                }
+            }
+        }
+    }
+
+    /// Runs the draw calls of the closure with the given render state and restores the previous state afterwards.
+    pub fn with_render_state<F>(&mut self, render_state : RenderState, draw : F) where F : FnOnce(&mut RenderDevice) {
+        self.push_render_state(render_state);
+        draw(self);
+        self.pop_render_state();
+    }
+
+    /// Sets the 2D layer of the following immediate-mode texture and rect draws.
+    /// Higher layers are drawn in front of lower layers, see `LAYER_DEPTH_STEP`.
+    pub fn set_layer(&mut self, layer : f32) {
+        self.layer = layer;
+    }
+
+    /// Returns the 2D layer of the immediate-mode draws.
+    pub fn get_layer(&self) -> f32 {
+        return self.layer;
+    }
+
+    /// Sets the viewport size for rendering.
+    pub fn set_viewport(&mut self, viewport : Viewport) {
+        self.flush_sprite_batch();
+        unsafe {
+            gl::Viewport(0, 0, viewport.size.x as i32, viewport.size.y as i32);
+        }
+        self.viewport = viewport;
+        self.apply_clip_rect();
+        let width = viewport.size.x.max(1) as f32;
+        let height = viewport.size.y.max(1) as f32;
+        self.frame_uniforms.viewport = Vector4::new(width, height, 1.0 / width, 1.0 / height);
+        self.frame_dirty = true;
+    }
+
+    /// Configures the camera's view and projection matrices.
+    pub fn set_camera<T: ICamera>(&mut self, camera : &mut T) {
+        let view_matrix = camera.get_view_matrix();
+        self.set_view_matrix(view_matrix);
+        self.projection_matrix = camera.get_projection_matrix(self.viewport, 1.0);
+        self.frame_dirty = true;
+    }
+
+    /// Configures the view matrix.
+    pub fn set_view_matrix(&mut self, matrix : Matrix4<f32>) {
+        self.flush_sprite_batch();
+        self.view_matrix = matrix;
+        //the camera position is the translation of the inverse view matrix
+        if let Some(camera_matrix) = matrix.try_inverse() {
+            self.frame_uniforms.camera_position = Vector4::new(camera_matrix[(0, 3)], camera_matrix[(1, 3)], camera_matrix[(2, 3)], 1.0);
+        }
+        self.frame_dirty = true;
+    }
+
+    /// Configures the projection matrix.
+    pub fn set_projection_matrix(&mut self, matrix : Matrix4<f32>) {
+        self.flush_sprite_batch();
+        self.projection_matrix = matrix;
+        self.frame_dirty = true;
+    }
+
+    /// Creates a render target (framebuffer) with the specified width and height.
+    /// The target has one RGBA8 color attachment and a depth-stencil renderbuffer.
+    /// Prints the error and returns an empty render target if the framebuffer is incomplete.
+    pub fn create_render_target(&mut self, width : u32, height : u32) -> RenderTarget {
+        return self.create_render_target_from_descriptor(&RenderTargetDescriptor::new(width, height));
+    }
+
+    /// Creates a render target (framebuffer) with the specified width and height.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::IncompleteFramebuffer` if the framebuffer is not complete. The created resources are released.
+    pub fn try_create_render_target(&mut self, width : u32, height : u32) -> GfxResult<RenderTarget> {
+        return self.try_create_render_target_from_descriptor(&RenderTargetDescriptor::new(width, height));
+    }
+
+    /// Creates a render target with the color and depth attachments of the descriptor.
+    /// Prints the error and returns an empty render target if the target can't be created.
+    pub fn create_render_target_from_descriptor(&mut self, descriptor : &RenderTargetDescriptor) -> RenderTarget {
+        match self.try_create_render_target_from_descriptor(descriptor) {
+            Ok(render_target) => {
+                return render_target;
+            }
+            Err(e) => {
+                eprintln!("Error while creating RenderTarget: {}", e);
+                return RenderTarget::default();
+            }
+        }
+    }
+
+    /// Creates a render target with the color and depth attachments of the descriptor.
+    /// The color attachments are written by the fragment shader outputs with the same location.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::Unsupported` if the descriptor has more color attachments than `MAX_COLOR_ATTACHMENTS`
+    ///   or `GL_MAX_DRAW_BUFFERS` of the driver.
+    /// - Returns `GfxError::IncompleteFramebuffer` if the framebuffer is not complete, e.g. because a format is not renderable.
+    ///   The created resources are released.
+    pub fn try_create_render_target_from_descriptor(&mut self, descriptor : &RenderTargetDescriptor) -> GfxResult<RenderTarget> {
+        let color_attachment_count = descriptor.color_formats.len();
+        let mut max_draw_buffers : GLint = 0;
+        unsafe {
+            gl::GetIntegerv(gl::MAX_DRAW_BUFFERS, &mut max_draw_buffers);
+        }
+        if color_attachment_count > MAX_COLOR_ATTACHMENTS || color_attachment_count > max_draw_buffers as usize {
+            return Err(GfxError::Unsupported(format!("A render target with {} color attachments is not supported, the maximum is {}.",
+                color_attachment_count, MAX_COLOR_ATTACHMENTS.min(max_draw_buffers as usize))));
+        }
+        let mut max_samples : GLint = 0;
+        unsafe {
+            gl::GetIntegerv(gl::MAX_SAMPLES, &mut max_samples);
+        }
+        if descriptor.samples > max_samples as u32 {
+            return Err(GfxError::Unsupported(format!("A render target with {} samples is not supported, the maximum is {}.", descriptor.samples, max_samples)));
+        }
+
+        self.flush_sprite_batch();
+        let mut render_target = RenderTarget {
+            size: descriptor.size,
+            color_attachment_count: color_attachment_count,
+            depth_attachment: descriptor.depth_attachment,
+            samples: descriptor.samples,
+            ..Default::default()
+        };
+        let texture_target = render_target.texture_target();
+        render_target.color_formats[.. color_attachment_count].copy_from_slice(&descriptor.color_formats);
+
+        unsafe {
+            let mut previous_framebuffer : GLint = 0;
+            gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut previous_framebuffer);
+            gl::GenFramebuffers(1, &mut render_target.framebuffer_id);
+
+            //multisampled textures have no sampler state
+            for texture_id in render_target.color_textures[.. color_attachment_count].iter_mut() {
+                gl::GenTextures(1, texture_id);
+                if texture_target == gl::TEXTURE_2D {
+                    gl::BindTexture(gl::TEXTURE_2D, *texture_id);
+                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint);
+                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
+                }
+            }
+            render_target.texture_id = render_target.color_textures[0];
+
+            match render_target.depth_attachment {
+                DepthAttachment::None => {}
+                DepthAttachment::Renderbuffer => {
+                    gl::GenRenderbuffers(1, &mut render_target.renderbuffer_id);
+                }
+                DepthAttachment::Texture => {
+                    gl::GenTextures(1, &mut render_target.depth_texture_id);
+                    if texture_target == gl::TEXTURE_2D {
+                        gl::BindTexture(gl::TEXTURE_2D, render_target.depth_texture_id);
+                        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as GLint);
+                        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as GLint);
+                        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as GLint);
+                        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as GLint);
+                    }
+                }
+            }
+            allocate_render_target_storage(&render_target);
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, render_target.framebuffer_id);
+            let mut draw_buffers = Vec::with_capacity(color_attachment_count);
+            for (index, texture_id) in render_target.color_texture_ids().iter().enumerate() {
+                let attachment = gl::COLOR_ATTACHMENT0 + index as u32;
+                gl::FramebufferTexture2D(gl::FRAMEBUFFER, attachment, texture_target, *texture_id, 0);
+                draw_buffers.push(attachment);
+            }
+            if draw_buffers.is_empty() {
+                gl::DrawBuffer(gl::NONE);
+                gl::ReadBuffer(gl::NONE);
+            }
+            else {
+                gl::DrawBuffers(draw_buffers.len() as GLsizei, draw_buffers.as_ptr());
+            }
+            if render_target.renderbuffer_id != 0 {
+                gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_STENCIL_ATTACHMENT, gl::RENDERBUFFER, render_target.renderbuffer_id);
+            }
+            if render_target.depth_texture_id != 0 {
+                gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::DEPTH_STENCIL_ATTACHMENT, texture_target, render_target.depth_texture_id, 0);
+            }
+
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, previous_framebuffer as GLuint);
+
+            if status != gl::FRAMEBUFFER_COMPLETE {
+                self.dispose_render_target(&mut render_target);
+                return Err(GfxError::IncompleteFramebuffer(status));
+            }
+            return Ok(render_target);
+        }
+    }
+
+    /// Resizes an existing render target to the specified dimensions.
+    /// All color attachments and the depth attachment are reallocated with their formats, the content is lost.
+    pub fn resize_render_target(&mut self, render_target : &mut RenderTarget, width : u32, height : u32) {
+        self.flush_sprite_batch();
+        render_target.size = Vector2::new(width, height);
+        unsafe {
+            allocate_render_target_storage(render_target);
+        }
+        //the clip rectangle is flipped with the height of the bound target
+        let mut bound_framebuffer : GLint = 0;
+        unsafe {
+            gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut bound_framebuffer);
+        }
+        if self.render_target_size.is_some() && bound_framebuffer as u32 == render_target.framebuffer_id {
+            self.render_target_size = Some(render_target.size);
+            self.apply_clip_rect();
+        }
+    }
+
+    /// Resolves a multisampled render target into a regular render target, which can be drawn with `draw_render_target`.
+    /// Prints the error if the targets don't match.
+    pub fn resolve_render_target(&mut self, source : &RenderTarget, destination : &RenderTarget) {
+        if let Err(e) = self.try_resolve_render_target(source, destination) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Resolves a multisampled render target into a regular render target by blitting every color attachment
+    /// into the attachment with the same index. The depth and stencil buffer are resolved as well if both targets have one.
+    /// The destination can be created with `source.descriptor().with_samples(0)`.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the targets differ in size or the destination has fewer color attachments.
+    pub fn try_resolve_render_target(&mut self, source : &RenderTarget, destination : &RenderTarget) -> GfxResult<()> {
+        if source.size != destination.size {
+            return Err(GfxError::InvalidState(format!("Unable to resolve a {}x{} render target into a {}x{} render target.",
+                source.size.x, source.size.y, destination.size.x, destination.size.y)));
+        }
+        if source.color_attachment_count > destination.color_attachment_count {
+            return Err(GfxError::InvalidState(format!("Unable to resolve {} color attachments into {} color attachments.",
+                source.color_attachment_count, destination.color_attachment_count)));
+        }
+
+        self.flush_sprite_batch();
+        let width = source.size.x as GLint;
+        let height = source.size.y as GLint;
+        unsafe {
+            let mut previous_read_framebuffer : GLint = 0;
+            let mut previous_draw_framebuffer : GLint = 0;
+            gl::GetIntegerv(gl::READ_FRAMEBUFFER_BINDING, &mut previous_read_framebuffer);
+            gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut previous_draw_framebuffer);
+            //blits are clipped by the scissor test
+            gl::Disable(gl::SCISSOR_TEST);
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, source.framebuffer_id);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, destination.framebuffer_id);
+
+            for index in 0 .. source.color_attachment_count {
+                let attachment = gl::COLOR_ATTACHMENT0 + index as u32;
+                let mut draw_buffers = vec![gl::NONE; index + 1];
+                draw_buffers[index] = attachment;
+                gl::ReadBuffer(attachment);
+                gl::DrawBuffers(draw_buffers.len() as GLsizei, draw_buffers.as_ptr());
+                gl::BlitFramebuffer(0, 0, width, height, 0, 0, width, height, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+            }
+            if source.depth_attachment != DepthAttachment::None && destination.depth_attachment != DepthAttachment::None {
+                gl::BlitFramebuffer(0, 0, width, height, 0, 0, width, height, gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT, gl::NEAREST);
+            }
+
+            //the read and draw buffers are part of the framebuffer state
+            if source.color_attachment_count > 0 {
+                gl::ReadBuffer(gl::COLOR_ATTACHMENT0);
+            }
+            let draw_buffers : Vec<GLenum> = (0 .. destination.color_attachment_count).map(|index| gl::COLOR_ATTACHMENT0 + index as u32).collect();
+            if !draw_buffers.is_empty() {
+                gl::DrawBuffers(draw_buffers.len() as GLsizei, draw_buffers.as_ptr());
+            }
+
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, previous_read_framebuffer as GLuint);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, previous_draw_framebuffer as GLuint);
+        }
+        self.apply_clip_rect();
+        return Ok(());
+    }
+
+    /// Sets the sampler which `load_texture` and `load_texture_array` use, e.g. `SamplerDescriptor::nearest()` for pixel art games.
+    /// Textures which are already loaded keep their sampler.
+    pub fn set_default_sampler(&mut self, sampler : SamplerDescriptor) {
+        self.default_sampler = sampler;
+    }
+
+    /// Returns the sampler which `load_texture` and `load_texture_array` use.
+    pub fn get_default_sampler(&self) -> SamplerDescriptor {
+        return self.default_sampler;
+    }
+
+    /// Returns the maximum anisotropy of the driver, which `SamplerDescriptor::max_anisotropy` is clamped to.
+    /// Returns 1.0 if anisotropic filtering is not supported.
+    pub fn get_max_anisotropy(&self) -> f32 {
+        return self.max_anisotropy.max(1.0);
+    }
+
+    /// Loads a texture from an ImageTexture object into OpenGL.
+    pub fn load_texture(&mut self, image_texture: &mut ImageTexture) {
+        if let Err(e) = self.try_load_texture(image_texture) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Loads a texture from an ImageTexture object into OpenGL with the default sampler, see `set_default_sampler`.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the texture is not in the `PreLoad` state.
+    pub fn try_load_texture(&mut self, image_texture: &mut ImageTexture) -> GfxResult<()> {
+        let sampler = self.default_sampler;
+        return self.try_load_texture_with_sampler(image_texture, &sampler);
+    }
+
+    /// Loads a texture from an ImageTexture object into OpenGL and samples it with the given sampler.
+    pub fn load_texture_with_sampler(&mut self, image_texture : &mut ImageTexture, sampler : &SamplerDescriptor) {
+        if let Err(e) = self.try_load_texture_with_sampler(image_texture, sampler) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Loads a texture from an ImageTexture object into OpenGL and samples it with the given sampler.
+    /// The mipmaps are generated if the sampler has a mipmap filter.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the texture is not in the `PreLoad` state or the data doesn't match the
+    ///   dimensions and the color mode.
+    pub fn try_load_texture_with_sampler(&mut self, image_texture : &mut ImageTexture, sampler : &SamplerDescriptor) -> GfxResult<()> {
+        match image_texture {
+            ImageTexture::PreLoad { path: _, dimensions, data , mode } => {
+                let expected_size = dimensions.x as usize * dimensions.y as usize * mode.bytes_per_pixel();
+                if data.len() != expected_size {
+                    return Err(GfxError::InvalidState(format!("The texture contains {} bytes, expected {} bytes.", data.len(), expected_size)));
+                }
+                unsafe {
+                    let mut texture_id: GLuint = 0;
+                    gl::GenTextures(1, &mut texture_id);
+                    gl::BindTexture(gl::TEXTURE_2D, texture_id);
+                    gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+                    gl::TexImage2D(
+                        gl::TEXTURE_2D,
+                        0,
+                        mode.internal_format() as GLint,
+                        dimensions.x as GLsizei,
+                        dimensions.y as GLsizei,
+                        0,
+                        mode.pixel_format(),
+                        mode.pixel_type(),
+                        data.as_ptr() as *const GLvoid,
+                    );
+                    if let Some(swizzle) = mode.swizzle() {
+                        gl::TexParameteriv(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_RGBA, swizzle.as_ptr());
+                    }
+                    sampler.apply(gl::TEXTURE_2D, self.max_anisotropy);
+                    gl::BindTexture(gl::TEXTURE_2D, 0);
+                    *image_texture = ImageTexture::Loaded { id: texture_id, dimensions: Vector2::new(dimensions.x, dimensions.y) };
+                }
+                return Ok(());
+            }
+            ImageTexture::Loaded { id: _, dimensions: _ } => {
+                return Err(GfxError::InvalidState(String::from("Texture allready loaded!")));
+            }
+            _ => {
+                return Err(GfxError::InvalidState(String::from("You try to load an corruped or unloaded texture!")));
+            }
+        }
+    }
+
+    /// Changes the sampler of a loaded texture.
+    pub fn set_texture_sampler(&mut self, image_texture : &ImageTexture, sampler : &SamplerDescriptor) {
+        if let Err(e) = self.try_set_texture_sampler(image_texture, sampler) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Changes the sampler of a loaded texture. The mipmaps are (re)generated if the sampler has a mipmap filter.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the texture is not loaded.
+    ///
+    /// ### Notes:
+    /// - Queued sprites of the texture are flushed first, since they would be drawn with the new sampler otherwise.
+    pub fn try_set_texture_sampler(&mut self, image_texture : &ImageTexture, sampler : &SamplerDescriptor) -> GfxResult<()> {
+        let ImageTexture::Loaded { id, .. } = image_texture else {
+            return Err(GfxError::InvalidState(String::from("The sampler can only be set for loaded textures.")));
+        };
+        self.flush_sprite_batch();
+        unsafe {
+            let mut previous_texture : GLint = 0;
+            gl::GetIntegerv(gl::TEXTURE_BINDING_2D, &mut previous_texture);
+            gl::BindTexture(gl::TEXTURE_2D, *id);
+            sampler.apply(gl::TEXTURE_2D, self.max_anisotropy);
+            gl::BindTexture(gl::TEXTURE_2D, previous_texture as GLuint);
+        }
+        return Ok(());
+    }
+
+    /// Uploads the layers of a texture array into a `GL_TEXTURE_2D_ARRAY`.
+    pub fn load_texture_array(&mut self, texture_array : &mut TextureArray) {
+        if let Err(e) = self.try_load_texture_array(texture_array) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Uploads the layers of a texture array into a `GL_TEXTURE_2D_ARRAY` with the default sampler, see `set_default_sampler`.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the texture array is not in the `PreLoad` state or has no layers.
+    pub fn try_load_texture_array(&mut self, texture_array : &mut TextureArray) -> GfxResult<()> {
+        match texture_array {
+            TextureArray::PreLoad { dimensions, mode, layers } => {
+                if layers.is_empty() {
+                    return Err(GfxError::InvalidState(String::from("The texture array has no layers.")));
+                }
+                let mut texture_id : GLuint = 0;
+                unsafe {
+                    gl::GenTextures(1, &mut texture_id);
+                    gl::BindTexture(gl::TEXTURE_2D_ARRAY, texture_id);
+                    gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+                    gl::TexImage3D(
+                        gl::TEXTURE_2D_ARRAY,
+                        0,
+                        mode.internal_format() as GLint,
+                        dimensions.x as GLsizei,
+                        dimensions.y as GLsizei,
+                        layers.len() as GLsizei,
+                        0,
+                        mode.pixel_format(),
+                        mode.pixel_type(),
+                        std::ptr::null()
+                    );
+                    if let Some(swizzle) = mode.swizzle() {
+                        gl::TexParameteriv(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_SWIZZLE_RGBA, swizzle.as_ptr());
+                    }
+                    for (layer, data) in layers.iter().enumerate() {
+                        gl::TexSubImage3D(
+                            gl::TEXTURE_2D_ARRAY,
+                            0,
+                            0,
+                            0,
+                            layer as GLint,
+                            dimensions.x as GLsizei,
+                            dimensions.y as GLsizei,
+                            1,
+                            mode.pixel_format(),
+                            mode.pixel_type(),
+                            data.as_ptr() as *const GLvoid
+                        );
+                    }
+                    self.default_sampler.apply(gl::TEXTURE_2D_ARRAY, self.max_anisotropy);
+                    gl::BindTexture(gl::TEXTURE_2D_ARRAY, 0);
+                }
+                *texture_array = TextureArray::Loaded { id: texture_id, dimensions: *dimensions, layer_count: layers.len() as u32 };
+                return Ok(());
+            }
+            TextureArray::Loaded { .. } => {
+                return Err(GfxError::InvalidState(String::from("Texture array allready loaded!")));
+            }
+            TextureArray::Disposed => {
+                return Err(GfxError::InvalidState(String::from("You try to load a disposed texture array!")));
+            }
         }
     }
 
-    /// Disables depth testing in OpenGL.
-    pub fn disable_depth_test(&mut self) {
-        unsafe {
-            gl::Disable(gl::DEPTH_TEST);
+    /// Uploads the faces of a cubemap into a `GL_TEXTURE_CUBE_MAP` with linear filtering and clamped edges.
+    pub fn load_cubemap(&mut self, cubemap : &mut Cubemap) {
+        if let Err(e) = self.try_load_cubemap(cubemap) {
+            eprintln!("{}", e);
         }
     }
 
-    /// Enables depth testing in OpenGL.
-    pub fn enable_depth_test(&mut self) {
-        unsafe {
-            gl::Enable(gl::DEPTH_TEST);
-        }
+    /// Uploads the faces of a cubemap into a `GL_TEXTURE_CUBE_MAP` with linear filtering and clamped edges.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the cubemap is not in the `PreLoad` state.
+    pub fn try_load_cubemap(&mut self, cubemap : &mut Cubemap) -> GfxResult<()> {
+        return self.try_load_cubemap_with_sampler(cubemap, &SamplerDescriptor::default().with_wrap(TextureWrap::ClampToEdge));
     }
 
-    /// Sets the viewport size for rendering.
-    pub fn set_viewport(&mut self, viewport : Viewport) {
-        unsafe {
-            gl::Viewport(0, 0, viewport.size.x as i32, viewport.size.y as i32);
+    /// Uploads the faces of a cubemap into a `GL_TEXTURE_CUBE_MAP` and samples it with the given sampler.
+    pub fn load_cubemap_with_sampler(&mut self, cubemap : &mut Cubemap, sampler : &SamplerDescriptor) {
+        if let Err(e) = self.try_load_cubemap_with_sampler(cubemap, sampler) {
+            eprintln!("{}", e);
         }
-        self.viewport = viewport;
     }
 
-    /// Configures the camera's view and projection matrices.
-    pub fn set_camera<T: ICamera>(&mut self, camera : &mut T) {
-        self.view_matrix = camera.get_view_matrix();
-        self.projection_matrix = camera.get_projection_matrix(self.viewport, 1.0);
-    }
-
-    /// Configures the view matrix.
-    pub fn set_view_matrix(&mut self, matrix : Matrix4<f32>) {
-        self.view_matrix = matrix;
-    }
-
-    /// Configures the projection matrix.
-    pub fn set_projection_matrix(&mut self, matrix : Matrix4<f32>) {
-        self.projection_matrix = matrix;
-    }
-
-    /// Creates a render target (framebuffer) with the specified width and height.
-    pub fn create_render_target(&mut self, width : u32, height : u32) -> RenderTarget {
-        unsafe {
-            let mut framebuffer_id : GLuint = 0;
-            gl::GenFramebuffers(1, &mut framebuffer_id);
-            gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer_id);
-
-            let mut texture_id : GLuint = 0;
-            gl::GenTextures(1, &mut texture_id);
-            gl::BindTexture(gl::TEXTURE_2D, texture_id);
-            gl::TexImage2D(gl::TEXTURE_2D, 0, gl::RGBA as GLint, width as GLsizei, height as GLsizei, 0, gl::RGBA, gl::UNSIGNED_BYTE, std::ptr::null());
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
-            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, texture_id, 0);
-            
-            let mut renderbuffer_id : GLuint = 0;
-            gl::GenRenderbuffers(1, &mut renderbuffer_id);
-            gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer_id);
-            gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH24_STENCIL8, width as GLsizei, height as GLsizei);
-            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_STENCIL_ATTACHMENT, gl::RENDERBUFFER, renderbuffer_id);
-            
-            if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) == gl::FRAMEBUFFER_COMPLETE {
-                println!("RenderTarget created");
+    /// Uploads the faces of a cubemap into a `GL_TEXTURE_CUBE_MAP` and samples it with the given sampler.
+    /// The mipmaps are generated if the sampler has a mipmap filter.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the cubemap is not in the `PreLoad` state, has not 6 faces
+    ///   or a face doesn't contain `size * size` pixels of its color mode.
+    ///
+    /// ### Notes:
+    /// - Seamless filtering across the edges of the faces is enabled for all cubemaps.
+    pub fn try_load_cubemap_with_sampler(&mut self, cubemap : &mut Cubemap, sampler : &SamplerDescriptor) -> GfxResult<()> {
+        match cubemap {
+            Cubemap::PreLoad { size, mode, faces } => {
+                if faces.len() != CUBEMAP_FACES.len() {
+                    return Err(GfxError::InvalidState(format!("A cubemap requires 6 faces, got {}.", faces.len())));
+                }
+                let face_length = *size as usize * *size as usize * mode.bytes_per_pixel();
+                if let Some(face) = faces.iter().position(|data| data.len() != face_length) {
+                    return Err(GfxError::InvalidState(format!("The face {} of the cubemap has {} bytes, but {}x{} pixels require {} bytes.",
+                        face, faces[face].len(), size, size, face_length)));
+                }
+                let mut texture_id : GLuint = 0;
+                unsafe {
+                    gl::Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+                    gl::GenTextures(1, &mut texture_id);
+                    gl::BindTexture(gl::TEXTURE_CUBE_MAP, texture_id);
+                    gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+                    for (face, data) in CUBEMAP_FACES.iter().zip(faces.iter()) {
+                        gl::TexImage2D(
+                            face.target(),
+                            0,
+                            mode.internal_format() as GLint,
+                            *size as GLsizei,
+                            *size as GLsizei,
+                            0,
+                            mode.pixel_format(),
+                            mode.pixel_type(),
+                            data.as_ptr() as *const GLvoid
+                        );
+                    }
+                    if let Some(swizzle) = mode.swizzle() {
+                        gl::TexParameteriv(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_SWIZZLE_RGBA, swizzle.as_ptr());
+                    }
+                    sampler.apply(gl::TEXTURE_CUBE_MAP, self.max_anisotropy);
+                    gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+                }
+                *cubemap = Cubemap::Loaded { id: texture_id, size: *size };
+                return Ok(());
             }
-            else {
-                println!("Error while creating RenderTarget");
+            Cubemap::Loaded { .. } => {
+                return Err(GfxError::InvalidState(String::from("Cubemap allready loaded!")));
             }
-            
-            gl::BindTexture(gl::TEXTURE_2D, 0);
-            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
-            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
-
-            return RenderTarget {
-                size : Vector2::new(width, height),
-                renderbuffer_id: renderbuffer_id,
-                texture_id: texture_id,
-                framebuffer_id: framebuffer_id
+            Cubemap::Disposed => {
+                return Err(GfxError::InvalidState(String::from("You try to load a disposed cubemap!")));
             }
         }
     }
 
-    /// Resizes an existing render target to the specified dimensions.
-    pub fn resize_render_target(&mut self, render_target : &mut RenderTarget, width : u32, height : u32) {
-        unsafe {
-            gl::BindTexture(gl::TEXTURE_2D, render_target.texture_id);
-            gl::TexImage2D(gl::TEXTURE_2D, 0, gl::RGBA as GLint, width as GLsizei, height as GLsizei, 0, gl::RGBA, gl::UNSIGNED_BYTE, std::ptr::null());
-            gl::BindTexture(gl::TEXTURE_2D, 0);
-
-            gl::BindRenderbuffer(gl::RENDERBUFFER, render_target.renderbuffer_id);
-            gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH24_STENCIL8, width as GLsizei, height as GLsizei);
-            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
-
-            render_target.size = Vector2::new(width, height);
+    /// Uploads a color lookup table into a `GL_TEXTURE_3D`.
+    pub fn load_color_lut(&mut self, color_lut : &mut ColorLut) {
+        if let Err(e) = self.try_load_color_lut(color_lut) {
+            eprintln!("{}", e);
         }
     }
 
-    /// Loads a texture from an ImageTexture object into OpenGL.
-    pub fn load_texture(&mut self, image_texture: &mut ImageTexture) {
-        match image_texture {
-            ImageTexture::PreLoad { path: _, dimensions, data , mode } => {
+    /// Uploads a color lookup table into a `GL_TEXTURE_3D` with linear filtering.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the table is not in the `PreLoad` state or its data doesn't match its size.
+    pub fn try_load_color_lut(&mut self, color_lut : &mut ColorLut) -> GfxResult<()> {
+        match color_lut {
+            ColorLut::PreLoad { size, data } => {
+                let expected_size = (*size as usize).pow(3) * 3;
+                if *size == 0 || data.len() != expected_size {
+                    return Err(GfxError::InvalidState(format!("The color lut contains {} bytes, expected {} bytes.", data.len(), expected_size)));
+                }
+                let mut texture_id : GLuint = 0;
                 unsafe {
-                    let mut texture_id: GLuint = 0;
                     gl::GenTextures(1, &mut texture_id);
-                    gl::BindTexture(gl::TEXTURE_2D, texture_id);
-                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as GLint);
-                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as GLint);
-                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint);
-                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
-                    gl::TexImage2D(
-                        gl::TEXTURE_2D,
+                    gl::BindTexture(gl::TEXTURE_3D, texture_id);
+                    gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as GLint);
+                    gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as GLint);
+                    gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_WRAP_R, gl::CLAMP_TO_EDGE as GLint);
+                    gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint);
+                    gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
+                    gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+                    gl::TexImage3D(
+                        gl::TEXTURE_3D,
                         0,
-                        mode.as_glint(),
-                        dimensions.x as GLsizei,
-                        dimensions.y as GLsizei,
+                        gl::RGB8 as GLint,
+                        *size as GLsizei,
+                        *size as GLsizei,
+                        *size as GLsizei,
                         0,
-                        mode.as_u32(),
+                        gl::RGB,
                         gl::UNSIGNED_BYTE,
-                        data.as_ptr() as *const GLvoid,
+                        data.as_ptr() as *const GLvoid
                     );
-                    gl::BindTexture(gl::TEXTURE_2D, 0);
-                    *image_texture = ImageTexture::Loaded { id: texture_id, dimensions: Vector2::new(dimensions.x, dimensions.y) };
+                    gl::BindTexture(gl::TEXTURE_3D, 0);
                 }
+                *color_lut = ColorLut::Loaded { id: texture_id, size: *size };
+                return Ok(());
             }
-            ImageTexture::Loaded { id: _, dimensions: _ } => {
-                println!("Texture allready loaded!");
+            ColorLut::Loaded { .. } => {
+                return Err(GfxError::InvalidState(String::from("Color lut allready loaded!")));
             }
-            _ => {
-                eprintln!("You try to load an corruped or unloaded texture!");
+            ColorLut::Disposed => {
+                return Err(GfxError::InvalidState(String::from("You try to load a disposed color lut!")));
             }
         }
     }
 
     /// Loads a texture batch into GPU memory, creating necessary buffers and updating the batch state to `Loaded`.
     pub fn load_texture2d_batch(&mut self, instance_batch : &mut Texture2DBatch) {
+        if let Err(e) = self.try_load_texture2d_batch(instance_batch) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Loads a texture batch into GPU memory, creating necessary buffers and updating the batch state to `Loaded`.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the batch is not in the `PreLoad` state.
+    pub fn try_load_texture2d_batch(&mut self, instance_batch : &mut Texture2DBatch) -> GfxResult<()> {
         match instance_batch {
             Texture2DBatch::PreLoad { instances } => {
-                let buffers = Texture2DBatch::create_buffers(instances);
-                let mut mbo : GLuint = 0;
-                let mut cbo : GLuint = 0;
-                let mut uvto: GLuint = 0;
-                let mut exbo: GLuint = 0;
+                let mut instance_buffer : GLuint = 0;
                 unsafe {
-                    gl::GenBuffers(1, &mut mbo);
-                    gl::BindBuffer(gl::ARRAY_BUFFER, mbo);
-                    gl::BufferData(
-                        gl::ARRAY_BUFFER,
-                        (buffers.0.len() * std::mem::size_of::<f32>()) as isize,
-                        buffers.0.as_ptr() as *const _,
-                        gl::DYNAMIC_DRAW
-                    );
-
-                    gl::GenBuffers(1, &mut cbo);
-                    gl::BindBuffer(gl::ARRAY_BUFFER, cbo);
-                    gl::BufferData(
-                        gl::ARRAY_BUFFER,
-                        (buffers.1.len() * std::mem::size_of::<f32>()) as isize,
-                        buffers.1.as_ptr() as *const _,
-                        gl::DYNAMIC_DRAW
-                    );
-
-                    gl::GenBuffers(1, &mut uvto);
-                    gl::BindBuffer(gl::ARRAY_BUFFER, uvto);
-                    gl::BufferData(
-                        gl::ARRAY_BUFFER,
-                        (buffers.2.len() * std::mem::size_of::<f32>()) as isize,
-                        buffers.2.as_ptr() as *const _,
-                        gl::DYNAMIC_DRAW
-                    );
-
-                    gl::GenBuffers(1, &mut exbo);
-                    gl::BindBuffer(gl::ARRAY_BUFFER, exbo);
-                    gl::BufferData(
-                            gl::ARRAY_BUFFER,
-                            (buffers.3.len() * std::mem::size_of::<f32>()) as isize,
-                            buffers.3.as_ptr() as *const _,
-                            gl::DYNAMIC_DRAW
-                    );
-
-                    gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+                    gl::GenBuffers(1, &mut instance_buffer);
                 }
-                *instance_batch = Texture2DBatch::Loaded { instances: instances.clone(), mbo: mbo, cbo : cbo, uvto: uvto, exbo: exbo}
+                let mut instances = std::mem::take(instances);
+                instances.mark_dirty();
+                *instance_batch = Texture2DBatch::Loaded { instances, instance_buffer, capacity: 0, sorted: false };
+                self.sync_texture2d_batch(instance_batch);
+                return Ok(());
             }
             _ => {
-                eprintln!("You try to load an allready loaded instance batch.");
+                return Err(GfxError::InvalidState(String::from("You try to load an allready loaded instance batch.")));
+            }
+        }
+    }
+
+    /// Uploads the changed instances of a loaded batch with a single call.
+    ///
+    /// The buffer grows to the next power of two when the instances no longer fit
+    /// and shrinks when less than a quarter of its capacity is used.
+    /// When the buffer is reallocated or most of the instances changed, the buffer gets orphaned and all instances are uploaded,
+    /// so the driver doesn't have to wait for the draws of the previous frame.
+    /// With batch sorting enabled, a batch whose instances are not in layer order is uploaded as a sorted copy.
+    fn sync_texture2d_batch(&mut self, instance_batch : &mut Texture2DBatch) {
+        if let Texture2DBatch::Loaded { instances, instance_buffer, capacity, sorted } = instance_batch {
+            let order = if self.texture2d_batch_sorting { instances.layer_order() } else { None };
+            let dirty_range = instances.take_dirty_range();
+            let count = instances.len();
+            let new_capacity = count.next_power_of_two().max(16);
+            //the buffer shrinks even if the removed instances left nothing to rewrite
+            let reallocate = count > *capacity || (count < *capacity / 4 && new_capacity < *capacity);
+            //the buffer has to be rewritten when its order no longer matches
+            if !reallocate && dirty_range.is_none() && order.is_some() == *sorted {
+                return;
+            }
+            if reallocate {
+                *capacity = new_capacity;
+            }
+            let dirty_range = dirty_range.unwrap_or(0 .. count);
+            let orphan = reallocate || order.is_some() || *sorted || dirty_range.len() * 2 > count;
+            let range = if orphan { 0 .. count } else { dirty_range };
+            let data = match &order {
+                Some(order) => order.iter().map(|index| instances.as_slice()[*index].create_instance_data()).collect(),
+                None => Texture2DBatch::create_instance_buffer(&instances.as_slice()[range.clone()])
+            };
+            *sorted = order.is_some();
+            let stride = std::mem::size_of::<Texture2DInstanceData>();
+            unsafe {
+                gl::BindBuffer(gl::ARRAY_BUFFER, *instance_buffer);
+                if orphan {
+                    gl::BufferData(gl::ARRAY_BUFFER, (*capacity * stride) as GLsizeiptr, std::ptr::null(), gl::DYNAMIC_DRAW);
+                }
+                if !data.is_empty() {
+                    gl::BufferSubData(gl::ARRAY_BUFFER, (range.start * stride) as GLintptr, (data.len() * stride) as GLsizeiptr, data.as_ptr() as *const c_void);
+                }
+                gl::BindBuffer(gl::ARRAY_BUFFER, 0);
             }
         }
     }
 
     /// Updates a specific instance in a loaded `Texture2DBatch`, modifying its transformation, color, and UV data in GPU buffers.
     pub fn update_texture2d_batch_instance(&mut self, texture2d_batch : &mut Texture2DBatch, index : isize, instance : Texture2DInstance) {
+        if let Err(e) = self.try_update_texture2d_batch_instance(texture2d_batch, index, instance) {
+            eprintln!("Error: {}", e);
+        }
+    }
+
+    /// Updates a specific instance in a loaded `Texture2DBatch`, modifying its transformation, color, and UV data in GPU buffers.
+    ///
+    /// ### Notes:
+    /// - The GPU buffers are updated before the batch is drawn the next time.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the batch is not loaded or the index is out of range.
+    pub fn try_update_texture2d_batch_instance(&mut self, texture2d_batch : &mut Texture2DBatch, index : isize, instance : Texture2DInstance) -> GfxResult<()> {
         match texture2d_batch {
-            Texture2DBatch::Loaded { instances, mbo, cbo, uvto, exbo } => {
-                unsafe {
-                    instances[index as usize] = instance.clone();
-                    //update the transform
-                    let mut size = 16 * std::mem::size_of::<f32>() as isize;
-                    let mut offset = index * size;
-                    gl::BindBuffer(gl::ARRAY_BUFFER, *mbo);
-                    gl::BufferSubData(gl::ARRAY_BUFFER, offset, size, instance.transform.as_slice().as_ptr() as *const _);
-                    //update the color
-                    size = 4 * std::mem::size_of::<f32>() as isize;
-                    offset = index * size;
-                    gl::BindBuffer(gl::ARRAY_BUFFER, *cbo);
-                    gl::BufferSubData(gl::ARRAY_BUFFER, offset, size, instance.color.as_slice().as_ptr() as *const _);
-                    //update uv transform
-                    size = 4 * std::mem::size_of::<f32>() as isize;
-                    offset = index * size;
-                    gl::BindBuffer(gl::ARRAY_BUFFER, *uvto);
-                    gl::BufferSubData(gl::ARRAY_BUFFER, offset, size, instance.uv_transform.as_slice().as_ptr() as *const _);
-                    //update extras
-                    size = 4 * std::mem::size_of::<f32>() as isize;
-                    offset = index * size;
-                    gl::BindBuffer(gl::ARRAY_BUFFER, *exbo);
-                    gl::BufferSubData(gl::ARRAY_BUFFER, offset, size, instance.create_extras_vec4().as_slice().as_ptr() as *const _);
-                    //reset buffer
-                    gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            Texture2DBatch::Loaded { instances, .. } => {
+                let handle = usize::try_from(index).ok().and_then(|index| instances.handle_at(index));
+                match handle {
+                    Some(handle) => {
+                        instances.set(handle, instance);
+                        return Ok(());
+                    }
+                    None => {
+                        return Err(GfxError::InvalidState(format!("The instance index {} is out of range for a batch with {} instances.", index, instances.len())));
+                    }
                 }
             }
             _ => {
-                eprintln!("Error: Invalid Texture2DBatch state. Expected 'Loaded' state. Ensure the Texture2DBatch is properly loaded before updating.");
+                return Err(GfxError::InvalidState(String::from("Invalid Texture2DBatch state. Expected 'Loaded' state. Ensure the Texture2DBatch is properly loaded before updating.")));
             }
         }
     }
 
     /// Loads a font into GPU memory using FreeType, generating character textures and related data for text rendering.
+    /// Prints the error and returns an empty font if the font can't be loaded.
     pub fn load_font(&mut self, font_file : &str, font_height : u32) -> Font{
+        match self.try_load_font(font_file, font_height) {
+            Ok(font) => {
+                return font;
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                return Font::new();
+            }
+        }
+    }
+
+    /// Loads a font into GPU memory using FreeType, generating character textures and related data for text rendering.
+    /// Characters which are missing in the font are skipped.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::Font` if FreeType cannot load the font or one of its glyphs.
+    pub fn try_load_font(&mut self, font_file : &str, font_height : u32) -> GfxResult<Font> {
+        let font_error = |reason : &str, e : freetype::Error| GfxError::Font { path: font_file.to_string(), reason: format!("{}: {}", reason, e) };
         let mut font = Font::new();
-        let library = Library::init().expect("Failed to initialize FreeType library");
-        let face = library.new_face(font_file, 0).expect("Failed to load font");
-        face.set_pixel_sizes(0, font_height).expect("Failed to set pixel size");
+        let library = Library::init().map_err(|e| font_error("Failed to initialize FreeType library", e))?;
+        let face = library.new_face(font_file, 0).map_err(|e| font_error("Failed to load font", e))?;
+        face.set_pixel_sizes(0, font_height).map_err(|e| font_error("Failed to set pixel size", e))?;
 
         unsafe {
             gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
@@ -333,7 +1385,10 @@ impl RenderDevice {
                 Some(index) => {
                     if index != 0 {
                         // println!("Loading cahr: {} code: {}", character, character as usize);
-                        face.load_glyph(index, LoadFlag::RENDER).expect("Failed to load glyphe");
+                        if let Err(e) = face.load_glyph(index, LoadFlag::RENDER) {
+                            self.dispose_font(&mut font);
+                            return Err(font_error("Failed to load glyphe", e));
+                        }
                         let glyph = face.glyph();       
                         unsafe {
                             let mut texture : GLuint = 0;
@@ -359,15 +1414,13 @@ impl RenderDevice {
                                 texture_id: texture,
                                 size: Vector2::new(glyph.bitmap().width(), glyph.bitmap().rows()),
                                 bearing: Vector2::new(glyph.bitmap_left(), glyph.bitmap_top()),
-                                advance: glyph.advance().x
+                                advance: glyph.advance().x as i32
                             };
                             font.characters.insert(character, gfx_char);
                         }
                     }
                 }
-                None => {
-                    println!("Unable to load char {}", character);
-                }
+                None => {}
             }
         };
 
@@ -395,105 +1448,465 @@ impl RenderDevice {
             font.vao = font_vao;
             font.vbo = font_vbo;
         }
-        return font;
+        return Ok(font);
     }
 
     /// Compiles an OpenGL shader from the given source code.
     /// Takes shader source and type (e.g., vertex or fragment) and compiles it into a shader ID for use.
+    /// If the shader cannot be compiled, the info log is printed and 0 is returned.
     pub fn compile_shader(&mut self, source : &str, shader_type : GLuint) -> u32{
+        match self.compile_shader_internal(source, shader_type) {
+            Ok(shader_id) => {
+                return shader_id;
+            }
+            Err(log) => {
+                let source_lines = diagnostics::format_source_lines(source, &diagnostics::parse_error_lines(&log), 2);
+                eprintln!("Unable to compile the shader:\n{}\n{}", log.trim_end(), source_lines.trim_end());
+                return 0;
+            }
+        }
+    }
+
+    /// Compiles an OpenGL shader for the given stage.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::ShaderCompile` with the full info log and the offending source lines if the shader cannot be compiled.
+    pub fn try_compile_shader(&mut self, source : &str, stage : ShaderStage) -> GfxResult<u32> {
+        return self.compile_shader_internal(source, stage.as_gl_type()).map_err(|log| GfxError::ShaderCompile {
+            stage: stage,
+            source_lines: diagnostics::format_source_lines(source, &diagnostics::parse_error_lines(&log), 2),
+            log: log
+        });
+    }
+
+    fn compile_shader_internal(&mut self, source : &str, shader_type : GLuint) -> Result<u32, String> {
+        let shader_source = CString::new(source).map_err(|_| String::from("The shader source contains a null byte"))?;
         unsafe {
             let shader_id = gl::CreateShader(shader_type);
-            let shader_source = CString::new(source).expect("CString::new failed");
             let shader_length = shader_source.to_bytes().len() as GLint;
             gl::ShaderSource(shader_id, 1, &shader_source.as_ptr(), &shader_length);
             gl::CompileShader(shader_id);
-            println!("Compiled shader {} with error {}", shader_id, gl::GetError());
-            return shader_id;
+
+            let mut success = 0;
+            gl::GetShaderiv(shader_id, gl::COMPILE_STATUS, &mut success);
+            if success == 0 {
+                let log = self.get_shader_info_log(shader_id);
+                gl::DeleteShader(shader_id);
+                return Err(log);
+            }
+            return Ok(shader_id);
+        }
+    }
+
+    /// Returns the complete info log of a shader.
+    pub fn get_shader_info_log(&self, shader_id : u32) -> String {
+        unsafe {
+            let mut length = 0;
+            gl::GetShaderiv(shader_id, gl::INFO_LOG_LENGTH, &mut length);
+            let mut log = vec![0u8; length.max(1) as usize];
+            let mut written = 0;
+            gl::GetShaderInfoLog(shader_id, log.len() as GLsizei, &mut written, log.as_mut_ptr() as *mut GLchar);
+            return String::from_utf8_lossy(&log[.. written.max(0) as usize]).to_string();
+        }
+    }
+
+    /// Returns the complete info log of a shader program.
+    pub fn get_program_info_log(&self, program_id : u32) -> String {
+        unsafe {
+            let mut length = 0;
+            gl::GetProgramiv(program_id, gl::INFO_LOG_LENGTH, &mut length);
+            let mut log = vec![0u8; length.max(1) as usize];
+            let mut written = 0;
+            gl::GetProgramInfoLog(program_id, log.len() as GLsizei, &mut written, log.as_mut_ptr() as *mut GLchar);
+            return String::from_utf8_lossy(&log[.. written.max(0) as usize]).to_string();
         }
     }
 
     /// Creates and links a shader program from vertex and fragment shaders.
     /// Compiles vertex and fragment shaders, links them into an OpenGL program, and stores the program ID.
+    /// Errors are printed and a program which fails to build moves to the `Failed` state.
     pub fn build_shader_program(&mut self, shader_program : &mut ShaderProgram) {
+        if let Err(e) = self.try_build_shader_program(shader_program) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Creates and links a shader program from vertex and fragment shaders.
+    /// Compiles vertex and fragment shaders, links them into an OpenGL program, and stores the program ID.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::ShaderCompile`, `GfxError::ShaderLink` or `GfxError::ShaderPreprocess` if the program cannot be built.
+    ///   The program moves to the `Failed` state, which can be built again.
+    /// - Returns `GfxError::InvalidState` if the program is already built or disposed.
+    pub fn try_build_shader_program(&mut self, shader_program : &mut ShaderProgram) -> GfxResult<()> {
         match shader_program {
-            ShaderProgram::PreBuild { fragment_shader, vertex_shader } => {
-                unsafe {
-                    let vertex_shader = self.compile_shader(&vertex_shader.source, gl::VERTEX_SHADER);
-                    let fragment_shader = self.compile_shader(&fragment_shader.source, gl::FRAGMENT_SHADER);
-        
-                    let program_id = gl::CreateProgram();
-                    gl::AttachShader(program_id, vertex_shader);
-                    gl::AttachShader(program_id, fragment_shader);
-                    gl::LinkProgram(program_id);
-                    gl::DeleteShader(vertex_shader);
-                    gl::DeleteShader(fragment_shader);
-                    
-                    let mut success = 1;
-                    let mut log = vec![0; 512];
-        
-                    gl::GetProgramiv(program_id, gl::LINK_STATUS, &mut success);
-                    if success == 0 {
-                        gl::GetProgramInfoLog(program_id, 512, std::ptr::null_mut(), log.as_mut_ptr() as *mut i8);
-                        println!("Shader Program Linking Failed: {}", String::from_utf8_lossy(&log)); 
+            ShaderProgram::PreBuild { fragment_shader, vertex_shader } | ShaderProgram::Failed { fragment_shader, vertex_shader, .. } => {
+                let mut fragment_shader = std::mem::take(fragment_shader);
+                let mut vertex_shader = std::mem::take(vertex_shader);
+                match self.link_shader_program(&mut vertex_shader, &mut fragment_shader) {
+                    Ok((program_id, reflection)) => {
+                        if Self::is_texture2d_shader(&vertex_shader, &fragment_shader) {
+                            self.sprite_batch_programs.insert(program_id);
+                        }
+                        *shader_program = ShaderProgram::Builded {
+                            program_id: program_id,
+                            fragment_shader: fragment_shader,
+                            vertex_shader: vertex_shader,
+                            reflection: reflection
+                        };
+                        return Ok(());
                     }
-                    else 
-                    {
-                        println!("Shader Program {} created with error {}", program_id, gl::GetError());
+                    Err(e) => {
+                        *shader_program = ShaderProgram::Failed {
+                            fragment_shader: fragment_shader,
+                            vertex_shader: vertex_shader,
+                            error: e.to_string()
+                        };
+                        return Err(e);
                     }
-
-                    *shader_program = ShaderProgram::Builded { program_id: program_id };
                 }
             }
-            ShaderProgram::Builded { program_id:_ } => {
-                panic!("Error: Attempted to build a shader program that has already been built.");
+            ShaderProgram::Builded { .. } => {
+                return Err(GfxError::InvalidState(String::from("Attempted to build a shader program that has already been built.")));
             }
             ShaderProgram::Disposed {} => {
-                panic!("Error: Cannot build a shader program that has been disposed.")
+                return Err(GfxError::InvalidState(String::from("Cannot build a shader program that has been disposed.")));
+            }
+        }
+    }
+
+    /// Returns true if the shaders are the unmodified sources of `Texture2DShader`, which the sprite batch can replace.
+    fn is_texture2d_shader(vertex_shader : &Shader, fragment_shader : &Shader) -> bool {
+        let ShaderProgram::PreBuild { vertex_shader: texture2d_vertex, fragment_shader: texture2d_fragment } = Texture2DShader::build_shader_program() else {
+            return false;
+        };
+        return vertex_shader.source == texture2d_vertex.source && fragment_shader.source == texture2d_fragment.source
+            && vertex_shader.defines.is_empty() && fragment_shader.defines.is_empty();
+    }
+
+    /// Returns the preprocessor which resolves the includes and defines of all shaders built by this device.
+    pub fn shader_preprocessor(&mut self) -> &mut ShaderPreprocessor {
+        return &mut self.shader_preprocessor;
+    }
+
+    /// Preprocesses both stages, compiles them and links them into a new OpenGL program.
+    /// The includes of the shaders are updated for hot reloading.
+    ///
+    /// # Returns
+    /// - The id and the reflected metadata of the new program.
+    fn link_shader_program(&mut self, vertex_shader : &mut Shader, fragment_shader : &mut Shader) -> GfxResult<(u32, ShaderReflection)> {
+        let vertex_source = self.shader_preprocessor.process(vertex_shader)?;
+        let fragment_source = self.shader_preprocessor.process(fragment_shader)?;
+        vertex_shader.includes = vertex_source.includes;
+        fragment_shader.includes = fragment_source.includes;
+
+        unsafe {
+            let vertex_shader = self.try_compile_shader(&vertex_source.source, ShaderStage::Vertex)?;
+            let fragment_shader = match self.try_compile_shader(&fragment_source.source, ShaderStage::Fragment) {
+                Ok(fragment_shader) => fragment_shader,
+                Err(e) => {
+                    gl::DeleteShader(vertex_shader);
+                    return Err(e);
+                }
+            };
+
+            let program_id = gl::CreateProgram();
+            //the id could belong to a program which has been deleted outside of the device
+            self.forget_shader_program(program_id);
+            gl::AttachShader(program_id, vertex_shader);
+            gl::AttachShader(program_id, fragment_shader);
+            gl::LinkProgram(program_id);
+            gl::DeleteShader(vertex_shader);
+            gl::DeleteShader(fragment_shader);
+            
+            let mut success = 1;
+            gl::GetProgramiv(program_id, gl::LINK_STATUS, &mut success);
+            if success == 0 {
+                let log = self.get_program_info_log(program_id);
+                gl::DeleteProgram(program_id);
+                return Err(GfxError::ShaderLink(log));
+            }
+
+            let mut reflection = ShaderReflection::reflect(program_id);
+            for block in reflection.uniform_blocks.iter_mut() {
+                let binding = match block.name.as_str() {
+                    FRAME_BLOCK_NAME => FRAME_BLOCK_BINDING,
+                    LIGHTS_BLOCK_NAME => LIGHTS_BLOCK_BINDING,
+                    _ => continue
+                };
+                gl::UniformBlockBinding(program_id, block.index, binding);
+                block.binding = binding;
+            }
+            self.shader_reflections.insert(program_id, reflection.clone());
+            return Ok((program_id, reflection));
+        }
+    }
+
+    /// Rebuilds a built shader program if one of its source files has been modified.
+    /// Errors are printed and the old program stays in use.
+    ///
+    /// # Returns
+    /// - `true` if the program was rebuilt.
+    pub fn reload_shader_program(&mut self, shader_program : &mut ShaderProgram) -> bool {
+        match self.try_reload_shader_program(shader_program) {
+            Ok(reloaded) => {
+                return reloaded;
+            }
+            Err(e) => {
+                eprintln!("Unable to reload the shader program: {}", e);
+                return false;
+            }
+        }
+    }
+
+    /// Rebuilds a built shader program if one of its source files has been modified.
+    ///
+    /// The sources are read again and compiled into a new program, which replaces the old one in place.
+    /// If the program is currently bound, the new program is bound instead. Call this once per frame
+    /// (or less often) to iterate on shaders while the application is running.
+    ///
+    /// # Returns
+    /// - `Ok(true)` if the program was rebuilt, `Ok(false)` if no source file has changed.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::Io`, `GfxError::ShaderCompile` or `GfxError::ShaderLink` if the new sources cannot be built.
+    ///   The old program stays valid and the failed revision is not retried until the files change again.
+    /// - Returns `GfxError::InvalidState` if the program is not built.
+    pub fn try_reload_shader_program(&mut self, shader_program : &mut ShaderProgram) -> GfxResult<bool> {
+        let ShaderProgram::Builded { program_id, fragment_shader, vertex_shader, reflection } = shader_program else {
+            return Err(GfxError::InvalidState(String::from("Only built shader programs can be reloaded.")));
+        };
+        if !vertex_shader.has_changed() && !fragment_shader.has_changed() {
+            return Ok(false);
+        }
+
+        let mut new_vertex_shader = vertex_shader.clone();
+        let mut new_fragment_shader = fragment_shader.clone();
+        let vertex_result = Self::reload_shader_source(&mut new_vertex_shader);
+        let fragment_result = Self::reload_shader_source(&mut new_fragment_shader);
+
+        let link_result = vertex_result.and(fragment_result)
+            .and_then(|()| self.link_shader_program(&mut new_vertex_shader, &mut new_fragment_shader));
+        //remember the revision even if it fails, so it is not compiled again every frame
+        vertex_shader.modified = new_vertex_shader.modified;
+        vertex_shader.includes = new_vertex_shader.includes.clone();
+        fragment_shader.modified = new_fragment_shader.modified;
+        fragment_shader.includes = new_fragment_shader.includes.clone();
+        let (new_program_id, new_reflection) = link_result?;
+
+        unsafe {
+            if self.shader_program == *program_id {
+                gl::UseProgram(new_program_id);
+                self.shader_program = new_program_id;
+            }
+            gl::DeleteProgram(*program_id);
+        }
+        self.forget_shader_program(*program_id);
+        *program_id = new_program_id;
+        *reflection = new_reflection;
+        *vertex_shader = new_vertex_shader;
+        *fragment_shader = new_fragment_shader;
+        return Ok(true);
+    }
+
+    fn reload_shader_source(shader : &mut Shader) -> GfxResult<()> {
+        if shader.path.is_some() {
+            return shader.reload();
+        }
+        return Ok(());
+    }
+
+    /// Returns the variant of the shader for the given define set and builds it on first use.
+    /// Errors are printed and `None` is returned.
+    pub fn get_shader_variant<'a>(&mut self, shader_variants : &'a mut ShaderVariants, defines : &ShaderDefines) -> Option<&'a mut ShaderProgram> {
+        match self.try_get_shader_variant(shader_variants, defines) {
+            Ok(shader_program) => {
+                return Some(shader_program);
+            }
+            Err(e) => {
+                eprintln!("Unable to build the shader variant {:?}: {}", defines, e);
+                return None;
             }
         }
     }
 
+    /// Returns the variant of the shader for the given define set and builds it on first use.
+    ///
+    /// # Errors
+    /// - Returns the build error if the variant cannot be built. Failed variants are not cached,
+    ///   so the build is attempted again on the next call.
+    pub fn try_get_shader_variant<'a>(&mut self, shader_variants : &'a mut ShaderVariants, defines : &ShaderDefines) -> GfxResult<&'a mut ShaderProgram> {
+        if shader_variants.get(defines).is_none() {
+            let mut shader_program = shader_variants.create_program(defines);
+            self.try_build_shader_program(&mut shader_program)?;
+            shader_variants.insert(defines.clone(), shader_program);
+        }
+        return shader_variants.get(defines).ok_or_else(|| GfxError::MissingResource(format!("shader variant {:?}", defines)));
+    }
+
+    /// Rebuilds all cached variants whose source files have been modified.
+    ///
+    /// # Returns
+    /// - The number of rebuilt variants.
+    pub fn reload_shader_variants(&mut self, shader_variants : &mut ShaderVariants) -> usize {
+        //keep the shared sources up to date for variants which are built later
+        for shader in [&mut shader_variants.vertex_shader, &mut shader_variants.fragment_shader] {
+            if shader.has_changed() {
+                if let Err(e) = Self::reload_shader_source(shader) {
+                    eprintln!("Unable to reload the shader source: {}", e);
+                }
+            }
+        }
+
+        let mut reloaded = 0;
+        for (_, shader_program) in shader_variants.iter_mut() {
+            if self.reload_shader_program(shader_program) {
+                reloaded += 1;
+            }
+        }
+        return reloaded;
+    }
+
+    /// Disposes all cached variants. The sources are kept, so the variants can be built again.
+    pub fn dispose_shader_variants(&mut self, shader_variants : &mut ShaderVariants) {
+        for mut shader_program in shader_variants.drain() {
+            self.dispose_shader_program(&mut shader_program);
+        }
+    }
+
     /// Binds a shader program for use in rendering.
     /// Sets the specified shader program as the current OpenGL program for drawing.
+    ///
+    /// ### Notes:
+    /// - Errors are printed. A program which failed to build is not bound, so the following draws are skipped by OpenGL.
     pub fn bind_shader_program(&mut self, shader_program : &mut ShaderProgram) {
+        if let Err(e) = self.try_bind_shader_program(shader_program) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Binds a shader program for use in rendering.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::ShaderBuildFailed` if the last build of the program failed. No program is bound afterwards.
+    /// - Returns `GfxError::InvalidState` if the program is not built.
+    pub fn try_bind_shader_program(&mut self, shader_program : &mut ShaderProgram) -> GfxResult<()> {
+        self.flush_sprite_batch();
         match shader_program {
-            ShaderProgram::Builded { program_id } => {
+            ShaderProgram::Builded { program_id, .. } => {
                 unsafe {
                     gl::UseProgram(*program_id);
                     self.shader_program = *program_id;
                 }
+                self.failed_program_bound = false;
+                return Ok(());
+            }
+            ShaderProgram::Failed { error, .. } => {
+                unsafe {
+                    gl::UseProgram(0);
+                    self.shader_program = 0;
+                }
+                self.failed_program_bound = true;
+                return Err(GfxError::ShaderBuildFailed(error.clone()));
             }
             ShaderProgram::PreBuild { fragment_shader:_ , vertex_shader:_ } => {
-                panic!("You try to bind an pre builded program!");
+                return Err(GfxError::InvalidState(String::from("You try to bind an pre builded program!")));
             }
             ShaderProgram::Disposed {} => {
-                panic!("You try to bind an disposed program!");
+                return Err(GfxError::InvalidState(String::from("You try to bind an disposed program!")));
             }
         }
-        
     }
 
     /// Unbinds the currently bound shader program.
     /// Deactivates the custom shader program, resetting OpenGL to use the default program.
     pub fn unbind_shader_program(&mut self) {
+        self.flush_sprite_batch();
         unsafe {
             gl::UseProgram(0);
             self.shader_program = 0;
         }
+        self.failed_program_bound = false;
     }
 
     /// Retrieves the location of a uniform variable in a shader program.
     /// Queries OpenGL for the uniform's location and returns it, or -1 if not found.
     /// The location is used to set or get uniform values during rendering.
+    /// Locations are cached per program, so OpenGL is only queried once per uniform.
     pub fn get_uniform_location(&mut self, program_id : u32, name : &str) -> i32 {
-        let name: CString = CString::new(name).expect("CString::new failed");
+        let locations = self.uniform_locations.entry(program_id).or_default();
+        if let Some(location) = locations.get(name) {
+            return *location;
+        }
+
+        let c_name: CString = CString::new(name).expect("CString::new failed");
         unsafe {
-            let location= gl::GetUniformLocation(program_id, name.as_ptr());
+            let location= gl::GetUniformLocation(program_id, c_name.as_ptr());
+            locations.insert(String::from(name), location);
             return location;
         }
     }
 
+    /// Assigns a value to a uniform of the bound shader program.
+    /// Uniforms which do not exist in the program are ignored.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the uniform.
+    /// - `value`: Any value implementing `Uniform`, e.g. `f32`, `bool`, `Vector4<f32>`, `Matrix4<f32>` or `&[f32]`.
+    ///
+    /// ### Notes:
+    /// - For programs built by this device a warning is printed once per uniform, if the program has no
+    ///   active uniform with the name or the type of the value does not match the uniform.
+    pub fn set_uniform<U : Uniform>(&mut self, name : &str, value : U) {
+        self.validate_uniform(name, &value);
+        self.set_uniform_internal(name, value);
+    }
+
+    /// Assigns a value to a uniform of the bound shader program without validating it.
+    /// Used for the uniforms of the prebuilt shaders, which custom shaders are free to omit.
+    fn set_uniform_internal<U : Uniform>(&mut self, name : &str, value : U) {
+        let location = self.get_uniform_location(self.shader_program, name);
+        if location >= 0 {
+            value.set_uniform(location);
+        }
+    }
+
+    /// Prints a warning if the uniform does not exist in the bound program or has a different type than the value.
+    fn validate_uniform<U : Uniform>(&mut self, name : &str, value : &U) {
+        let Some(reflection) = self.shader_reflections.get(&self.shader_program) else {
+            return;
+        };
+
+        let warning = match reflection.get_uniform(name) {
+            None => {
+                Some(format!("The shader program {} has no active uniform {}, it may have been optimized out", self.shader_program, name))
+            }
+            Some(uniform) if uniform.block_index >= 0 => {
+                Some(format!("The uniform {} of the shader program {} is part of a uniform block and cannot be set directly", name, self.shader_program))
+            }
+            Some(uniform) if !value.is_compatible(uniform.gl_type) => {
+                Some(format!("The uniform {} of the shader program {} has the type {}, which does not match the assigned value", name, self.shader_program, gl_type_name(uniform.gl_type)))
+            }
+            Some(_) => None
+        };
+
+        if let Some(warning) = warning {
+            if self.uniform_warnings.insert((self.shader_program, String::from(name))) {
+                eprintln!("Warning: {}", warning);
+            }
+        }
+    }
+
+    /// Removes all cached data of a program id, after the program has been deleted or the id is reused.
+    fn forget_shader_program(&mut self, program_id : u32) {
+        self.uniform_locations.remove(&program_id);
+        self.shader_reflections.remove(&program_id);
+        self.sprite_batch_programs.remove(&program_id);
+        self.uniform_warnings.retain(|(id, _)| *id != program_id);
+    }
+
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_bool(&mut self, location : &str, v0 : bool) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -502,6 +1915,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_1f(&mut self, location : &str, v0 : f32) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -510,6 +1924,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_1fv(&mut self, location : &str, values : &[f32]) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -518,6 +1933,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_1i(&mut self, location : &str, v0 : i32) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -526,6 +1942,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_1iv(&mut self, location : &str, values : &[i32]) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -534,6 +1951,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_2f(&mut self, location : &str, v0 : f32, v1 : f32) {
         unsafe  {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -542,6 +1960,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_2fv(&mut self, location : &str, values : &[f32]) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -551,6 +1970,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_2i(&mut self, location : &str, v0 : i32, v1 : i32) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -559,6 +1979,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_2iv(&mut self, location : &str, values : &[i32]) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -568,6 +1989,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_3f(&mut self, location : &str, v0 : f32, v1 : f32, v2 : f32) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -576,6 +1998,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_3fv(&mut self, location : &str, values : &[f32]) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -585,6 +2008,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_3i(&mut self, location : &str, v0 : i32, v1 : i32, v2 : i32) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -593,6 +2017,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_3iv(&mut self, location : &str, values : &[i32]) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -602,6 +2027,7 @@ impl RenderDevice {
     }
     
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_4f(&mut self, location : &str, v0 : f32, v1 : f32, v2 : f32, v3 : f32) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -610,6 +2036,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_4fv(&mut self, location : &str, values : &[f32]) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -619,6 +2046,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_4i(&mut self, location : &str, v0 : i32, v1 : i32, v2 : i32, v3 : i32) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -627,6 +2055,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_4iv(&mut self, location : &str, values : &[i32]) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -636,6 +2065,7 @@ impl RenderDevice {
     }
 
     /// Prepares the binded shader with the given data
+    #[deprecated(note = "Use `set_uniform` instead")]
     pub fn prepare_shader_m4(&mut self, location : &str, transpose : bool, matrix : &[f32; 16]) {
         unsafe {
             let location_id = self.get_uniform_location(self.shader_program, location);
@@ -694,28 +2124,67 @@ impl RenderDevice {
 
     /// Binds the given image_texture to the assigned texture_slot
     pub fn bind_image_texture(&mut self, texture_slot : TextureSlot, image_texture : ImageTexture) {
+        if let Err(e) = self.try_bind_image_texture(texture_slot, &image_texture) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Binds the given image_texture to the assigned texture_slot
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the texture is not loaded.
+    pub fn try_bind_image_texture(&mut self, texture_slot : TextureSlot, image_texture : &ImageTexture) -> GfxResult<()> {
         match image_texture {
             ImageTexture::Loaded { id, dimensions:_ } => {
+                //the pending sprites rebind TEXTURE0 when they are drawn
+                self.flush_sprite_batch();
                 unsafe {
                     gl::ActiveTexture(texture_slot as u32);
-                    gl::BindTexture(gl::TEXTURE_2D, id);
+                    gl::BindTexture(gl::TEXTURE_2D, *id);
                 }
+                return Ok(());
             }
             _ => {
-                eprintln!("You try to bind an unloaded image_texture");
+                return Err(GfxError::InvalidState(String::from("You try to bind an unloaded image_texture")));
             }
         }
     }
 
+    /// Binds the given cubemap to the assigned texture_slot, e.g. for reflections within a custom shader.
+    pub fn bind_cubemap(&mut self, texture_slot : TextureSlot, cubemap : &Cubemap) {
+        if let Err(e) = self.try_bind_cubemap(texture_slot, cubemap) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Binds the given cubemap to the assigned texture_slot, e.g. for reflections within a custom shader.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the cubemap is not loaded.
+    pub fn try_bind_cubemap(&mut self, texture_slot : TextureSlot, cubemap : &Cubemap) -> GfxResult<()> {
+        let Cubemap::Loaded { id, .. } = cubemap else {
+            return Err(GfxError::InvalidState(String::from("You try to bind an unloaded cubemap")));
+        };
+        self.flush_sprite_batch();
+        unsafe {
+            gl::ActiveTexture(texture_slot as u32);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, *id);
+        }
+        return Ok(());
+    }
+
     /// Binds the specified render target (framebuffer) for rendering.
     /// This directs rendering operations to the given framebuffer instead of the default one.
     /// ### Notes:
     /// - After rendering, call `unbind_render_target` to return to the default framebuffer.
     pub fn bind_render_target(&mut self, render_target : RenderTarget) 
     {
+        self.flush_sprite_batch();
         unsafe {
             gl::BindFramebuffer(gl::FRAMEBUFFER, render_target.framebuffer_id);
         }
+        self.render_target_size = Some(render_target.size);
+        self.apply_clip_rect();
     }
 
     /// Unbinds the currently bound render target and reverts to the default framebuffer.
@@ -724,9 +2193,102 @@ impl RenderDevice {
     /// ### Notes:
     /// - Call this after rendering to a custom framebuffer to reset the rendering target.
     pub fn unbind_render_target(&mut self) {
+        self.flush_sprite_batch();
         unsafe {
             gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
         }
+        self.render_target_size = None;
+        self.apply_clip_rect();
+    }
+
+    /// Reads the pixels of the render target's color attachment back into a preloaded `ImageTexture`.
+    /// The rows are returned top-down like in an image file, so the result can be saved with `ImageTexture::save_png`.
+    pub fn read_render_target(&mut self, render_target : &RenderTarget) -> ImageTexture {
+        return self.read_render_target_attachment(render_target, 0);
+    }
+
+    /// Reads the pixels of the color attachment with the given index back into a preloaded `ImageTexture`.
+    /// Float formats are read back unclamped as 32 bit floats, see `RenderTargetFormat::read_color_mode`.
+    /// Returns `ImageTexture::Corrupted` if the target has no color attachment with this index.
+    pub fn read_render_target_attachment(&mut self, render_target : &RenderTarget, index : usize) -> ImageTexture {
+        if index >= render_target.color_attachment_count {
+            eprintln!("The render target has no color attachment {}", index);
+            return ImageTexture::Corrupted;
+        }
+        if render_target.is_multisampled() {
+            eprintln!("A multisampled render target can't be read back, resolve it with resolve_render_target first");
+            return ImageTexture::Corrupted;
+        }
+        self.flush_sprite_batch();
+        let mode = render_target.color_formats[index].read_color_mode();
+        return self.read_framebuffer_pixels(render_target.framebuffer_id, gl::COLOR_ATTACHMENT0 + index as u32, render_target.size, mode);
+    }
+
+    /// Reads the pixels of the default framebuffer (the screen) within the current viewport back into a preloaded `ImageTexture`.
+    /// The rows are returned top-down like in an image file. Call this before swapping the buffers.
+    pub fn read_framebuffer(&mut self) -> ImageTexture {
+        self.flush_sprite_batch();
+        return self.read_framebuffer_pixels(0, gl::BACK, self.viewport.size, ColorMode::RGBA);
+    }
+
+    /// Reads the pixels of a loaded image texture back into a preloaded `ImageTexture` with the color mode of the texture.
+    /// The rows are returned in the same order in which they were uploaded with `load_texture`.
+    ///
+    /// ### Notes:
+    /// - Textures with an internal format which has no `ColorMode`, e.g. render target attachments, are read as RGBA.
+    pub fn read_image_texture(&mut self, image_texture : &ImageTexture) -> ImageTexture {
+        self.flush_sprite_batch();
+        match image_texture {
+            ImageTexture::Loaded { id, dimensions } => {
+                unsafe {
+                    gl::BindTexture(gl::TEXTURE_2D, *id);
+                    let mut internal_format : GLint = 0;
+                    gl::GetTexLevelParameteriv(gl::TEXTURE_2D, 0, gl::TEXTURE_INTERNAL_FORMAT, &mut internal_format);
+                    let mode = ColorMode::from_internal_format(internal_format as u32).unwrap_or(ColorMode::RGBA);
+
+                    let mut data = vec![0u8; dimensions.x as usize * dimensions.y as usize * mode.bytes_per_pixel()];
+                    gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+                    gl::GetTexImage(gl::TEXTURE_2D, 0, mode.pixel_format(), mode.pixel_type(), data.as_mut_ptr() as *mut GLvoid);
+                    gl::BindTexture(gl::TEXTURE_2D, 0);
+                    return ImageTexture::PreLoad {
+                        path: String::new(),
+                        dimensions: *dimensions,
+                        data: data,
+                        mode: mode
+                    };
+                }
+            }
+            _ => {
+                eprintln!("You try to read back an unloaded image_texture");
+                return ImageTexture::Corrupted;
+            }
+        }
+    }
+
+    fn read_framebuffer_pixels(&mut self, framebuffer_id : u32, read_buffer : GLenum, size : Vector2<u32>, mode : ColorMode) -> ImageTexture {
+        let mut data = vec![0u8; size.x as usize * size.y as usize * mode.bytes_per_pixel()];
+        unsafe {
+            let mut previous_framebuffer : GLint = 0;
+            gl::GetIntegerv(gl::READ_FRAMEBUFFER_BINDING, &mut previous_framebuffer);
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, framebuffer_id);
+            //the read buffer is part of the framebuffer state
+            let mut previous_read_buffer : GLint = 0;
+            gl::GetIntegerv(gl::READ_BUFFER, &mut previous_read_buffer);
+            gl::ReadBuffer(read_buffer);
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(0, 0, size.x as GLsizei, size.y as GLsizei, mode.pixel_format(), mode.pixel_type(), data.as_mut_ptr() as *mut GLvoid);
+            gl::ReadBuffer(previous_read_buffer as GLenum);
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, previous_framebuffer as GLuint);
+        }
+        let mut image_texture = ImageTexture::PreLoad {
+            path: String::new(),
+            dimensions: size,
+            data: data,
+            mode: mode
+        };
+        //opengl returns the rows bottom-up
+        image_texture.flip_vertically();
+        return image_texture;
     }
 
     /// Initializes a shape's vertex data by creating and binding the necessary OpenGL buffers (VAO, VBO, IBO, TBO) 
@@ -995,6 +2557,21 @@ impl RenderDevice {
     /// and uses the appropriate shaders to render the mesh. The material properties, including texture and color, 
     /// are handled, and OpenGL's `gl::DrawElements` is used to draw the mesh.
     pub fn draw_mesh(&mut self, transform : &mut Transform3D, mesh : &mut Mesh, apply_local_matrix : bool) {
+        if let Err(e) = self.try_draw_mesh(transform, mesh, apply_local_matrix) {
+            eprintln!("Error: {}", e);
+        }
+    }
+
+    /// Draws the mesh with the given transformation and material properties.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if a texture of the material is not loaded. Nothing is drawn in this case.
+    pub fn try_draw_mesh(&mut self, transform : &mut Transform3D, mesh : &mut Mesh, apply_local_matrix : bool) -> GfxResult<()> {
+        self.flush_sprite_batch();
+        let textures = [&mesh.material.base_color_texture, &mesh.material.normal_map];
+        if textures.iter().any(|texture| matches!(texture, Some(texture) if !matches!(texture, ImageTexture::Loaded { .. }))) {
+            return Err(GfxError::InvalidState(String::from("You try to render an invalid texture")));
+        }
 
         let m_mat = if apply_local_matrix {
             mesh.get_local_matrix() * transform.get_model_matrix()
@@ -1003,10 +2580,10 @@ impl RenderDevice {
         };
 
         unsafe {
-            gl::UniformMatrix4fv(self.get_uniform_location(self.shader_program, "p_mat"), 1, gl::FALSE, self.projection_matrix.as_ptr());
-            gl::UniformMatrix4fv(self.get_uniform_location(self.shader_program, "v_mat"), 1, gl::FALSE, self.view_matrix.as_ptr());
-            gl::UniformMatrix4fv(self.get_uniform_location(self.shader_program, "m_mat"), 1, gl::FALSE, m_mat.as_ptr());
-            gl::Uniform4f(self.get_uniform_location(self.shader_program, "vertexColor"), mesh.material.base_color_friction.x, mesh.material.base_color_friction.y, mesh.material.base_color_friction.z, mesh.material.base_color_friction.w);
+            self.upload_frame_uniforms();
+            self.set_legacy_camera_uniforms();
+            self.set_uniform_internal("m_mat", m_mat);
+            self.set_uniform_internal("vertexColor", mesh.material.base_color_friction);
 
             //bind the basecolor map
             if let Some(base_color_texture) = &mesh.material.base_color_texture {
@@ -1014,11 +2591,9 @@ impl RenderDevice {
                     ImageTexture::Loaded { id, dimensions:_ } => {
                         gl::ActiveTexture(gl::TEXTURE0);
                         gl::BindTexture(gl::TEXTURE_2D, *id);
-                        gl::Uniform1i(self.get_uniform_location(self.shader_program, "textureSampler"), 0);
-                    }
-                    _ => {
-                        println!("Error: You try to render an invalid texture");
+                        self.set_uniform_internal("textureSampler", 0);
                     }
+                    _ => {}
                 }
             }
 
@@ -1028,11 +2603,9 @@ impl RenderDevice {
                     ImageTexture::Loaded { id, dimensions: _ } => {
                         gl::ActiveTexture(gl::TEXTURE1);
                         gl::BindTexture(gl::TEXTURE_2D, *id);
-                        gl::Uniform1i(self.get_uniform_location(self.shader_program, "normalMapSampler"), 1);
-                    }
-                    _ => {
-                        println!("Error: You try to render an invalid texture");
+                        self.set_uniform_internal("normalMapSampler", 1);
                     }
+                    _ => {}
                 }
             }
 
@@ -1040,6 +2613,70 @@ impl RenderDevice {
             gl::DrawElements(gl::TRIANGLES, mesh.render_data.index_count as i32, gl::UNSIGNED_INT, std::ptr::null());
             gl::BindVertexArray(0);
         }
+        return Ok(());
+    }
+
+    /// Draws a cubemap as sky around the camera.
+    pub fn draw_skybox(&mut self, cubemap : &Cubemap, tint : Vector4<f32>) {
+        if let Err(e) = self.try_draw_skybox(cubemap, tint) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Draws a cubemap as sky around the camera, using the view and projection of the camera which was set with
+    /// `set_camera`, usually a `PerspectiveCamera`. The translation of the view is removed, so the sky never moves closer.
+    ///
+    /// # Arguments
+    /// - `cubemap`: The loaded cubemap.
+    /// - `tint`: The color the sky is multiplied with, e.g. to darken it at night.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the cubemap is not loaded.
+    /// - Returns `GfxError::MissingResource` if the render device is not initialized.
+    /// - Returns the errors of `try_build_shader_program` when the sky is drawn the first time.
+    ///
+    /// ### Notes:
+    /// - The sky lies on the far plane and doesn't write depth. Draw it after the opaque meshes, so it only covers the
+    ///   pixels which are still empty, or first, so everything else is drawn on top.
+    /// - The bound shader program and the render state are restored afterwards.
+    pub fn try_draw_skybox(&mut self, cubemap : &Cubemap, tint : Vector4<f32>) -> GfxResult<()> {
+        let Cubemap::Loaded { id, .. } = cubemap else {
+            return Err(GfxError::InvalidState(String::from("You try to draw an unloaded cubemap")));
+        };
+        let Some(shape) = self.render_shapes.get("skybox_shape").copied() else {
+            return Err(GfxError::MissingResource(String::from("Skybox shape not found!")));
+        };
+        if self.skybox_program.is_none() {
+            let mut shader_program = SkyboxShader::build_shader_program();
+            self.try_build_shader_program(&mut shader_program)?;
+            self.skybox_program = Some(shader_program);
+        }
+        let Some(ShaderProgram::Builded { program_id, .. }) = &self.skybox_program else {
+            return Err(GfxError::InvalidState(String::from("The skybox shader is not built.")));
+        };
+        let program_id = *program_id;
+        self.flush_sprite_batch();
+        self.upload_frame_uniforms();
+
+        let previous_program = self.shader_program;
+        //the depth of the sky is exactly 1.0, which passes against the cleared depth buffer
+        self.push_render_state(self.render_state.with_depth_test(true).with_depth_write(false).with_depth_function(DepthFunction::LessEqual).with_cull_mode(CullMode::None));
+        unsafe {
+            gl::UseProgram(program_id);
+            self.shader_program = program_id;
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, *id);
+            self.set_uniform_internal("cubemapSampler", 0);
+            self.set_uniform_internal("tint", tint);
+
+            gl::BindVertexArray(shape.vao);
+            gl::DrawElements(gl::TRIANGLES, shape.index_count as i32, gl::UNSIGNED_INT, std::ptr::null());
+            gl::BindVertexArray(0);
+            gl::UseProgram(previous_program);
+        }
+        self.shader_program = previous_program;
+        self.pop_render_state();
+        return Ok(());
     }
 
     /// Renders a framebuffer (render target) as a texture with the specified transformation and color tint.
@@ -1047,7 +2684,13 @@ impl RenderDevice {
     /// This function is often used for rendering off-screen content (like post-processing effects) to the screen.
     /// The texture is rendered with the provided transformation (position, rotation, scale) and optional color tint.
     pub fn draw_texture2drt<T : ITransform>(&mut self, transform : T, render_target: &mut RenderTarget, color: Vector4<f32>) {
-        self.draw_texture2di_internal(transform, render_target.texture_id, color, Vector4::new(1.0, 1.0, 1.0, 1.0), Vector2::new(1.0, 1.0));
+        if render_target.is_multisampled() {
+            eprintln!("A multisampled render target can't be drawn, resolve it with resolve_render_target first");
+            return;
+        }
+        if let Err(e) = self.draw_texture2di_internal(transform, render_target.texture_id, color, Vector4::new(1.0, 1.0, 1.0, 1.0), Vector2::new(1.0, 1.0)) {
+            eprintln!("{}", e);
+        }
     }
 
     /// Renders a 2D texture with the specified transformation and color tint.
@@ -1055,12 +2698,22 @@ impl RenderDevice {
     /// (position, rotation, scale) and optional color tint. This function ensures the texture is loaded before rendering.
     /// If the texture is not loaded, an error message will be logged.
     pub fn draw_texture2d<T: ITransform>(&mut self, transform : T, image_texture: &mut ImageTexture, color: Vector4<f32>) {
+        if let Err(e) = self.try_draw_texture2d(transform, image_texture, color) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Renders a 2D texture with the specified transformation and color tint.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the texture is not loaded.
+    pub fn try_draw_texture2d<T: ITransform>(&mut self, transform : T, image_texture: &mut ImageTexture, color: Vector4<f32>) -> GfxResult<()> {
         match image_texture {
             ImageTexture::Loaded { id, dimensions: _ } => {
-                self.draw_texture2di_internal(transform, *id, color, Vector4::new(1.0, 1.0, 1.0, 1.0), Vector2::new(1.0, 1.0));
+                return self.draw_texture2di_internal(transform, *id, color, Vector4::new(1.0, 1.0, 1.0, 1.0), Vector2::new(1.0, 1.0));
             }
             _ => {
-                eprintln!("Texture not loaded");
+                return Err(GfxError::InvalidState(String::from("Texture not loaded")));
             }
         }
     }
@@ -1071,24 +2724,44 @@ impl RenderDevice {
     /// Useful for rendering parts of a texture, such as sprite sheets or icons.
     /// If the texture is not loaded or an error occurs, a message will be logged.
     pub fn draw_sub_texture2d<T: ITransform>(&mut self, transform : T, point : Vector2<f32>, size : Vector2<f32>, image_texture: &mut ImageTexture, color: Vector4<f32>) {
+        if let Err(e) = self.try_draw_sub_texture2d(transform, point, size, image_texture, color) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Renders a specific subregion of a 2D texture to the screen with the specified transformation and color tint.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the texture is not loaded.
+    pub fn try_draw_sub_texture2d<T: ITransform>(&mut self, transform : T, point : Vector2<f32>, size : Vector2<f32>, image_texture: &mut ImageTexture, color: Vector4<f32>) -> GfxResult<()> {
         match image_texture {
             ImageTexture::Loaded { id, dimensions: _ } => {
-                self.draw_texture2di_internal(transform, *id, color, Vector4::new(size.x, size.y, point.x, point.y), Vector2::new(1.0, 1.0));
+                return self.draw_texture2di_internal(transform, *id, color, Vector4::new(size.x, size.y, point.x, point.y), Vector2::new(1.0, 1.0));
             }
             _ => {
-                eprintln!("Texture not loaded"); 
+                return Err(GfxError::InvalidState(String::from("Texture not loaded")));
             }
         }
     }
     
     /// Renders a 2D texture with the specified transformation, color tint, and UV transformation.
     pub fn draw_texture2d_uvt<T : ITransform>(&mut self, transform : T, image_texture : &mut ImageTexture, color : Vector4<f32>, uv_transform : Vector4<f32>, uv_scale : Vector2<f32>) {
+        if let Err(e) = self.try_draw_texture2d_uvt(transform, image_texture, color, uv_transform, uv_scale) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Renders a 2D texture with the specified transformation, color tint, and UV transformation.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the texture is not loaded.
+    pub fn try_draw_texture2d_uvt<T : ITransform>(&mut self, transform : T, image_texture : &mut ImageTexture, color : Vector4<f32>, uv_transform : Vector4<f32>, uv_scale : Vector2<f32>) -> GfxResult<()> {
         match image_texture {
             ImageTexture::Loaded { id, dimensions: _ } => {
-                self.draw_texture2di_internal(transform, *id, color, uv_transform, uv_scale);
+                return self.draw_texture2di_internal(transform, *id, color, uv_transform, uv_scale);
             }
             _ => {
-                eprintln!("Texture not loaded"); 
+                return Err(GfxError::InvalidState(String::from("Texture not loaded")));
             }
         }
     }
@@ -1099,30 +2772,50 @@ impl RenderDevice {
     /// Assumes the full texture is rendered, and the texture size is used to generate correct UV coordinates.
     /// If the texture is not loaded or there are issues with the texture, it may fail to render and log an error.
     pub fn draw_texture2di<T: ITransform>(&mut self, transform : T, texture_size : Vector2<f32>, texture_id: u32, color: Vector4<f32>) {
-        self.draw_texture2di_internal(transform, texture_id, color, Vector4::new(1.0, 1.0, 1.0, 1.0), texture_size);
+        if let Err(e) = self.draw_texture2di_internal(transform, texture_id, color, Vector4::new(1.0, 1.0, 1.0, 1.0), texture_size) {
+            eprintln!("{}", e);
+        }
     }
 
-    fn draw_texture2di_internal<T : ITransform>(&mut self, transform : T, texture_id : u32, color : Vector4<f32>, uv_transform : Vector4<f32>, uv_scale : Vector2<f32>) {
+    fn draw_texture2di_internal<T : ITransform>(&mut self, transform : T, texture_id : u32, color : Vector4<f32>, uv_transform : Vector4<f32>, uv_scale : Vector2<f32>) -> GfxResult<()> {
+        //custom programs are bound with a flush, so their sprites are simply drawn immediately
+        //sprites of a program which failed to build are not redirected to the sprite batch shader
+        let batchable = !self.failed_program_bound
+            && (self.shader_program == 0 || self.sprite_batch_programs.contains(&self.shader_program));
+        if batchable && self.sprite_batcher.as_ref().is_some_and(|sprite_batcher| !sprite_batcher.accepts(texture_id)) {
+            self.flush_sprite_batch();
+        }
+        if let Some(sprite_batcher) = self.sprite_batcher.as_mut().filter(|_| batchable) {
+            sprite_batcher.push(texture_id, self.layer, SpriteInstance {
+                transform: apply_layer(transform.get_model_matrix(), self.layer),
+                color,
+                uv_transform,
+                extras: Vector4::new(uv_scale.x, uv_scale.y, 0.0, 0.0)
+            });
+            return Ok(());
+        }
+
         let shape = self.render_shapes.get("texture_shape").copied();
         match shape {
             Some(shape) => {
                 unsafe {
-                    gl::UniformMatrix4fv(self.get_uniform_location(self.shader_program, "p_mat"), 1, gl::FALSE, self.projection_matrix.as_ptr());
-                    gl::UniformMatrix4fv(self.get_uniform_location(self.shader_program, "v_mat"), 1, gl::FALSE, self.view_matrix.as_ptr());
-                    gl::UniformMatrix4fv(self.get_uniform_location(self.shader_program, "m_mat"), 1, gl::FALSE, transform.get_model_matrix().as_ptr());
-                    gl::Uniform4f(self.get_uniform_location(self.shader_program, "vertexColor"), color.x, color.y, color.z, color.w);
-                    gl::Uniform4f(self.get_uniform_location(self.shader_program, "uvTransform"), uv_transform.x, uv_transform.y, uv_transform.z, uv_transform.w);
-                    gl::Uniform2f(self.get_uniform_location(self.shader_program, "uvScale"), uv_scale.x, uv_scale.y);
+                    self.upload_frame_uniforms();
+                    self.set_legacy_camera_uniforms();
+                    self.set_uniform_internal("m_mat", apply_layer(transform.get_model_matrix(), self.layer));
+                    self.set_uniform_internal("vertexColor", color);
+                    self.set_uniform_internal("uvTransform", uv_transform);
+                    self.set_uniform_internal("uvScale", uv_scale);
                     gl::ActiveTexture(gl::TEXTURE0);
                     gl::BindTexture(gl::TEXTURE_2D, texture_id);
-                    gl::Uniform1i(self.get_uniform_location(self.shader_program, "textureSampler"), 0);
+                    self.set_uniform_internal("textureSampler", 0);
                     gl::BindVertexArray(shape.vao);
                     gl::DrawElements(gl::TRIANGLES, shape.index_count as i32, gl::UNSIGNED_INT, std::ptr::null());
                     gl::BindVertexArray(0);
                 }
+                return Ok(());
             }
             None => {
-                eprintln!("Texture shape not found!");
+                return Err(GfxError::MissingResource(String::from("Texture shape not found!")));
             }
         }
     }
@@ -1133,82 +2826,107 @@ impl RenderDevice {
     /// Requires a loaded texture and a properly initialized `Texture2DBatch` with buffers for model transformations, colors, and UVs.
     /// Ideal for rendering large quantities of the same object with different properties (e.g., sprites, particles).
     pub fn draw_texture2d_batch(&mut self, image_texture: &mut ImageTexture, instance_batch : &mut Texture2DBatch) {
+        if let Err(e) = self.try_draw_texture2d_batch(image_texture, instance_batch) {
+            eprintln!("Error: {}", e);
+        }
+    }
+
+    /// Renders a batch of 2D textured instances with unique transformations, colors, and UV coordinates.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the texture or the batch is not loaded.
+    pub fn try_draw_texture2d_batch(&mut self, image_texture: &mut ImageTexture, instance_batch : &mut Texture2DBatch) -> GfxResult<()> {
         match image_texture {
             ImageTexture::Loaded { id, dimensions: _ } => {
-                self.draw_texture2di_batch(*id, instance_batch);
+                return self.try_draw_texture2di_batch(*id, instance_batch);
             }
             _ => {
-                eprintln!("Error: The provided ImageTexture is not loaded. Ensure that the ImageTexture is properly initialized and loaded before attempting to draw.");
+                return Err(GfxError::InvalidState(String::from("The provided ImageTexture is not loaded. Ensure that the ImageTexture is properly initialized and loaded before attempting to draw.")));
             }
         }
     }
 
+    /// Renders a batch of 2D textured instances with the given texture id.
     pub fn draw_texture2di_batch(&mut self, texture_id: u32, instance_batch : &mut Texture2DBatch) {
+        if let Err(e) = self.try_draw_texture2di_batch(texture_id, instance_batch) {
+            eprintln!("Error: {}", e);
+        }
+    }
+
+    /// Renders a batch of 2D textured instances with the given texture id.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the batch is not loaded.
+    pub fn try_draw_texture2di_batch(&mut self, texture_id: u32, instance_batch : &mut Texture2DBatch) -> GfxResult<()> {
+        return self.draw_texture2d_batch_internal(gl::TEXTURE_2D, texture_id, instance_batch);
+    }
+
+    /// Renders a batch of 2D textured instances, where every instance uses the layer of its `texture_index`.
+    /// Requires a bound shader which samples a `sampler2DArray`, like `Texture2DArrayBatchShader`.
+    pub fn draw_texture2d_array_batch(&mut self, texture_array : &mut TextureArray, instance_batch : &mut Texture2DBatch) {
+        if let Err(e) = self.try_draw_texture2d_array_batch(texture_array, instance_batch) {
+            eprintln!("Error: {}", e);
+        }
+    }
+
+    /// Renders a batch of 2D textured instances, where every instance uses the layer of its `texture_index`.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the texture array or the batch is not loaded.
+    pub fn try_draw_texture2d_array_batch(&mut self, texture_array : &mut TextureArray, instance_batch : &mut Texture2DBatch) -> GfxResult<()> {
+        match texture_array {
+            TextureArray::Loaded { id, .. } => {
+                return self.draw_texture2d_batch_internal(gl::TEXTURE_2D_ARRAY, *id, instance_batch);
+            }
+            _ => {
+                return Err(GfxError::InvalidState(String::from("The provided TextureArray is not loaded. Ensure that the TextureArray is properly loaded before attempting to draw.")));
+            }
+        }
+    }
+
+    fn draw_texture2d_batch_internal(&mut self, texture_target : u32, texture_id : u32, instance_batch : &mut Texture2DBatch) -> GfxResult<()> {
+        self.flush_sprite_batch();
+        self.sync_texture2d_batch(instance_batch);
         match instance_batch {
-            Texture2DBatch::Loaded { instances, mbo, cbo, uvto, exbo} => {
+            Texture2DBatch::Loaded { instances, instance_buffer, .. } => {
+                if instances.is_empty() {
+                    return Ok(());
+                }
                 let shape = self.render_shapes.get("texture_batch_shape").copied();
                 match shape {
                     Some(shape) => {
                         unsafe {
-                            let vec4_size = std::mem::size_of::<f32>() * 4;
-                            let matrix_stride = vec4_size * 4;
-
                             //Prepare shader
-                            gl::UniformMatrix4fv(self.get_uniform_location(self.shader_program, "p_mat"), 1, gl::FALSE, self.projection_matrix.as_ptr());
-                            gl::UniformMatrix4fv(self.get_uniform_location(self.shader_program, "v_mat"), 1, gl::FALSE, self.view_matrix.as_ptr());
+                            self.upload_frame_uniforms();
+                            self.set_legacy_camera_uniforms();
                             gl::ActiveTexture(gl::TEXTURE0);
-                            gl::BindTexture(gl::TEXTURE_2D, texture_id);
-                            gl::Uniform1i(self.get_uniform_location(self.shader_program, "textureSampler"), 0);
+                            gl::BindTexture(texture_target, texture_id);
+                            self.set_uniform_internal("textureSampler", 0);
 
-                            //Bind the vao
+                            //Bind the vao and assign the interleaved instance buffer to the instance attributes
                             gl::BindVertexArray(shape.vao);
-
-                            //Bind the color buffer and assign it to the location per instance
-                            gl::BindBuffer(gl::ARRAY_BUFFER, *cbo);
-                            gl::EnableVertexAttribArray(2);
-                            gl::VertexAttribPointer(2, 4, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
-                            gl::VertexAttribDivisor(2, 1);
-
-                            //Bind the transforms buffer and assign it to the locations per instance
-                            gl::BindBuffer(gl::ARRAY_BUFFER, *mbo);
-                            gl::EnableVertexAttribArray(3);
-                            gl::VertexAttribPointer(3, 4, gl::FLOAT, gl::FALSE, matrix_stride as i32, (0 * vec4_size) as *const _);
-                            gl::VertexAttribDivisor(3, 1);
-                            gl::EnableVertexAttribArray(4);
-                            gl::VertexAttribPointer(4, 4, gl::FLOAT, gl::FALSE, matrix_stride as i32, (1 * vec4_size) as *const _);
-                            gl::VertexAttribDivisor(4, 1);
-                            gl::EnableVertexAttribArray(5);
-                            gl::VertexAttribPointer(5, 4, gl::FLOAT, gl::FALSE, matrix_stride as i32, (2 * vec4_size) as *const _);
-                            gl::VertexAttribDivisor(5, 1);
-                            gl::EnableVertexAttribArray(6);
-                            gl::VertexAttribPointer(6, 4, gl::FLOAT, gl::FALSE, matrix_stride as i32, (3 * vec4_size) as *const _);
-                            gl::VertexAttribDivisor(6, 1);
-
-                            //Bind the uv transform buffer
-                            gl::BindBuffer(gl::ARRAY_BUFFER, *uvto);
-                            gl::EnableVertexAttribArray(7);
-                            gl::VertexAttribPointer(7, 4, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
-                            gl::VertexAttribDivisor(7, 1);
-
-                            //Bind the extra buffer
-                            gl::BindBuffer(gl::ARRAY_BUFFER, *exbo);
-                            gl::EnableVertexAttribArray(8);
-                            gl::VertexAttribPointer(8, 4, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
-                            gl::VertexAttribDivisor(8, 1);
+                            gl::BindBuffer(gl::ARRAY_BUFFER, *instance_buffer);
+                            let stride = std::mem::size_of::<Texture2DInstanceData>();
+                            enable_instance_attributes(stride, 0);
+                            //the user defined data
+                            gl::EnableVertexAttribArray(9);
+                            gl::VertexAttribPointer(9, 4, gl::FLOAT, gl::FALSE, stride as i32, std::mem::offset_of!(Texture2DInstanceData, custom) as *const _);
+                            gl::VertexAttribDivisor(9, 1);
 
                             //Draw the elements instanced
                             gl::DrawElementsInstanced(gl::TRIANGLES, shape.index_count as i32, gl::UNSIGNED_INT, std::ptr::null(), instances.len() as i32);
                             gl::BindVertexArray(0);
                             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
                         }
+                        return Ok(());
                     }
                     None => {
-                        eprintln!("Error: Render shape 'texture_batch_shape' not found. Ensure that the render_shapes map contains a valid entry for 'texture_batch_shape'.");
+                        return Err(GfxError::MissingResource(String::from("Render shape 'texture_batch_shape' not found. Ensure that the render_shapes map contains a valid entry for 'texture_batch_shape'.")));
                     }
                 }
             }
             _ => {
-                eprintln!("Error: The provided InstanceBatch is not loaded. Ensure that the InstanceBatch is properly initialized and loaded before attempting to draw.");
+                return Err(GfxError::InvalidState(String::from("The provided InstanceBatch is not loaded. Ensure that the InstanceBatch is properly initialized and loaded before attempting to draw.")));
             }
         }
     }
@@ -1219,13 +2937,15 @@ impl RenderDevice {
     /// If a character is missing in the font, a warning is logged.
     /// Ideal for rendering UI elements or small text batches.
     pub fn draw_text2d(&mut self, position : Vector2<f32>, text : &str, scale : f32, color : Vector4<f32>, font : &mut Font, alignment : TextAlignment) {
+        self.flush_sprite_batch();
         unsafe {
             let mut x = position.x;
             let y = position.y;
             let offset = font.get_offset(text, scale, alignment);
 
-            gl::UniformMatrix4fv(self.get_uniform_location(self.shader_program, "p_mat"), 1, gl::FALSE, self.projection_matrix.as_ptr());
-            gl::Uniform4f(self.get_uniform_location(self.shader_program, "vertexColor"), color.x, color.y, color.z, color.w);
+            self.upload_frame_uniforms();
+            self.set_legacy_camera_uniforms();
+            self.set_uniform_internal("vertexColor", color);
             gl::BindVertexArray(font.vao);
 
             for c in text.chars() {
@@ -1234,7 +2954,7 @@ impl RenderDevice {
                     Some(character) => {
                         gl::ActiveTexture(gl::TEXTURE0);
                         gl::BindTexture(gl::TEXTURE_2D, character.texture_id);
-                        gl::Uniform1i(self.get_uniform_location(self.shader_program, "textureSampler"), 0);
+                        self.set_uniform_internal("textureSampler", 0);
 
                         let xpos = (x + character.bearing.x as f32 * scale) + offset.x;
                         let ypos = (y - (character.size.y as f32 - character.bearing.y as f32) * scale) + offset.y;
@@ -1278,22 +2998,34 @@ impl RenderDevice {
     /// The function assumes the "rect_shape" is preloaded as a VAO and uses `gl::DrawElements` for rendering.
     /// Efficient for drawing background or UI elements with a single draw call.
     pub fn fill_rect<T: ITransform>(&mut self, transform : T, color : Vector4<f32>) {
+        if let Err(e) = self.try_fill_rect(transform, color) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Renders a filled rectangle at a specified position with a given color.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::MissingResource` if the render device is not initialized.
+    pub fn try_fill_rect<T: ITransform>(&mut self, transform : T, color : Vector4<f32>) -> GfxResult<()> {
+        self.flush_sprite_batch();
         let shape = self.render_shapes.get("rect_shape").copied();
         match shape {
             Some(shape) => {
                 unsafe {
-                    gl::UniformMatrix4fv(self.get_uniform_location(self.shader_program, "p_mat"), 1, gl::FALSE, self.projection_matrix.as_ptr());
-                    gl::UniformMatrix4fv(self.get_uniform_location(self.shader_program, "v_mat"), 1, gl::FALSE, self.view_matrix.as_ptr());
-                    gl::UniformMatrix4fv(self.get_uniform_location(self.shader_program, "m_mat"), 1, gl::FALSE, transform.get_model_matrix().as_ptr());
-                    gl::Uniform4f(self.get_uniform_location(self.shader_program, "vertexColor"), color.x, color.y, color.z, color.w);
-                    gl::Uniform1i(self.get_uniform_location(self.shader_program,"isSolid"), 1);
+                    self.upload_frame_uniforms();
+                    self.set_legacy_camera_uniforms();
+                    self.set_uniform_internal("m_mat", apply_layer(transform.get_model_matrix(), self.layer));
+                    self.set_uniform_internal("vertexColor", color);
+                    self.set_uniform_internal("isSolid", true);
                     gl::BindVertexArray(shape.vao);
                     gl::DrawElements(gl::TRIANGLES, shape.index_count as i32, gl::UNSIGNED_INT, std::ptr::null());
                     gl::BindVertexArray(0);
                 }
+                return Ok(());
             }
             None => {
-                eprintln!("Rect shape not found!");
+                return Err(GfxError::MissingResource(String::from("Rect shape not found!")));
             }
         }
     }
@@ -1304,25 +3036,37 @@ impl RenderDevice {
     /// The aspect ratio is calculated and passed to the shader for proper rendering adjustments.
     /// Efficient for rendering rectangles with borders, useful for UI elements or graphical shapes.
     pub fn draw_rect<T: ITransform>(&mut self, transform : T, line_width : f32, color : Vector4<f32>) {
+        if let Err(e) = self.try_draw_rect(transform, line_width, color) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Renders a rectangle with a border at a given position.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::MissingResource` if the render device is not initialized.
+    pub fn try_draw_rect<T: ITransform>(&mut self, transform : T, line_width : f32, color : Vector4<f32>) -> GfxResult<()> {
+        self.flush_sprite_batch();
         let shape = self.render_shapes.get("rect_shape").copied();
         match shape {
             Some(shape) => {
                 let aspect = transform.clone().get_aspect_ratio();                
                 unsafe {
-                    gl::UniformMatrix4fv(self.get_uniform_location(self.shader_program, "p_mat"), 1, gl::FALSE, self.projection_matrix.as_ptr());
-                    gl::UniformMatrix4fv(self.get_uniform_location(self.shader_program, "v_mat"), 1, gl::FALSE, self.view_matrix.as_ptr());
-                    gl::UniformMatrix4fv(self.get_uniform_location(self.shader_program, "m_mat"), 1, gl::FALSE, transform.get_model_matrix().as_ptr());
-                    gl::Uniform4f(self.get_uniform_location(self.shader_program, "vertexColor"), color.x, color.y, color.z, color.w);
-                    gl::Uniform1f(self.get_uniform_location(self.shader_program, "borderWidth"), line_width);
-                    gl::Uniform1f(self.get_uniform_location(self.shader_program, "aspect"), aspect);
-                    gl::Uniform1i(self.get_uniform_location(self.shader_program,"isSolid"), 0);
+                    self.upload_frame_uniforms();
+                    self.set_legacy_camera_uniforms();
+                    self.set_uniform_internal("m_mat", apply_layer(transform.get_model_matrix(), self.layer));
+                    self.set_uniform_internal("vertexColor", color);
+                    self.set_uniform_internal("borderWidth", line_width);
+                    self.set_uniform_internal("aspect", aspect);
+                    self.set_uniform_internal("isSolid", false);
                     gl::BindVertexArray(shape.vao);
                     gl::DrawElements(gl::TRIANGLES, shape.index_count as i32, gl::UNSIGNED_INT, std::ptr::null());
                     gl::BindVertexArray(0);
                 }
+                return Ok(());
             }
             None => {
-                eprintln!("Rect shape not found!");
+                return Err(GfxError::MissingResource(String::from("Rect shape not found!")));
             }
         }
     }
@@ -1333,6 +3077,21 @@ impl RenderDevice {
     /// Uses a single `gl::DrawElements` call to render the texture efficiently.
     /// Typically used for displaying textures from framebuffers to the screen.
     pub fn draw_render_target(&mut self, render_target : RenderTarget) {
+        if let Err(e) = self.try_draw_render_target(render_target) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Renders the texture from a `RenderTarget` as a fullscreen 2D quad.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the render target is multisampled.
+    /// - Returns `GfxError::MissingResource` if the render device is not initialized.
+    pub fn try_draw_render_target(&mut self, render_target : RenderTarget) -> GfxResult<()> {
+        if render_target.is_multisampled() {
+            return Err(GfxError::InvalidState(String::from("A multisampled render target can't be drawn, resolve it with resolve_render_target first")));
+        }
+        self.flush_sprite_batch();
         let shape = self.render_shapes.get("framebuffer_shape").copied();
         match shape {
            Some(shape) => {
@@ -1340,27 +3099,318 @@ impl RenderDevice {
                     gl::Enable(gl::TEXTURE_2D);
                     gl::ActiveTexture(gl::TEXTURE0);
                     gl::BindTexture(gl::TEXTURE_2D, render_target.texture_id);
-                    gl::Uniform1i(self.get_uniform_location(self.shader_program, "textureSampler"), 0);
+                    self.set_uniform_internal("textureSampler", 0);
                     gl::BindVertexArray(shape.vao);
-                    gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
+                    gl::DrawElements(gl::TRIANGLES, shape.index_count as i32, gl::UNSIGNED_INT, std::ptr::null());
                     gl::BindVertexArray(0);
                     gl::Disable(gl::TEXTURE_2D);
                 }
+                return Ok(());
            }
            None => {
-                eprintln!("Framebuffer shape not found!");
+                return Err(GfxError::MissingResource(String::from("Framebuffer shape not found!")));
            } 
         }
     }
 
+    /// Creates the internal render targets of a post process chain and prepares its effects.
+    pub fn load_post_process_chain(&mut self, chain : &mut PostProcessChain, width : u32, height : u32) {
+        if let Err(e) = self.try_load_post_process_chain(chain, width, height) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Creates the internal render targets of a post process chain with the given size, builds the programs of its effects
+    /// and loads their lookup tables.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the chain is already loaded.
+    /// - Returns the errors of `try_create_render_target_from_descriptor`, `try_build_shader_program` and `try_load_color_lut`.
+    ///   The created render targets are released.
+    pub fn try_load_post_process_chain(&mut self, chain : &mut PostProcessChain, width : u32, height : u32) -> GfxResult<()> {
+        if chain.is_loaded() {
+            return Err(GfxError::InvalidState(String::from("Post process chain allready loaded!")));
+        }
+        chain.size = Vector2::new(width, height);
+        for index in 0 .. 2 {
+            match self.try_create_post_process_target(chain.format, chain.size) {
+                Ok(render_target) => {
+                    chain.targets[index] = render_target;
+                }
+                Err(e) => {
+                    self.dispose_post_process_targets(chain);
+                    return Err(e);
+                }
+            }
+        }
+        if let Err(e) = self.prepare_post_process_effects(chain) {
+            self.dispose_post_process_targets(chain);
+            return Err(e);
+        }
+        return Ok(());
+    }
+
+    /// Resizes the internal render targets of a post process chain, e.g. after the window has been resized.
+    pub fn resize_post_process_chain(&mut self, chain : &mut PostProcessChain, width : u32, height : u32) {
+        chain.size = Vector2::new(width, height);
+        let bloom_size = bloom_target_size(chain.size);
+        for index in 0 .. 2 {
+            if chain.targets[index].framebuffer_id != 0 {
+                self.resize_render_target(&mut chain.targets[index], width, height);
+            }
+            if chain.bloom_targets[index].framebuffer_id != 0 {
+                self.resize_render_target(&mut chain.bloom_targets[index], bloom_size.x, bloom_size.y);
+            }
+        }
+    }
+
+    /// Applies the effects of a post process chain to a render target and draws the result into the bound framebuffer.
+    pub fn draw_post_process_chain(&mut self, chain : &mut PostProcessChain, source : &RenderTarget) {
+        if let Err(e) = self.try_draw_post_process_chain(chain, source) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Applies the effects of a post process chain to the first color attachment of the source and draws the result into
+    /// the bound framebuffer, covering the current viewport. Without effects the source is copied.
+    /// Effects which have been added since the chain was loaded are prepared first.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the chain is not loaded or the source is multisampled or has no color attachment.
+    /// - Returns `GfxError::MissingResource` if the render device is not initialized.
+    /// - Returns the errors of `try_build_shader_program` and `try_load_color_lut` for new effects.
+    ///
+    /// ### Notes:
+    /// - The output replaces the content of the framebuffer, blending and the depth test are disabled for all passes.
+    /// - The bound shader program and the render state are restored afterwards.
+    pub fn try_draw_post_process_chain(&mut self, chain : &mut PostProcessChain, source : &RenderTarget) -> GfxResult<()> {
+        if !chain.is_loaded() {
+            return Err(GfxError::InvalidState(String::from("You try to draw an unloaded post process chain!")));
+        }
+        if source.is_multisampled() || source.texture_id == 0 {
+            return Err(GfxError::InvalidState(String::from("The source of a post process chain requires a color attachment and can't be multisampled.")));
+        }
+        let Some(shape) = self.render_shapes.get("framebuffer_shape").copied() else {
+            return Err(GfxError::MissingResource(String::from("Framebuffer shape not found!")));
+        };
+        self.prepare_post_process_effects(chain)?;
+        self.flush_sprite_batch();
+        self.upload_frame_uniforms();
+
+        let previous_program = self.shader_program;
+        let mut previous_read_framebuffer : GLint = 0;
+        let mut previous_draw_framebuffer : GLint = 0;
+        unsafe {
+            gl::GetIntegerv(gl::READ_FRAMEBUFFER_BINDING, &mut previous_read_framebuffer);
+            gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut previous_draw_framebuffer);
+            //the passes cover the whole targets
+            gl::Disable(gl::SCISSOR_TEST);
+            gl::BindVertexArray(shape.vao);
+        }
+        self.push_render_state(RenderState::default().with_blend_mode(BlendMode::Disabled).with_depth_test(false).with_depth_write(false));
+
+        let output = (previous_draw_framebuffer as u32, self.viewport.size);
+        let mut input = (source.texture_id, source.size);
+        if chain.effects.is_empty() {
+            self.begin_post_process_pass(chain.program_id(PostProcessPass::Copy), output, input);
+            draw_post_process_quad(&shape);
+        }
+
+        let effect_count = chain.effects.len();
+        let mut next_target = 0;
+        for index in 0 .. effect_count {
+            let last = index + 1 == effect_count;
+            //the blur renders its horizontal pass into the next target first
+            let scratch = chain.targets[next_target];
+            if let PostProcessEffect::GaussianBlur { .. } = chain.effects[index] {
+                next_target ^= 1;
+            }
+            let target = chain.targets[next_target];
+            let destination = if last { output } else { (target.framebuffer_id, target.size) };
+
+            match &chain.effects[index] {
+                PostProcessEffect::GaussianBlur { radius } => {
+                    let program_id = chain.program_id(PostProcessPass::GaussianBlur);
+                    self.draw_blur_pass(&shape, program_id, (scratch.framebuffer_id, scratch.size), input, Vector2::new(1.0, 0.0), *radius);
+                    self.draw_blur_pass(&shape, program_id, destination, (scratch.texture_id, scratch.size), Vector2::new(0.0, 1.0), *radius);
+                }
+                PostProcessEffect::Bloom { threshold, intensity, radius } => {
+                    let [bloom_a, bloom_b] = chain.bloom_targets;
+                    self.begin_post_process_pass(chain.program_id(PostProcessPass::BloomThreshold), (bloom_a.framebuffer_id, bloom_a.size), input);
+                    self.set_uniform_internal("threshold", *threshold);
+                    draw_post_process_quad(&shape);
+
+                    let program_id = chain.program_id(PostProcessPass::GaussianBlur);
+                    self.draw_blur_pass(&shape, program_id, (bloom_b.framebuffer_id, bloom_b.size), (bloom_a.texture_id, bloom_a.size), Vector2::new(1.0, 0.0), *radius);
+                    self.draw_blur_pass(&shape, program_id, (bloom_a.framebuffer_id, bloom_a.size), (bloom_b.texture_id, bloom_b.size), Vector2::new(0.0, 1.0), *radius);
+
+                    self.begin_post_process_pass(chain.program_id(PostProcessPass::BloomComposite), destination, input);
+                    unsafe {
+                        gl::ActiveTexture(gl::TEXTURE1);
+                        gl::BindTexture(gl::TEXTURE_2D, bloom_a.texture_id);
+                    }
+                    self.set_uniform_internal("bloomSampler", 1);
+                    self.set_uniform_internal("intensity", *intensity);
+                    draw_post_process_quad(&shape);
+                }
+                PostProcessEffect::Fxaa => {
+                    self.begin_post_process_pass(chain.program_id(PostProcessPass::Fxaa), destination, input);
+                    draw_post_process_quad(&shape);
+                }
+                PostProcessEffect::Vignette { intensity, smoothness } => {
+                    self.begin_post_process_pass(chain.program_id(PostProcessPass::Vignette), destination, input);
+                    self.set_uniform_internal("intensity", *intensity);
+                    self.set_uniform_internal("smoothness", *smoothness);
+                    draw_post_process_quad(&shape);
+                }
+                PostProcessEffect::ColorGrading { lut, intensity } => {
+                    self.begin_post_process_pass(chain.program_id(PostProcessPass::ColorGrading), destination, input);
+                    if let ColorLut::Loaded { id, size } = lut {
+                        unsafe {
+                            gl::ActiveTexture(gl::TEXTURE1);
+                            gl::BindTexture(gl::TEXTURE_3D, *id);
+                        }
+                        self.set_uniform_internal("lutSampler", 1);
+                        self.set_uniform_internal("lutSize", *size as f32);
+                    }
+                    self.set_uniform_internal("intensity", *intensity);
+                    draw_post_process_quad(&shape);
+                    unsafe {
+                        gl::ActiveTexture(gl::TEXTURE1);
+                        gl::BindTexture(gl::TEXTURE_3D, 0);
+                    }
+                }
+                PostProcessEffect::ChromaticAberration { offset } => {
+                    self.begin_post_process_pass(chain.program_id(PostProcessPass::ChromaticAberration), destination, input);
+                    self.set_uniform_internal("offset", *offset);
+                    draw_post_process_quad(&shape);
+                }
+                PostProcessEffect::Custom(custom) => {
+                    if let ShaderProgram::Builded { program_id, .. } = custom.shader_program {
+                        self.begin_post_process_pass(program_id, destination, input);
+                        for (name, value) in custom.uniforms.iter() {
+                            let location = self.get_uniform_location(program_id, name);
+                            if location >= 0 {
+                                value.set_uniform(location);
+                            }
+                        }
+                        draw_post_process_quad(&shape);
+                    }
+                }
+            }
+
+            if !last {
+                input = (target.texture_id, target.size);
+                next_target ^= 1;
+            }
+        }
+
+        unsafe {
+            gl::BindVertexArray(0);
+            gl::ActiveTexture(gl::TEXTURE1);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, previous_read_framebuffer as GLuint);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, previous_draw_framebuffer as GLuint);
+            gl::Viewport(0, 0, self.viewport.size.x as i32, self.viewport.size.y as i32);
+            gl::UseProgram(previous_program);
+        }
+        self.shader_program = previous_program;
+        self.pop_render_state();
+        self.apply_clip_rect();
+        return Ok(());
+    }
+
+    /// Creates a render target without depth buffer for the passes of a post process chain.
+    fn try_create_post_process_target(&mut self, format : RenderTargetFormat, size : Vector2<u32>) -> GfxResult<RenderTarget> {
+        let descriptor = RenderTargetDescriptor::new(size.x, size.y)
+            .with_color_format(format)
+            .with_depth_attachment(DepthAttachment::None);
+        let render_target = self.try_create_render_target_from_descriptor(&descriptor)?;
+        unsafe {
+            //the blur samples beyond the edges
+            gl::BindTexture(gl::TEXTURE_2D, render_target.texture_id);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as GLint);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+        return Ok(render_target);
+    }
+
+    /// Builds the programs, loads the lookup tables and creates the bloom targets which the effects of the chain require.
+    fn prepare_post_process_effects(&mut self, chain : &mut PostProcessChain) -> GfxResult<()> {
+        let mut passes = vec![PostProcessPass::Copy];
+        for effect in chain.effects.iter_mut() {
+            passes.extend_from_slice(effect.passes());
+            match effect {
+                PostProcessEffect::ColorGrading { lut, .. } if !matches!(lut, ColorLut::Loaded { .. }) => {
+                    self.try_load_color_lut(lut)?;
+                }
+                PostProcessEffect::Custom(custom) if !matches!(custom.shader_program, ShaderProgram::Builded { .. }) => {
+                    self.try_build_shader_program(&mut custom.shader_program)?;
+                }
+                PostProcessEffect::Bloom { .. } if chain.bloom_targets[0].framebuffer_id == 0 => {
+                    let bloom_size = bloom_target_size(chain.size);
+                    for bloom_target in chain.bloom_targets.iter_mut() {
+                        *bloom_target = self.try_create_post_process_target(chain.format, bloom_size)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for pass in passes {
+            if let std::collections::hash_map::Entry::Vacant(entry) = chain.programs.entry(pass) {
+                let mut shader_program = pass.build_shader_program();
+                self.try_build_shader_program(&mut shader_program)?;
+                entry.insert(shader_program);
+            }
+        }
+        return Ok(());
+    }
+
+    /// Binds the destination, the program and the input texture of a post processing pass.
+    /// The destination and the input consist of a framebuffer or texture and its size.
+    fn begin_post_process_pass(&mut self, program_id : u32, destination : (u32, Vector2<u32>), input : (u32, Vector2<u32>)) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, destination.0);
+            gl::Viewport(0, 0, destination.1.x as GLsizei, destination.1.y as GLsizei);
+            gl::UseProgram(program_id);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, input.0);
+        }
+        self.shader_program = program_id;
+        self.set_uniform_internal("textureSampler", 0);
+        self.set_uniform_internal("texelSize", Vector2::new(1.0 / input.1.x.max(1) as f32, 1.0 / input.1.y.max(1) as f32));
+    }
+
+    /// Draws one direction of the separable gaussian blur with the bound framebuffer shape.
+    fn draw_blur_pass(&mut self, shape : &RenderData, program_id : u32, destination : (u32, Vector2<u32>), input : (u32, Vector2<u32>), direction : Vector2<f32>, radius : f32) {
+        self.begin_post_process_pass(program_id, destination, input);
+        self.set_uniform_internal("direction", direction);
+        self.set_uniform_internal("radius", radius);
+        draw_post_process_quad(shape);
+    }
+
     /// Disposes of all render shapes in `self.render_shapes`.
     /// This function will iterate over all the shapes in `self.render_shapes`
     /// and call `dispose_render_data` on each one to properly clean up the resources.
     pub fn dispose(&mut self) {
+        self.disable_sprite_batching();
+        if let Some(mut skybox_program) = self.skybox_program.take() {
+            self.dispose_shader_program(&mut skybox_program);
+        }
         let render_shapes = std::mem::take(&mut self.render_shapes);
         for (_key, mut value) in render_shapes {
             self.dispose_render_data(&mut value);
         }
+        let mut frame_buffer = self.frame_buffer;
+        self.dispose_uniform_buffer(&mut frame_buffer);
+        self.frame_buffer = frame_buffer;
+        let mut lights_buffer = self.lights_buffer;
+        self.dispose_uniform_buffer(&mut lights_buffer);
+        self.lights_buffer = lights_buffer;
     }
 
     /// Disposes of the resources associated with a specific `RenderData` object.
@@ -1403,6 +3453,7 @@ impl RenderDevice {
     /// Disposes of a loaded image texture by deleting the texture from OpenGL.
     /// If the texture is not loaded, it prints a message.
     pub fn dispose_image_texture(&mut self, image_texture: &mut ImageTexture) {
+        self.flush_sprite_batch();
         match image_texture {
             ImageTexture::Loaded { id, dimensions: _ } => {
                 unsafe {
@@ -1416,13 +3467,29 @@ impl RenderDevice {
         }
     }
 
+    /// Disposes of a texture array by deleting its texture.
+    pub fn dispose_texture_array(&mut self, texture_array : &mut TextureArray) {
+        self.flush_sprite_batch();
+        match texture_array {
+            TextureArray::Loaded { id, .. } => {
+                unsafe {
+                    gl::DeleteTextures(1, &*id);
+                }
+                *texture_array = TextureArray::Disposed;
+            }
+            _ => {
+                println!("Texture array was not loaded!")
+            }
+        }
+    }
+
     /// Disposes of a font by deleting its VAO, VBO, and associated textures.
     /// This ensures that all resources associated with the font are cleaned up.
     pub fn dispose_font(&mut self, font : &mut Font) {
         unsafe {
             gl::DeleteVertexArrays(1, &font.vao);
             gl::DeleteBuffers(1, &font.vbo);
-            for (_key, value) in &font.characters {
+            for value in font.characters.values() {
                 gl::DeleteTextures(1, &value.texture_id);
             }
         }
@@ -1431,11 +3498,20 @@ impl RenderDevice {
     /// Disposes of a render target by deleting its texture, renderbuffer, and framebuffer.
     /// This ensures that all resources associated with the render target are freed.
     pub fn dispose_render_target(&mut self, render_target : &mut RenderTarget) {
+        self.flush_sprite_batch();
         unsafe {
 
-            if render_target.texture_id != 0 {
-                gl::DeleteTextures(1, &render_target.texture_id);
-                render_target.texture_id = 0;
+            for texture_id in render_target.color_textures.iter_mut() {
+                if *texture_id != 0 {
+                    gl::DeleteTextures(1, texture_id);
+                    *texture_id = 0;
+                }
+            }
+            render_target.texture_id = 0;
+
+            if render_target.depth_texture_id != 0 {
+                gl::DeleteTextures(1, &render_target.depth_texture_id);
+                render_target.depth_texture_id = 0;
             }
 
             if render_target.renderbuffer_id != 0 {
@@ -1450,6 +3526,66 @@ impl RenderDevice {
         }
     }
 
+    /// Disposes the internal render targets and programs of a post process chain, as well as the lookup tables
+    /// and programs of its effects.
+    pub fn dispose_post_process_chain(&mut self, chain : &mut PostProcessChain) {
+        self.dispose_post_process_targets(chain);
+        for (_, mut shader_program) in chain.programs.drain() {
+            self.dispose_shader_program(&mut shader_program);
+        }
+        for effect in chain.effects.iter_mut() {
+            match effect {
+                PostProcessEffect::ColorGrading { lut, .. } => {
+                    self.dispose_color_lut(lut);
+                }
+                PostProcessEffect::Custom(custom) => {
+                    if matches!(custom.shader_program, ShaderProgram::Builded { .. }) {
+                        self.dispose_shader_program(&mut custom.shader_program);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn dispose_post_process_targets(&mut self, chain : &mut PostProcessChain) {
+        for render_target in chain.targets.iter_mut().chain(chain.bloom_targets.iter_mut()) {
+            self.dispose_render_target(render_target);
+        }
+    }
+
+    /// Disposes of a cubemap by deleting its texture.
+    pub fn dispose_cubemap(&mut self, cubemap : &mut Cubemap) {
+        self.flush_sprite_batch();
+        match cubemap {
+            Cubemap::Loaded { id, .. } => {
+                unsafe {
+                    gl::DeleteTextures(1, &*id);
+                }
+                *cubemap = Cubemap::Disposed;
+            }
+            _ => {
+                println!("Cubemap was not loaded!")
+            }
+        }
+    }
+
+    /// Disposes of a color lookup table by deleting its texture.
+    pub fn dispose_color_lut(&mut self, color_lut : &mut ColorLut) {
+        self.flush_sprite_batch();
+        match color_lut {
+            ColorLut::Loaded { id, .. } => {
+                unsafe {
+                    gl::DeleteTextures(1, &*id);
+                }
+                *color_lut = ColorLut::Disposed;
+            }
+            _ => {
+                println!("Color lut was not loaded!")
+            }
+        }
+    }
+
     pub fn dispose_material(&mut self, material : &mut Material) {
         //Dispose basecolor
         if let Some(mut base_color_texture) = material.base_color_texture.as_mut() {
@@ -1477,34 +3613,43 @@ impl RenderDevice {
     /// Disposes of a shader program by deleting its OpenGL program.
     /// This ensures that the shader program is properly cleaned up from the GPU.
     pub fn dispose_shader_program(&mut self, shader_program : &mut ShaderProgram) {
+        if let Err(e) = self.try_dispose_shader_program(shader_program) {
+            panic!("{}", e);
+        }
+    }
+
+    /// Disposes of a shader program by deleting its OpenGL program.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the program is already disposed.
+    pub fn try_dispose_shader_program(&mut self, shader_program : &mut ShaderProgram) -> GfxResult<()> {
         match shader_program {
-            ShaderProgram::Builded { program_id } => {
+            ShaderProgram::Builded { program_id, .. } => {
                 unsafe {
                     gl::DeleteProgram(*program_id);
-                    println!("Disposed shader programm {}", program_id);
+                    self.forget_shader_program(*program_id);
                     *shader_program = ShaderProgram::Disposed{};
                 }
+                return Ok(());
             }
             ShaderProgram::Disposed { } => {
-                panic!("You try to dispose an disposed shader!");
+                return Err(GfxError::InvalidState(String::from("You try to dispose an disposed shader!")));
             }
-            ShaderProgram::PreBuild { fragment_shader:_ , vertex_shader:_ } => {
+            ShaderProgram::PreBuild { .. } | ShaderProgram::Failed { .. } => {
                 *shader_program = ShaderProgram::Disposed{};
+                return Ok(());
             }
         }
     }
 
-    /// Disposes of a texture2D batch by deleting the associated buffers (MBO, CBO, UVTO).
+    /// Disposes of a texture2D batch by deleting the associated instance buffer.
     /// This ensures that the batch resources are cleaned up properly.
     pub fn dispose_texture2d_batch(&mut self, instance_batch : &mut Texture2DBatch) {
         match instance_batch {
-            Texture2DBatch::Loaded { instances, mbo, cbo, uvto, exbo } => {
+            Texture2DBatch::Loaded { instances, instance_buffer, .. } => {
                 unsafe {
-                    gl::DeleteBuffers(1, mbo);
-                    gl::DeleteBuffers(1, cbo);
-                    gl::DeleteBuffers(1, uvto);
-                    gl::DeleteBuffers(1, exbo);
-                    *instance_batch = Texture2DBatch::Disposed { instances: instances.clone() }
+                    gl::DeleteBuffers(1, instance_buffer);
+                    *instance_batch = Texture2DBatch::Disposed { instances: std::mem::take(instances) }
                 }
             }
             _ => {
@@ -1513,9 +3658,85 @@ impl RenderDevice {
         }
     }
 
+    /// Returns the number of samples per pixel of the bound framebuffer, 0 if it is not multisampled.
+    pub fn get_samples(&self) -> u32 {
+        let mut samples : GLint = 0;
+        unsafe {
+            gl::GetIntegerv(gl::SAMPLES, &mut samples);
+        }
+        return samples as u32;
+    }
+
     /// Retrieves the last OpenGL error code.
     /// This can be useful for debugging OpenGL calls and checking for errors.
     pub fn get_error(&self) -> u32 {
         unsafe { gl::GetError() }
     }
-}
\ No newline at end of file
+}
+
+/// Assigns the interleaved instance data within the bound array buffer to the instance attributes of the bound vertex array.
+/// The first instance starts at `base_offset` bytes within the buffer.
+///
+/// Expects the layout which `SpriteInstance` and `Texture2DInstanceData` share: the transform columns (locations 3 - 6),
+/// followed by the color (2), the uv transform (7) and the extras (8).
+unsafe fn enable_instance_attributes(stride : usize, base_offset : usize) {
+    let vec4_size = std::mem::size_of::<Vector4<f32>>();
+    //the columns of the transform matrix
+    for column in 0 .. 4 {
+        gl::EnableVertexAttribArray(3 + column);
+        gl::VertexAttribPointer(3 + column, 4, gl::FLOAT, gl::FALSE, stride as i32, (base_offset + column as usize * vec4_size) as *const _);
+        gl::VertexAttribDivisor(3 + column, 1);
+    }
+    //color, uv transform and extras
+    for (location, offset) in [(2, 4 * vec4_size), (7, 5 * vec4_size), (8, 6 * vec4_size)] {
+        gl::EnableVertexAttribArray(location);
+        gl::VertexAttribPointer(location, 4, gl::FLOAT, gl::FALSE, stride as i32, (base_offset + offset) as *const _);
+        gl::VertexAttribDivisor(location, 1);
+    }
+}
+
+/// Allocates the storage of all attachments of the render target with its current size.
+unsafe fn allocate_render_target_storage(render_target : &RenderTarget) {
+    let width = render_target.size.x as GLsizei;
+    let height = render_target.size.y as GLsizei;
+    let samples = render_target.samples as GLsizei;
+    let texture_target = render_target.texture_target();
+    for (texture_id, format) in render_target.color_texture_ids().iter().zip(render_target.color_formats.iter()) {
+        gl::BindTexture(texture_target, *texture_id);
+        if render_target.is_multisampled() {
+            gl::TexImage2DMultisample(texture_target, samples, format.internal_format(), width, height, gl::TRUE);
+        }
+        else {
+            let (pixel_format, pixel_type) = format.pixel_format();
+            gl::TexImage2D(texture_target, 0, format.internal_format() as GLint, width, height, 0, pixel_format, pixel_type, std::ptr::null());
+        }
+    }
+    if render_target.depth_texture_id != 0 {
+        gl::BindTexture(texture_target, render_target.depth_texture_id);
+        if render_target.is_multisampled() {
+            gl::TexImage2DMultisample(texture_target, samples, gl::DEPTH24_STENCIL8, width, height, gl::TRUE);
+        }
+        else {
+            gl::TexImage2D(texture_target, 0, gl::DEPTH24_STENCIL8 as GLint, width, height, 0, gl::DEPTH_STENCIL, gl::UNSIGNED_INT_24_8, std::ptr::null());
+        }
+    }
+    gl::BindTexture(texture_target, 0);
+
+    if render_target.renderbuffer_id != 0 {
+        gl::BindRenderbuffer(gl::RENDERBUFFER, render_target.renderbuffer_id);
+        gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, samples, gl::DEPTH24_STENCIL8, width, height);
+        gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+    }
+}
+
+/// Draws the framebuffer shape, which has to be bound, as the fullscreen quad of a post processing pass.
+fn draw_post_process_quad(shape : &RenderData) {
+    unsafe {
+        gl::DrawElements(gl::TRIANGLES, shape.index_count as i32, gl::UNSIGNED_INT, std::ptr::null());
+    }
+}
+
+/// Returns the size of the half resolution bloom targets.
+fn bloom_target_size(size : Vector2<u32>) -> Vector2<u32> {
+    return Vector2::new((size.x / 2).max(1), (size.y / 2).max(1));
+}
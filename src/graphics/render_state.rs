@@ -0,0 +1,198 @@
+/// The comparison of the depth test, see `RenderDevice::set_depth_function`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DepthFunction {
+    Never = gl::NEVER as isize,
+    Less = gl::LESS as isize,
+    Equal = gl::EQUAL as isize,
+    LessEqual = gl::LEQUAL as isize,
+    Greater = gl::GREATER as isize,
+    NotEqual = gl::NOTEQUAL as isize,
+    GreaterEqual = gl::GEQUAL as isize,
+    Always = gl::ALWAYS as isize
+}
+
+/// Selects which pixels are drawn while a stencil mask is active, see `RenderDevice::begin_mask`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MaskMode {
+    /// Draws only inside of the mask.
+    Inside,
+    /// Draws only outside of the mask.
+    Outside
+}
+
+/// Describes how the output of a draw is combined with the pixels of the framebuffer.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlendMode {
+    /// The output replaces the framebuffer pixels.
+    Disabled,
+    /// Classic alpha blending for colors with straight alpha.
+    Alpha,
+    /// Alpha blending for colors which are already multiplied with their alpha, e.g. composited render targets.
+    Premultiplied,
+    /// Adds the output weighted by its alpha, for glows, fire and other light effects.
+    Additive,
+    /// Multiplies the framebuffer with the output, for shadows and tinting.
+    Multiply,
+    /// Brightens the framebuffer by the inverse multiplication of both colors.
+    Screen
+}
+
+impl BlendMode {
+    /// Returns the source and destination factors for the color and the alpha channel,
+    /// or `None` if blending is disabled.
+    pub fn blend_factors(self) -> Option<(u32, u32, u32, u32)> {
+        match self {
+            BlendMode::Disabled => None,
+            BlendMode::Alpha => Some((gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA, gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA)),
+            BlendMode::Premultiplied => Some((gl::ONE, gl::ONE_MINUS_SRC_ALPHA, gl::ONE, gl::ONE_MINUS_SRC_ALPHA)),
+            BlendMode::Additive => Some((gl::SRC_ALPHA, gl::ONE, gl::ZERO, gl::ONE)),
+            BlendMode::Multiply => Some((gl::DST_COLOR, gl::ONE_MINUS_SRC_ALPHA, gl::ZERO, gl::ONE)),
+            BlendMode::Screen => Some((gl::ONE, gl::ONE_MINUS_SRC_COLOR, gl::ZERO, gl::ONE))
+        }
+    }
+}
+
+/// The faces which are discarded before rasterization.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CullMode {
+    None,
+    Back,
+    Front
+}
+
+/// The color channels which are written by a draw.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ColorWriteMask {
+    pub red : bool,
+    pub green : bool,
+    pub blue : bool,
+    pub alpha : bool,
+}
+
+impl ColorWriteMask {
+    /// Writes all channels.
+    pub const ALL : ColorWriteMask = ColorWriteMask { red: true, green: true, blue: true, alpha: true };
+    /// Writes no channel, e.g. to fill only the depth or stencil buffer.
+    pub const NONE : ColorWriteMask = ColorWriteMask { red: false, green: false, blue: false, alpha: false };
+}
+
+impl Default for ColorWriteMask {
+    fn default() -> Self {
+        ColorWriteMask::ALL
+    }
+}
+
+/// The fixed function state which is used by draw calls.
+///
+/// The default state matches the state after `RenderDevice::init`: alpha blending, depth test and depth writes with
+/// `DepthFunction::Less`, no face culling and all color channels written.
+///
+/// # Example
+/// ```ignore
+/// render_device.push_render_state(RenderState::default().with_blend_mode(BlendMode::Additive).with_depth_write(false));
+/// render_device.draw_texture2d(transform, &mut glow_texture, color);
+/// render_device.pop_render_state();
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RenderState {
+    pub blend_mode : BlendMode,
+    pub depth_test : bool,
+    pub depth_write : bool,
+    pub depth_function : DepthFunction,
+    pub cull_mode : CullMode,
+    pub color_write_mask : ColorWriteMask,
+}
+
+impl Default for RenderState {
+    fn default() -> Self {
+        RenderState {
+            blend_mode: BlendMode::Alpha,
+            depth_test: true,
+            depth_write: true,
+            depth_function: DepthFunction::Less,
+            cull_mode: CullMode::None,
+            color_write_mask: ColorWriteMask::ALL
+        }
+    }
+}
+
+impl RenderState {
+
+    /// Returns the state with the given blend mode.
+    pub fn with_blend_mode(mut self, blend_mode : BlendMode) -> RenderState {
+        self.blend_mode = blend_mode;
+        return self;
+    }
+
+    /// Returns the state with the depth test enabled or disabled.
+    pub fn with_depth_test(mut self, depth_test : bool) -> RenderState {
+        self.depth_test = depth_test;
+        return self;
+    }
+
+    /// Returns the state with depth writes enabled or disabled.
+    pub fn with_depth_write(mut self, depth_write : bool) -> RenderState {
+        self.depth_write = depth_write;
+        return self;
+    }
+
+    /// Returns the state with the given depth function.
+    pub fn with_depth_function(mut self, depth_function : DepthFunction) -> RenderState {
+        self.depth_function = depth_function;
+        return self;
+    }
+
+    /// Returns the state with the given face culling.
+    pub fn with_cull_mode(mut self, cull_mode : CullMode) -> RenderState {
+        self.cull_mode = cull_mode;
+        return self;
+    }
+
+    /// Returns the state with the given color write mask.
+    pub fn with_color_write_mask(mut self, color_write_mask : ColorWriteMask) -> RenderState {
+        self.color_write_mask = color_write_mask;
+        return self;
+    }
+
+    /// Applies the state to the current OpenGL context.
+    pub(crate) fn apply(&self) {
+        unsafe {
+            match self.blend_mode.blend_factors() {
+                Some((src_color, dst_color, src_alpha, dst_alpha)) => {
+                    gl::Enable(gl::BLEND);
+                    gl::BlendEquation(gl::FUNC_ADD);
+                    gl::BlendFuncSeparate(src_color, dst_color, src_alpha, dst_alpha);
+                }
+                None => {
+                    gl::Disable(gl::BLEND);
+                }
+            }
+
+            if self.depth_test {
+                gl::Enable(gl::DEPTH_TEST);
+            }
+            else {
+                gl::Disable(gl::DEPTH_TEST);
+            }
+            gl::DepthMask(self.depth_write as u8);
+            gl::DepthFunc(self.depth_function as u32);
+
+            match self.cull_mode {
+                CullMode::None => {
+                    gl::Disable(gl::CULL_FACE);
+                }
+                CullMode::Back => {
+                    gl::Enable(gl::CULL_FACE);
+                    gl::CullFace(gl::BACK);
+                }
+                CullMode::Front => {
+                    gl::Enable(gl::CULL_FACE);
+                    gl::CullFace(gl::FRONT);
+                }
+            }
+
+            let mask = self.color_write_mask;
+            gl::ColorMask(mask.red as u8, mask.green as u8, mask.blue as u8, mask.alpha as u8);
+        }
+    }
+}
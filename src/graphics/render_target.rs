@@ -1,9 +1,194 @@
 use nalgebra::Vector2;
 
+use super::image_texture::ColorMode;
+
+/// The maximum number of color attachments of a render target. OpenGL guarantees at least 8 draw buffers.
+pub const MAX_COLOR_ATTACHMENTS : usize = 8;
+
+/// The pixel format of a color attachment.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum RenderTargetFormat {
+    /// 8 bit per channel, clamped to [0, 1].
+    #[default]
+    RGBA8,
+    /// 16 bit floats per channel, e.g. for HDR rendering.
+    RGBA16F,
+    /// 32 bit floats per channel, e.g. for positions within a G-buffer.
+    RGBA32F,
+    /// Packed floats without alpha, a compact HDR format.
+    R11G11B10F
+}
+
+impl RenderTargetFormat {
+    /// Returns the internal format of the texture.
+    pub fn internal_format(self) -> u32 {
+        match self {
+            RenderTargetFormat::RGBA8 => gl::RGBA8,
+            RenderTargetFormat::RGBA16F => gl::RGBA16F,
+            RenderTargetFormat::RGBA32F => gl::RGBA32F,
+            RenderTargetFormat::R11G11B10F => gl::R11F_G11F_B10F
+        }
+    }
+
+    /// Returns the pixel format and type which are used to allocate the texture.
+    pub fn pixel_format(self) -> (u32, u32) {
+        match self {
+            RenderTargetFormat::RGBA8 => (gl::RGBA, gl::UNSIGNED_BYTE),
+            RenderTargetFormat::RGBA16F => (gl::RGBA, gl::HALF_FLOAT),
+            RenderTargetFormat::RGBA32F => (gl::RGBA, gl::FLOAT),
+            RenderTargetFormat::R11G11B10F => (gl::RGB, gl::FLOAT)
+        }
+    }
+
+    /// Returns true if the format stores floats which are not clamped to [0, 1].
+    pub fn is_float(self) -> bool {
+        return self != RenderTargetFormat::RGBA8;
+    }
+
+    /// Returns the color mode in which the pixels are read back, float formats are read as 32 bit floats.
+    pub fn read_color_mode(self) -> ColorMode {
+        match self {
+            RenderTargetFormat::RGBA8 => ColorMode::RGBA,
+            RenderTargetFormat::RGBA16F | RenderTargetFormat::RGBA32F => ColorMode::RGBA32F,
+            RenderTargetFormat::R11G11B10F => ColorMode::RGB32F
+        }
+    }
+}
+
+/// The depth and stencil buffer of a render target.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum DepthAttachment {
+    /// No depth and stencil buffer, e.g. for post processing passes.
+    None,
+    /// A depth-stencil renderbuffer which can't be sampled.
+    #[default]
+    Renderbuffer,
+    /// A depth-stencil texture which can be sampled after rendering, e.g. for shadow maps.
+    Texture
+}
+
+/// Describes the attachments of a render target, see `RenderDevice::create_render_target_from_descriptor`.
+///
+/// The default descriptor matches `RenderDevice::create_render_target`: one RGBA8 color attachment, a
+/// depth-stencil renderbuffer and no multisampling.
+///
+/// # Example
+/// ```ignore
+/// let descriptor = RenderTargetDescriptor::new(1280, 720)
+///     .with_color_formats(&[RenderTargetFormat::RGBA16F, RenderTargetFormat::RGBA16F])
+///     .with_depth_attachment(DepthAttachment::Texture);
+/// let g_buffer = render_device.create_render_target_from_descriptor(&descriptor);
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RenderTargetDescriptor {
+    pub size : Vector2<u32>,
+    /// The format of each color attachment. Can be empty for depth only targets.
+    pub color_formats : Vec<RenderTargetFormat>,
+    pub depth_attachment : DepthAttachment,
+    /// The number of samples per pixel, 0 disables multisampling.
+    pub samples : u32,
+}
+
+impl RenderTargetDescriptor {
+
+    /// Creates a descriptor with one RGBA8 color attachment and a depth-stencil renderbuffer.
+    pub fn new(width : u32, height : u32) -> RenderTargetDescriptor {
+        return RenderTargetDescriptor {
+            size: Vector2::new(width, height),
+            color_formats: vec![RenderTargetFormat::RGBA8],
+            depth_attachment: DepthAttachment::Renderbuffer,
+            samples: 0
+        };
+    }
+
+    /// Returns the descriptor with the given color attachments.
+    pub fn with_color_formats(mut self, color_formats : &[RenderTargetFormat]) -> RenderTargetDescriptor {
+        self.color_formats = color_formats.to_vec();
+        return self;
+    }
+
+    /// Returns the descriptor with a single color attachment of the given format.
+    pub fn with_color_format(self, color_format : RenderTargetFormat) -> RenderTargetDescriptor {
+        return self.with_color_formats(&[color_format]);
+    }
+
+    /// Returns the descriptor with the given depth attachment.
+    pub fn with_depth_attachment(mut self, depth_attachment : DepthAttachment) -> RenderTargetDescriptor {
+        self.depth_attachment = depth_attachment;
+        return self;
+    }
+
+    /// Returns the descriptor with the given number of samples per pixel, 0 disables multisampling.
+    ///
+    /// ### Notes:
+    /// - A multisampled target can't be sampled, resolve it with `RenderDevice::resolve_render_target` first.
+    pub fn with_samples(mut self, samples : u32) -> RenderTargetDescriptor {
+        self.samples = samples;
+        return self;
+    }
+}
+
+/// A framebuffer with its attachments, created by `RenderDevice::create_render_target`.
+///
+/// `texture_id` is the first color attachment, the other ones are within `color_textures`.
 #[derive(Clone, Copy, Default)]
 pub struct RenderTarget {
     pub size: Vector2<u32>,
     pub framebuffer_id: u32,
     pub texture_id: u32,
+    /// The depth-stencil renderbuffer, 0 if the target has no `DepthAttachment::Renderbuffer`.
     pub renderbuffer_id: u32,
+    pub color_textures : [u32; MAX_COLOR_ATTACHMENTS],
+    pub color_formats : [RenderTargetFormat; MAX_COLOR_ATTACHMENTS],
+    pub color_attachment_count : usize,
+    pub depth_attachment : DepthAttachment,
+    /// The depth-stencil texture, 0 if the target has no `DepthAttachment::Texture`.
+    pub depth_texture_id : u32,
+    /// The number of samples per pixel, 0 if the target is not multisampled.
+    pub samples : u32,
+}
+
+impl RenderTarget {
+
+    /// Returns the texture of the color attachment with the given index.
+    pub fn color_texture(&self, index : usize) -> Option<u32> {
+        return self.color_texture_ids().get(index).copied();
+    }
+
+    /// Returns the textures of all color attachments.
+    pub fn color_texture_ids(&self) -> &[u32] {
+        return &self.color_textures[.. self.color_attachment_count];
+    }
+
+    /// Returns the depth texture, if the target has one.
+    pub fn depth_texture(&self) -> Option<u32> {
+        if self.depth_texture_id == 0 {
+            return None;
+        }
+        return Some(self.depth_texture_id);
+    }
+
+    /// Returns true if the target has multisampled attachments, which is the case for any sample count above 0.
+    pub fn is_multisampled(&self) -> bool {
+        return self.samples > 0;
+    }
+
+    /// Returns the texture target of the attachments, `GL_TEXTURE_2D_MULTISAMPLE` for multisampled targets.
+    pub fn texture_target(&self) -> u32 {
+        if self.is_multisampled() {
+            return gl::TEXTURE_2D_MULTISAMPLE;
+        }
+        return gl::TEXTURE_2D;
+    }
+
+    /// Returns the descriptor the target has been created with, using its current size.
+    /// A resolve target for a multisampled target can be created with `descriptor().with_samples(0)`.
+    pub fn descriptor(&self) -> RenderTargetDescriptor {
+        return RenderTargetDescriptor {
+            size: self.size,
+            color_formats: self.color_formats[.. self.color_attachment_count].to_vec(),
+            depth_attachment: self.depth_attachment,
+            samples: self.samples
+        };
+    }
 }
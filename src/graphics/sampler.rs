@@ -0,0 +1,205 @@
+use std::ffi::{c_char, CStr};
+
+use gl::types::GLint;
+use nalgebra::Vector4;
+
+/// `GL_TEXTURE_MAX_ANISOTROPY` of OpenGL 4.6 and `GL_EXT_texture_filter_anisotropic`.
+const TEXTURE_MAX_ANISOTROPY : u32 = 0x84FE;
+/// `GL_MAX_TEXTURE_MAX_ANISOTROPY` of OpenGL 4.6 and `GL_EXT_texture_filter_anisotropic`.
+const MAX_TEXTURE_MAX_ANISOTROPY : u32 = 0x84FF;
+
+/// The filter which is used to sample a texture level.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TextureFilter {
+    /// Uses the closest texel, keeps pixel art sharp.
+    Nearest,
+    /// Interpolates between the closest texels.
+    Linear
+}
+
+/// The filter which is used between the mipmap levels of a texture.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MipmapFilter {
+    /// The texture has no mipmaps.
+    None,
+    /// Uses the closest mipmap level.
+    Nearest,
+    /// Interpolates between the two closest mipmap levels (trilinear filtering).
+    Linear
+}
+
+/// The handling of texture coordinates outside of [0, 1].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TextureWrap {
+    Repeat = gl::REPEAT as isize,
+    MirroredRepeat = gl::MIRRORED_REPEAT as isize,
+    ClampToEdge = gl::CLAMP_TO_EDGE as isize,
+    /// Uses the border color of the sampler.
+    ClampToBorder = gl::CLAMP_TO_BORDER as isize
+}
+
+/// Describes how a texture is sampled, see `RenderDevice::load_texture_with_sampler` and `RenderDevice::set_texture_sampler`.
+///
+/// The default sampler matches the previous behaviour of `RenderDevice::load_texture`: linear filtering, repeated texture
+/// coordinates and no mipmaps.
+///
+/// # Example
+/// ```ignore
+/// //sharp pixel art
+/// render_device.load_texture_with_sampler(&mut sprite, &SamplerDescriptor::nearest());
+/// //smooth ground textures at a flat angle
+/// render_device.load_texture_with_sampler(&mut ground, &SamplerDescriptor::default().with_mipmaps(MipmapFilter::Linear).with_anisotropy(8.0));
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SamplerDescriptor {
+    pub min_filter : TextureFilter,
+    pub mag_filter : TextureFilter,
+    /// Mipmaps are generated if the filter is not `MipmapFilter::None`.
+    pub mipmap_filter : MipmapFilter,
+    pub wrap_u : TextureWrap,
+    pub wrap_v : TextureWrap,
+    /// The wrap along the third axis of 3D textures and cubemaps.
+    pub wrap_w : TextureWrap,
+    /// The color outside of the texture for `TextureWrap::ClampToBorder`.
+    pub border_color : Vector4<f32>,
+    /// The maximum anisotropy, 1.0 disables anisotropic filtering. Clamped to the maximum of the driver.
+    pub max_anisotropy : f32,
+}
+
+impl Default for SamplerDescriptor {
+    fn default() -> Self {
+        SamplerDescriptor {
+            min_filter: TextureFilter::Linear,
+            mag_filter: TextureFilter::Linear,
+            mipmap_filter: MipmapFilter::None,
+            wrap_u: TextureWrap::Repeat,
+            wrap_v: TextureWrap::Repeat,
+            wrap_w: TextureWrap::Repeat,
+            border_color: Vector4::new(0.0, 0.0, 0.0, 0.0),
+            max_anisotropy: 1.0
+        }
+    }
+}
+
+impl SamplerDescriptor {
+
+    /// Returns the default sampler with nearest filtering, e.g. for pixel art.
+    pub fn nearest() -> SamplerDescriptor {
+        return SamplerDescriptor::default().with_filter(TextureFilter::Nearest, TextureFilter::Nearest);
+    }
+
+    /// Returns the sampler with the given minification and magnification filter.
+    pub fn with_filter(mut self, min_filter : TextureFilter, mag_filter : TextureFilter) -> SamplerDescriptor {
+        self.min_filter = min_filter;
+        self.mag_filter = mag_filter;
+        return self;
+    }
+
+    /// Returns the sampler with mipmaps, which are filtered with the given filter. `MipmapFilter::None` disables the mipmaps.
+    pub fn with_mipmaps(mut self, mipmap_filter : MipmapFilter) -> SamplerDescriptor {
+        self.mipmap_filter = mipmap_filter;
+        return self;
+    }
+
+    /// Returns the sampler with the given wrap along all axes.
+    pub fn with_wrap(mut self, wrap : TextureWrap) -> SamplerDescriptor {
+        self.wrap_u = wrap;
+        self.wrap_v = wrap;
+        self.wrap_w = wrap;
+        return self;
+    }
+
+    /// Returns the sampler with the given wrap along the horizontal and the vertical axis.
+    pub fn with_wrap_uv(mut self, wrap_u : TextureWrap, wrap_v : TextureWrap) -> SamplerDescriptor {
+        self.wrap_u = wrap_u;
+        self.wrap_v = wrap_v;
+        return self;
+    }
+
+    /// Returns the sampler with the given border color.
+    pub fn with_border_color(mut self, border_color : Vector4<f32>) -> SamplerDescriptor {
+        self.border_color = border_color;
+        return self;
+    }
+
+    /// Returns the sampler with the given maximum anisotropy, e.g. 4.0 or 16.0.
+    pub fn with_anisotropy(mut self, max_anisotropy : f32) -> SamplerDescriptor {
+        self.max_anisotropy = max_anisotropy;
+        return self;
+    }
+
+    /// Returns true if the texture requires mipmaps.
+    pub fn has_mipmaps(&self) -> bool {
+        return self.mipmap_filter != MipmapFilter::None;
+    }
+
+    /// Returns the OpenGL minification filter, which combines the min filter and the mipmap filter.
+    pub fn gl_min_filter(&self) -> u32 {
+        match (self.min_filter, self.mipmap_filter) {
+            (TextureFilter::Nearest, MipmapFilter::None) => gl::NEAREST,
+            (TextureFilter::Linear, MipmapFilter::None) => gl::LINEAR,
+            (TextureFilter::Nearest, MipmapFilter::Nearest) => gl::NEAREST_MIPMAP_NEAREST,
+            (TextureFilter::Linear, MipmapFilter::Nearest) => gl::LINEAR_MIPMAP_NEAREST,
+            (TextureFilter::Nearest, MipmapFilter::Linear) => gl::NEAREST_MIPMAP_LINEAR,
+            (TextureFilter::Linear, MipmapFilter::Linear) => gl::LINEAR_MIPMAP_LINEAR
+        }
+    }
+
+    /// Returns the OpenGL magnification filter.
+    pub fn gl_mag_filter(&self) -> u32 {
+        match self.mag_filter {
+            TextureFilter::Nearest => gl::NEAREST,
+            TextureFilter::Linear => gl::LINEAR
+        }
+    }
+
+    /// Applies the sampler to the texture which is bound to the given target and generates its mipmaps if required.
+    ///
+    /// # Arguments
+    /// - `target`: The texture target, e.g. `gl::TEXTURE_2D`.
+    /// - `max_supported_anisotropy`: The limit of the driver from `query_max_anisotropy`.
+    pub(crate) fn apply(&self, target : u32, max_supported_anisotropy : f32) {
+        unsafe {
+            if self.has_mipmaps() {
+                gl::GenerateMipmap(target);
+            }
+            gl::TexParameteri(target, gl::TEXTURE_MIN_FILTER, self.gl_min_filter() as GLint);
+            gl::TexParameteri(target, gl::TEXTURE_MAG_FILTER, self.gl_mag_filter() as GLint);
+            gl::TexParameteri(target, gl::TEXTURE_WRAP_S, self.wrap_u as GLint);
+            gl::TexParameteri(target, gl::TEXTURE_WRAP_T, self.wrap_v as GLint);
+            gl::TexParameteri(target, gl::TEXTURE_WRAP_R, self.wrap_w as GLint);
+            gl::TexParameterfv(target, gl::TEXTURE_BORDER_COLOR, self.border_color.as_ptr());
+            if max_supported_anisotropy > 1.0 {
+                gl::TexParameterf(target, TEXTURE_MAX_ANISOTROPY, self.max_anisotropy.clamp(1.0, max_supported_anisotropy));
+            }
+        }
+    }
+}
+
+/// Returns the maximum anisotropy of the driver, or 1.0 if anisotropic filtering is not supported.
+/// The limit is only queried if OpenGL 4.6 or one of the anisotropic filtering extensions is available,
+/// so the query never raises an OpenGL error.
+pub(crate) fn query_max_anisotropy() -> f32 {
+    unsafe {
+        let (mut major, mut minor) = (0, 0);
+        gl::GetIntegerv(gl::MAJOR_VERSION, &mut major);
+        gl::GetIntegerv(gl::MINOR_VERSION, &mut minor);
+        let mut supported = (major, minor) >= (4, 6);
+        if !supported {
+            let mut extension_count = 0;
+            gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut extension_count);
+            supported = (0 .. extension_count.max(0) as u32).any(|index| {
+                let name = gl::GetStringi(gl::EXTENSIONS, index);
+                return !name.is_null() && matches!(CStr::from_ptr(name as *const c_char).to_bytes(),
+                    b"GL_EXT_texture_filter_anisotropic" | b"GL_ARB_texture_filter_anisotropic");
+            });
+        }
+        if !supported {
+            return 1.0;
+        }
+
+        let mut max_anisotropy : f32 = 1.0;
+        gl::GetFloatv(MAX_TEXTURE_MAX_ANISOTROPY, &mut max_anisotropy);
+        return max_anisotropy.max(1.0);
+    }
+}
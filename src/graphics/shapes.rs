@@ -130,4 +130,51 @@ impl Shape for RectShape {
         return None;
     }
     
-}
\ No newline at end of file
+}
+
+/// A cube from -1 to 1 without uvs, the positions are the directions of a skybox.
+pub struct SkyboxShape;
+impl Shape for SkyboxShape {
+
+    //returns the vertex buffer data
+    fn get_vertex_buffer(&self) -> Option<Vec<f32>> {
+        let buffer: Vec<f32> = vec![
+            -1.0, -1.0, -1.0,
+            1.0, -1.0, -1.0,
+            1.0, 1.0, -1.0,
+            -1.0, 1.0, -1.0,
+            -1.0, -1.0, 1.0,
+            1.0, -1.0, 1.0,
+            1.0, 1.0, 1.0,
+            -1.0, 1.0, 1.0
+        ];
+        return Some(buffer);
+    }
+
+    //returns the uv buffer
+    fn get_uv_buffer(&self) -> Option<Vec<f32>> {
+        return None;
+    }
+
+    //returns the index buffer, the faces point inwards
+    fn get_index_buffer(&self) -> Option<Vec<u32>> {
+        let buffer: Vec<u32> = vec![
+            0, 1, 2, 0, 2, 3,
+            5, 4, 7, 5, 7, 6,
+            4, 0, 3, 4, 3, 7,
+            1, 5, 6, 1, 6, 2,
+            4, 5, 1, 4, 1, 0,
+            3, 2, 6, 3, 6, 7
+        ];
+        return Some(buffer);
+    }
+
+    fn get_normal_buffer(&self) -> Option<Vec<f32>> {
+        return None;
+    }
+
+    fn get_tangent_buffer(&self) -> Option<Vec<f32>> {
+        return None;
+    }
+
+}
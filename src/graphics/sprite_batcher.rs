@@ -0,0 +1,98 @@
+use nalgebra::{Matrix4, Vector4};
+
+use crate::shader::ShaderProgram;
+
+use super::RenderData;
+
+/// The per instance data of a batched sprite, as it is stored within the streaming instance buffer.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpriteInstance {
+    pub transform : Matrix4<f32>,
+    pub color : Vector4<f32>,
+    pub uv_transform : Vector4<f32>,
+    /// xy = uv scale
+    pub extras : Vector4<f32>,
+}
+
+/// Counts the flushed sprites and draw calls since the last `RenderDevice::begin_frame`.
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct SpriteBatchStats {
+    pub draw_calls : usize,
+    pub sprites : usize,
+}
+
+/// Collects immediate-mode texture draws and renders them with a single instanced draw call per texture.
+///
+/// The batcher is owned by the `RenderDevice` and enabled with `RenderDevice::enable_sprite_batching`.
+/// With sorting enabled, sprites of different textures are collected together and drawn back to front by their layer.
+pub struct SpriteBatcher {
+    pub(crate) shader_program : ShaderProgram,
+    pub(crate) render_data : RenderData,
+    pub(crate) instance_buffer : u32,
+    /// The number of instances the instance buffer can hold.
+    pub(crate) capacity : usize,
+    pub(crate) instances : Vec<SpriteInstance>,
+    /// The texture and the layer of each instance.
+    pub(crate) sprites : Vec<(u32, f32)>,
+    pub(crate) sorting : bool,
+    pub(crate) stats : SpriteBatchStats,
+}
+
+impl SpriteBatcher {
+    pub(crate) fn new(shader_program : ShaderProgram, render_data : RenderData, instance_buffer : u32) -> SpriteBatcher {
+        return SpriteBatcher {
+            shader_program,
+            render_data,
+            instance_buffer,
+            capacity: 0,
+            instances: Vec::new(),
+            sprites: Vec::new(),
+            sorting: false,
+            stats: SpriteBatchStats::default()
+        };
+    }
+
+    /// Returns true if a draw with the given texture can be added without flushing the batch first.
+    pub(crate) fn accepts(&self, texture_id : u32) -> bool {
+        return self.sorting || self.sprites.last().is_none_or(|(last_texture, _)| *last_texture == texture_id);
+    }
+
+    pub(crate) fn push(&mut self, texture_id : u32, layer : f32, instance : SpriteInstance) {
+        self.sprites.push((texture_id, layer));
+        self.instances.push(instance);
+    }
+
+    /// Sorts the pending sprites back to front by their layer, if sorting is enabled.
+    /// Sprites within the same layer keep their order.
+    pub(crate) fn sort(&mut self) {
+        if !self.sorting || self.sprites.windows(2).all(|pair| pair[0].1 <= pair[1].1) {
+            return;
+        }
+        let mut order : Vec<usize> = (0 .. self.sprites.len()).collect();
+        order.sort_by(|a, b| self.sprites[*a].1.total_cmp(&self.sprites[*b].1));
+        self.instances = order.iter().map(|index| self.instances[*index]).collect();
+        self.sprites = order.iter().map(|index| self.sprites[*index]).collect();
+    }
+
+    /// Returns the texture and the index range of each run of consecutive sprites with the same texture.
+    pub(crate) fn texture_runs(&self) -> Vec<(u32, std::ops::Range<usize>)> {
+        let mut runs : Vec<(u32, std::ops::Range<usize>)> = Vec::new();
+        for (index, (texture_id, _)) in self.sprites.iter().enumerate() {
+            match runs.last_mut() {
+                Some((run_texture, range)) if *run_texture == *texture_id => {
+                    range.end = index + 1;
+                }
+                _ => {
+                    runs.push((*texture_id, index .. index + 1));
+                }
+            }
+        }
+        return runs;
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.instances.clear();
+        self.sprites.clear();
+    }
+}
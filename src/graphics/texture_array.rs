@@ -0,0 +1,104 @@
+use nalgebra::Vector2;
+
+use crate::error::{GfxError, GfxResult};
+
+use super::image_texture::{ColorMode, ImageTexture};
+
+/// A `GL_TEXTURE_2D_ARRAY` with one image per layer.
+///
+/// The instances of a `Texture2DBatch` select their layer with `Texture2DInstance::texture_index`,
+/// so a batch can render several images with one instanced draw call.
+///
+/// ### Notes:
+/// - All layers must have the same dimensions and color mode.
+#[derive(Clone)]
+pub enum TextureArray {
+    PreLoad {
+        dimensions: Vector2<u32>,
+        mode: ColorMode,
+        layers: Vec<Vec<u8>>
+    },
+    Loaded {
+        id: u32,
+        dimensions: Vector2<u32>,
+        layer_count: u32
+    },
+    Disposed
+}
+
+impl TextureArray {
+
+    /// Creates an empty texture array for layers with the given dimensions and color mode.
+    pub fn new(dimensions : Vector2<u32>, mode : ColorMode) -> TextureArray {
+        return TextureArray::PreLoad {
+            dimensions,
+            mode,
+            layers: Vec::new()
+        };
+    }
+
+    /// Creates a texture array from preloaded image textures. The index of an image becomes its layer.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the list is empty, an image is not preloaded
+    ///   or the images differ in dimensions or color mode.
+    pub fn from_images(images : &[ImageTexture]) -> GfxResult<TextureArray> {
+        let mut texture_array : Option<TextureArray> = None;
+        for image in images {
+            let ImageTexture::PreLoad { path: _, dimensions, data, mode } = image else {
+                return Err(GfxError::InvalidState(String::from("Only preloaded images can be added to a texture array.")));
+            };
+            let texture_array = texture_array.get_or_insert_with(|| TextureArray::new(*dimensions, *mode));
+            texture_array.add_layer(*dimensions, *mode, data.clone())?;
+        }
+        return texture_array.ok_or(GfxError::InvalidState(String::from("A texture array requires at least one image.")));
+    }
+
+    /// Loads the images of the given files into a texture array, in the order of the files.
+    ///
+    /// # Errors
+    /// - Returns the errors of `ImageTexture::try_load_from_file` and `TextureArray::from_images`.
+    pub fn try_load_from_files(files : &[&str], flip_vertically : bool) -> GfxResult<TextureArray> {
+        let mut images = Vec::new();
+        for file in files {
+            images.push(ImageTexture::try_load_from_file(file, flip_vertically)?);
+        }
+        return TextureArray::from_images(&images);
+    }
+
+    /// Appends a layer to a preloaded texture array.
+    ///
+    /// # Returns
+    /// - The index of the new layer.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the array is not preloaded or the layer doesn't match the array.
+    pub fn add_layer(&mut self, layer_dimensions : Vector2<u32>, layer_mode : ColorMode, data : Vec<u8>) -> GfxResult<u32> {
+        match self {
+            TextureArray::PreLoad { dimensions, mode, layers } => {
+                if layer_dimensions != *dimensions || layer_mode != *mode {
+                    return Err(GfxError::InvalidState(format!("The layer with {}x{} pixels doesn't match the texture array with {}x{} pixels and the same color mode.",
+                        layer_dimensions.x, layer_dimensions.y, dimensions.x, dimensions.y)));
+                }
+                let expected_size = dimensions.x as usize * dimensions.y as usize * mode.bytes_per_pixel();
+                if data.len() != expected_size {
+                    return Err(GfxError::InvalidState(format!("The layer contains {} bytes, expected {} bytes.", data.len(), expected_size)));
+                }
+                layers.push(data);
+                return Ok(layers.len() as u32 - 1);
+            }
+            _ => {
+                return Err(GfxError::InvalidState(String::from("Layers can only be added to a preloaded texture array.")));
+            }
+        }
+    }
+
+    /// Returns the number of layers.
+    pub fn layer_count(&self) -> u32 {
+        match self {
+            TextureArray::PreLoad { layers, .. } => layers.len() as u32,
+            TextureArray::Loaded { layer_count, .. } => *layer_count,
+            TextureArray::Disposed => 0
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use nalgebra::{Matrix4, Vector4};
+
+use super::light::ILight;
+
+/// The binding point of the `GfxFrame` uniform block. Reserved by the render device.
+pub const FRAME_BLOCK_BINDING : u32 = 0;
+/// The binding point of the `GfxLights` uniform block. Reserved by the render device.
+pub const LIGHTS_BLOCK_BINDING : u32 = 1;
+/// The name of the per-frame uniform block declared in `gfx/frame.glsl`.
+pub const FRAME_BLOCK_NAME : &str = "GfxFrame";
+/// The name of the light uniform block declared in `gfx/lights.glsl`.
+pub const LIGHTS_BLOCK_NAME : &str = "GfxLights";
+/// The maximum number of lights within the `GfxLights` block.
+pub const MAX_LIGHTS : usize = 8;
+
+/// An OpenGL uniform buffer which backs a uniform block.
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct UniformBuffer {
+    pub id : u32,
+    /// The binding point the buffer is bound to.
+    pub binding : u32,
+    /// The size of the buffer in bytes.
+    pub size : usize,
+}
+
+/// The data of the `GfxFrame` uniform block in std140 layout.
+///
+/// ```glsl
+/// layout(std140) uniform GfxFrame {
+///     mat4 p_mat;
+///     mat4 v_mat;
+///     vec4 gfx_camera_position; // xyz = position
+///     vec4 gfx_viewport;        // width, height, 1 / width, 1 / height
+///     vec4 gfx_time;            // time, delta time, frame index
+///     vec4 gfx_render_params;   // x = alpha cutoff
+/// };
+/// ```
+#[repr(C)]
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct FrameUniforms {
+    pub projection_matrix : Matrix4<f32>,
+    pub view_matrix : Matrix4<f32>,
+    pub camera_position : Vector4<f32>,
+    pub viewport : Vector4<f32>,
+    pub time : Vector4<f32>,
+    /// x = alpha cutoff, fragments with a lower alpha are discarded by the prebuild shaders
+    pub render_params : Vector4<f32>,
+}
+
+/// A single point light within the `GfxLights` block.
+#[repr(C)]
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct PointLightUniform {
+    /// xyz = position, w = intensity
+    pub position : Vector4<f32>,
+    /// rgb = color
+    pub color : Vector4<f32>,
+}
+
+/// The data of the `GfxLights` uniform block in std140 layout.
+///
+/// ```glsl
+/// struct GfxPointLight {
+///     vec4 position; // xyz = position, w = intensity
+///     vec4 color;
+/// };
+///
+/// layout(std140) uniform GfxLights {
+///     GfxPointLight gfx_lights[GFX_MAX_LIGHTS];
+///     ivec4 gfx_light_count; // x = number of lights
+/// };
+/// ```
+#[repr(C)]
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct LightUniforms {
+    pub lights : [PointLightUniform; MAX_LIGHTS],
+    pub count : [i32; 4],
+}
+
+impl LightUniforms {
+    /// Creates the block data from the given lights. Lights above `MAX_LIGHTS` are ignored.
+    pub fn from_lights<T : ILight>(lights : &mut [T]) -> LightUniforms {
+        let mut uniforms = LightUniforms::default();
+        let count = lights.len().min(MAX_LIGHTS);
+        for (i, light) in lights.iter_mut().take(count).enumerate() {
+            let position = light.get_light_pos();
+            let color = light.get_light_color();
+            uniforms.lights[i] = PointLightUniform {
+                position: Vector4::new(position.x, position.y, position.z, light.get_light_intensity()),
+                color: Vector4::new(color.x, color.y, color.z, 1.0)
+            };
+        }
+        uniforms.count[0] = count as i32;
+        return uniforms;
+    }
+}
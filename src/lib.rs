@@ -5,5 +5,7 @@ pub mod core;
 pub mod shader;
 pub mod utils;
 pub mod assets;
+pub mod testing;
+pub mod error;
 
 use graphics::*;
\ No newline at end of file
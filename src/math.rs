@@ -16,4 +16,25 @@ impl<T: Copy> Rect<T> {
     pub fn get_size_vector(&self) -> Vector2<T> {
         return Vector2::new(self.widht, self.height);
     }
-}
\ No newline at end of file
+}
+
+impl<T> Rect<T> where T : Copy + Default + PartialOrd + std::ops::Add<Output = T> + std::ops::Sub<Output = T> {
+    /// Returns the overlapping area of both rectangles. The width and height are both zero if the rectangles don't overlap.
+    pub fn intersection(&self, other : &Rect<T>) -> Rect<T> {
+        let max = |a : T, b : T| if a > b { a } else { b };
+        let min = |a : T, b : T| if a < b { a } else { b };
+        let x = max(self.x, other.x);
+        let y = max(self.y, other.y);
+        let right = min(self.x + self.widht, other.x + other.widht);
+        let bottom = min(self.y + self.height, other.y + other.height);
+        if right <= x || bottom <= y {
+            return Rect { x, y, widht: T::default(), height: T::default() };
+        }
+        return Rect {
+            x,
+            y,
+            widht: right - x,
+            height: bottom - y
+        };
+    }
+}
@@ -1,10 +1,126 @@
-use crate::utils;
+use std::{collections::{BTreeMap, HashMap}, time::SystemTime};
+
+use crate::{error::{GfxError, GfxResult}, utils};
+
+use self::reflection::ShaderReflection;
 
 pub mod prebuild_shader;
+pub mod diagnostics;
+pub mod preprocessor;
+pub mod reflection;
+pub mod uniform;
+
+/// A set of preprocessor defines, mapped from name to value.
+/// The map is ordered, so the same set always produces the same source and can be used as a cache key.
+pub type ShaderDefines = BTreeMap<String, String>;
+
+/// Represents the stage of a shader within a shader program.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ShaderStage {
+    Vertex,
+    Fragment
+}
+
+impl ShaderStage {
+    /// Returns the OpenGL shader type of the stage.
+    pub fn as_gl_type(self) -> u32 {
+        match self {
+            ShaderStage::Vertex => gl::VERTEX_SHADER,
+            ShaderStage::Fragment => gl::FRAGMENT_SHADER
+        }
+    }
+}
+
+impl std::fmt::Display for ShaderStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShaderStage::Vertex => write!(f, "vertex"),
+            ShaderStage::Fragment => write!(f, "fragment")
+        }
+    }
+}
 
 #[derive(Default, Clone)]
 pub struct Shader {
     pub source : String,
+    /// The file the source was loaded from, if any.
+    pub path : Option<String>,
+    /// The modification time of the file when the source was loaded.
+    pub modified : Option<SystemTime>,
+    /// The defines which are injected when the shader is preprocessed.
+    pub defines : ShaderDefines,
+    /// The files included by the shader and their modification times. Updated whenever the shader is built.
+    pub includes : Vec<(String, Option<SystemTime>)>,
+}
+
+impl Shader {
+    /// Creates a shader from the given source code without a backing file.
+    pub fn from_source(source : &str) -> Shader {
+        return Shader {
+            source: String::from(source),
+            ..Default::default()
+        };
+    }
+
+    /// Loads a shader from a file and remembers the path for hot reloading.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::Io` if the file cannot be read.
+    pub fn from_file(file : &str) -> GfxResult<Shader> {
+        let source = utils::try_load_file_as_string(file)?;
+        return Ok(Shader {
+            source: source,
+            path: Some(String::from(file)),
+            modified: Self::modified_time(file),
+            ..Default::default()
+        });
+    }
+
+    /// Sets a define which is injected when the shader is preprocessed.
+    pub fn with_define(mut self, name : &str, value : &str) -> Shader {
+        self.defines.insert(String::from(name), String::from(value));
+        return self;
+    }
+
+    /// Returns true if the shader or one of its includes is backed by a file which has been modified since it was loaded.
+    pub fn has_changed(&self) -> bool {
+        let file_changed = |path : &str, loaded : Option<SystemTime>| {
+            let modified = Self::modified_time(path);
+            return modified.is_some() && modified != loaded;
+        };
+        if let Some(path) = &self.path {
+            if file_changed(path, self.modified) {
+                return true;
+            }
+        }
+        return self.includes.iter().any(|(path, modified)| file_changed(path, *modified));
+    }
+
+    /// Reloads the source from the backing file.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::InvalidState` if the shader has no backing file.
+    /// - Returns `GfxError::Io` if the file cannot be read. The source is kept, but the modification time is updated,
+    ///   so `has_changed` doesn't report the unreadable revision again.
+    pub fn reload(&mut self) -> GfxResult<()> {
+        let Some(path) = self.path.clone() else {
+            return Err(GfxError::InvalidState(String::from("Cannot reload a shader without a source file.")));
+        };
+        let shader = match Self::from_file(&path) {
+            Ok(shader) => shader,
+            Err(e) => {
+                self.modified = Self::modified_time(&path);
+                return Err(e);
+            }
+        };
+        self.source = shader.source;
+        self.modified = shader.modified;
+        return Ok(());
+    }
+
+    fn modified_time(file : &str) -> Option<SystemTime> {
+        return std::fs::metadata(file).and_then(|metadata| metadata.modified()).ok();
+    }
 }
 
 #[derive(Clone)]
@@ -14,26 +130,160 @@ pub enum ShaderProgram{
         vertex_shader : Shader,
     },
     Builded {
-        program_id : u32
+        program_id : u32,
+        /// The shaders the program was built from. Used to rebuild the program on hot reload.
+        fragment_shader : Shader,
+        vertex_shader : Shader,
+        /// The active uniforms, attributes and uniform blocks of the program.
+        reflection : ShaderReflection,
+    },
+    /// The last build failed. Binding the program reports the error instead of panicking, building it again retries the build.
+    Failed {
+        fragment_shader : Shader,
+        vertex_shader : Shader,
+        /// The compile, link or preprocess error of the last build.
+        error : String,
     },
     Disposed {}
 }
 
 impl ShaderProgram {    
+    /// Creates a new program in the `PreBuild` state from a vertex and a fragment shader file.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::Io` if one of the files cannot be read.
+    pub fn from_files(vertex_file : &str, fragment_file : &str) -> GfxResult<ShaderProgram> {
+        return Ok(ShaderProgram::PreBuild {
+            vertex_shader: Shader::from_file(vertex_file)?,
+            fragment_shader: Shader::from_file(fragment_file)?
+        });
+    }
+
     pub fn load_vertex_shader(&mut self, file : String) {
-        if let ShaderProgram::PreBuild { ref mut vertex_shader, .. } = self {
-            vertex_shader.source = utils::load_file_as_string(file);
-        } else {
-            panic!("Cannot load vertex shader when the program is not in PreBuild state.");
+        if let Err(e) = self.try_load_vertex_shader(&file) {
+            match e {
+                GfxError::InvalidState(reason) => panic!("{}", reason),
+                e => eprintln!("Error while loading the file {}", e)
+            }
         }
     }
 
     pub fn load_fragment_shader(&mut self, file : String) {
-        if let ShaderProgram::PreBuild { ref mut fragment_shader, ..} = self {
-            fragment_shader.source = utils::load_file_as_string(file);
+        if let Err(e) = self.try_load_fragment_shader(&file) {
+            match e {
+                GfxError::InvalidState(reason) => panic!("{}", reason),
+                e => eprintln!("Error while loading the file {}", e)
+            }
         }
-        else {
-            panic!("Cannot load fragment shader when the program is not in PreBuild state.");
+    }
+
+    /// Returns the reflected metadata of the program, or `None` if the program is not built.
+    pub fn get_reflection(&self) -> Option<&ShaderReflection> {
+        match self {
+            ShaderProgram::Builded { reflection, .. } => {
+                return Some(reflection);
+            }
+            _ => {
+                return None;
+            }
         }
     }
-}
\ No newline at end of file
+
+    /// Returns true if one of the shaders of the program is backed by a file which has been modified since it was loaded.
+    pub fn has_changed(&self) -> bool {
+        match self {
+            ShaderProgram::PreBuild { fragment_shader, vertex_shader }
+            | ShaderProgram::Builded { fragment_shader, vertex_shader, .. }
+            | ShaderProgram::Failed { fragment_shader, vertex_shader, .. } => {
+                return vertex_shader.has_changed() || fragment_shader.has_changed();
+            }
+            ShaderProgram::Disposed {} => {
+                return false;
+            }
+        }
+    }
+
+    /// Loads the vertex shader source from a file. The path is kept for hot reloading.
+    ///
+    /// # Errors
+    /// - Returns an error if the program is not in the `PreBuild` state or the file cannot be read.
+    pub fn try_load_vertex_shader(&mut self, file : &str) -> GfxResult<()> {
+        if let ShaderProgram::PreBuild { ref mut vertex_shader, .. } = self {
+            *vertex_shader = Shader::from_file(file)?;
+            return Ok(());
+        }
+        return Err(GfxError::InvalidState(String::from("Cannot load vertex shader when the program is not in PreBuild state.")));
+    }
+
+    /// Loads the fragment shader source from a file. The path is kept for hot reloading.
+    ///
+    /// # Errors
+    /// - Returns an error if the program is not in the `PreBuild` state or the file cannot be read.
+    pub fn try_load_fragment_shader(&mut self, file : &str) -> GfxResult<()> {
+        if let ShaderProgram::PreBuild { ref mut fragment_shader, .. } = self {
+            *fragment_shader = Shader::from_file(file)?;
+            return Ok(());
+        }
+        return Err(GfxError::InvalidState(String::from("Cannot load fragment shader when the program is not in PreBuild state.")));
+    }
+}
+
+/// A cache of shader program permutations which share the same sources but differ in their defines,
+/// e.g. a material shader with and without `NORMAL_MAP`.
+///
+/// The variants are built on demand with `RenderDevice::get_shader_variant`.
+pub struct ShaderVariants {
+    pub vertex_shader : Shader,
+    pub fragment_shader : Shader,
+    variants : HashMap<ShaderDefines, ShaderProgram>,
+}
+
+impl ShaderVariants {
+    /// Creates an empty variant cache for the given shaders. The defines of the shaders are shared by all variants.
+    pub fn new(vertex_shader : Shader, fragment_shader : Shader) -> ShaderVariants {
+        return ShaderVariants {
+            vertex_shader,
+            fragment_shader,
+            variants: HashMap::new()
+        };
+    }
+
+    /// Creates a new `PreBuild` program for the given define set.
+    pub fn create_program(&self, defines : &ShaderDefines) -> ShaderProgram {
+        let mut vertex_shader = self.vertex_shader.clone();
+        let mut fragment_shader = self.fragment_shader.clone();
+        vertex_shader.defines.extend(defines.clone());
+        fragment_shader.defines.extend(defines.clone());
+        return ShaderProgram::PreBuild { fragment_shader, vertex_shader };
+    }
+
+    /// Returns the cached variant for the given define set, if it has been built.
+    pub fn get(&mut self, defines : &ShaderDefines) -> Option<&mut ShaderProgram> {
+        return self.variants.get_mut(defines);
+    }
+
+    /// Adds a built variant to the cache.
+    pub fn insert(&mut self, defines : ShaderDefines, shader_program : ShaderProgram) {
+        self.variants.insert(defines, shader_program);
+    }
+
+    /// Returns an iterator over all cached variants.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&ShaderDefines, &mut ShaderProgram)> {
+        return self.variants.iter_mut();
+    }
+
+    /// Removes all variants from the cache and returns them, e.g. to dispose them.
+    pub fn drain(&mut self) -> Vec<ShaderProgram> {
+        return self.variants.drain().map(|(_, shader_program)| shader_program).collect();
+    }
+
+    /// Returns the number of cached variants.
+    pub fn len(&self) -> usize {
+        return self.variants.len();
+    }
+
+    /// Returns true if no variant has been built yet.
+    pub fn is_empty(&self) -> bool {
+        return self.variants.is_empty();
+    }
+}
@@ -0,0 +1,83 @@
+/// Extracts the source line numbers referenced by a shader info log.
+///
+/// Supports the common driver formats:
+/// - Mesa: `0:12(5): error: ...`
+/// - NVIDIA: `0(12) : error C0000: ...`
+/// - AMD, Intel and Apple: `ERROR: 0:12: ...`
+///
+/// # Returns
+/// - The 1-based line numbers in the order of their first appearance, without duplicates.
+pub fn parse_error_lines(log : &str) -> Vec<usize> {
+    let mut lines = Vec::new();
+    for log_line in log.lines() {
+        if let Some(line) = parse_log_line(log_line) {
+            if !lines.contains(&line) {
+                lines.push(line);
+            }
+        }
+    }
+    return lines;
+}
+
+/// Finds the first `<source>:<line>` or `<source>(<line>)` pair within a single log line.
+fn parse_log_line(log_line : &str) -> Option<usize> {
+    let bytes = log_line.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        //skip the source string number
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i < bytes.len() && (bytes[i] == b':' || bytes[i] == b'(') {
+            let start = i + 1;
+            let mut end = start;
+            while end < bytes.len() && bytes[end].is_ascii_digit() {
+                end += 1;
+            }
+            if end > start {
+                return log_line[start .. end].parse().ok();
+            }
+        }
+    }
+    return None;
+}
+
+/// Formats the given lines of a shader source with line numbers and surrounding context.
+/// The referenced lines are marked with `>`.
+///
+/// # Arguments
+/// - `source`: The shader source.
+/// - `lines`: The 1-based line numbers which should be shown.
+/// - `context`: The number of lines before and after each referenced line.
+pub fn format_source_lines(source : &str, lines : &[usize], context : usize) -> String {
+    let source_lines : Vec<&str> = source.lines().collect();
+    let mut visible = vec![false; source_lines.len()];
+    for line in lines {
+        if *line == 0 || *line > source_lines.len() {
+            continue;
+        }
+        let first = line.saturating_sub(context + 1);
+        let last = (line + context).min(source_lines.len());
+        visible[first .. last].fill(true);
+    }
+
+    let mut output = String::new();
+    let mut previous_visible = true;
+    for (index, source_line) in source_lines.iter().enumerate() {
+        if !visible[index] {
+            previous_visible = false;
+            continue;
+        }
+        if !previous_visible && !output.is_empty() {
+            output.push_str("     ...\n");
+        }
+        let marker = if lines.contains(&(index + 1)) { ">" } else { " " };
+        output.push_str(&format!("{} {:4} | {}\n", marker, index + 1, source_line));
+        previous_visible = true;
+    }
+    return output;
+}
@@ -8,28 +8,22 @@ pub struct Texture2DShader ;
 impl PrebuildShaderProgram for Texture2DShader  {
     fn build_shader_program() -> ShaderProgram {
 
-        let vertex_shader = Shader {
-            source: String::from("
+        let vertex_shader = Shader::from_source("
                 #version 410 core
                 layout(location = 0) in vec3 inPosition;
                 layout(location = 1) in vec2 inTexCoord;
 
                 out vec2 texCoord;
 
-                uniform mat4 p_mat;
-                uniform mat4 v_mat;
-                uniform mat4 m_mat;
+                #include <gfx/mvp.glsl>
                 
                 void main() {
-                    mat4 mvp = p_mat * v_mat * m_mat;
-                    gl_Position = mvp * vec4(inPosition, 1.0);
+                    gl_Position = gfx_mvp_transform(inPosition);
                     texCoord = inTexCoord;
                 }
-            "),
-        };
+            ");
 
-        let fragment_shader = Shader {
-            source: String::from("
+        let fragment_shader = Shader::from_source("
                 #version 330 core
                 in vec2 texCoord;
                 in vec4 vColor;
@@ -41,15 +35,17 @@ impl PrebuildShaderProgram for Texture2DShader  {
                 uniform vec2 uvScale;
                 uniform sampler2D textureSampler;
 
+                #include <gfx/frame.glsl>
+
                 void main() {
                     vec2 localUV = fract(texCoord * uvScale);
                     vec2 transformedTexCoord = localUV * uvTransform.xy + uvTransform.zw;
                     vec4 texColor = texture(textureSampler, transformedTexCoord);
 
                     fragColor = texColor * vertexColor;
+                    GFX_ALPHA_TEST(fragColor.a);
                 }
-            ")
-        };
+            ");
 
         return ShaderProgram::PreBuild {
             vertex_shader: vertex_shader,
@@ -63,8 +59,7 @@ impl PrebuildShaderProgram for ScreenShader {
 
     fn build_shader_program() -> ShaderProgram {
 
-        let vertex_shader = Shader {
-            source: String::from("
+        let vertex_shader = Shader::from_source("
                 #version 330 core
                 layout (location = 0) in vec3 aPos;
                 layout (location = 1) in vec2 aTexCoords;
@@ -76,11 +71,9 @@ impl PrebuildShaderProgram for ScreenShader {
                     gl_Position = vec4(aPos, 1.0); 
                     TexCoords = aTexCoords;
                 }
-            "),
-        };
+            ");
 
-        let fragment_shader = Shader {
-            source: String::from("
+        let fragment_shader = Shader::from_source("
                 #version 330 core
                 out vec4 FragColor;
   
@@ -95,8 +88,7 @@ impl PrebuildShaderProgram for ScreenShader {
                     //texColor.rgb = pow(texColor.rgb, vec3(1.0 / gamma));
                     FragColor = texColor;
                 }
-            ")
-        };
+            ");
 
         return ShaderProgram::PreBuild {
             vertex_shader: vertex_shader,
@@ -106,44 +98,96 @@ impl PrebuildShaderProgram for ScreenShader {
 
 }
 
+/// The vertex shader of `Texture2DBatchShader` and `Texture2DArrayBatchShader`. Reads the instance attributes of a `Texture2DBatch`.
+const TEXTURE2D_BATCH_VERTEX_SHADER : &str = "
+        #version 330 core
+        layout(location = 0) in vec3 inPosition;
+        layout(location = 1) in vec2 inTexCoord;
+        layout(location = 2) in vec4 inInstanceVertexColor;
+        layout(location = 3) in mat4 inInstanceMatrix;
+        layout(location = 7) in vec4 inUvTransform;
+        layout(location = 8) in vec4 inExtras;
+        layout(location = 9) in vec4 inCustom;
+
+        #include <gfx/frame.glsl>
+
+        out vec3 fragPos;
+        out vec2 texCoord;
+        out vec4 vertexColor;
+        out vec4 uvTransform;
+        out vec4 extras;
+        out vec4 custom;
+
+        void main() {
+            //extras: x = visible, y = flip flags, z = layer, w = texture index
+            gl_Position = p_mat * v_mat * inInstanceMatrix * vec4(inPosition, 1.0);
+            fragPos = inPosition;
+            int flipFlags = int(inExtras.y + 0.5);
+            texCoord = inTexCoord;
+            if((flipFlags & 1) != 0) {
+                texCoord.x = 1.0 - texCoord.x;
+            }
+            if((flipFlags & 2) != 0) {
+                texCoord.y = 1.0 - texCoord.y;
+            }
+            vertexColor = inInstanceVertexColor;
+            uvTransform = inUvTransform;
+            extras = inExtras;
+            custom = inCustom;
+        }
+    ";
+
 pub struct Texture2DBatchShader;
 impl PrebuildShaderProgram for Texture2DBatchShader {
 
     fn build_shader_program() -> ShaderProgram {
 
-        let vertex_shader = Shader {
-            source: String::from("
+        let vertex_shader = Shader::from_source(TEXTURE2D_BATCH_VERTEX_SHADER);
+
+        let fragment_shader = Shader::from_source("
                 #version 330 core
-                layout(location = 0) in vec3 inPosition;
-                layout(location = 1) in vec2 inTexCoord;
-                layout(location = 2) in vec4 inInstanceVertexColor;
-                layout(location = 3) in mat4 inInstanceMatrix;
-                layout(location = 7) in vec4 inUvTransform;
-                layout(location = 8) in vec4 inExtras;
 
-                uniform mat4 p_mat;
-                uniform mat4 v_mat;
+                in vec3 fragPos;
+                in vec2 texCoord;
+                in vec4 vertexColor;
+                in vec4 uvTransform;
+                in vec4 extras;
+
+                out vec4 FragColor;
+                
+                uniform sampler2D textureSampler;
 
-                out vec3 fragPos;
-                out vec2 texCoord;
-                out vec4 vertexColor;
-                out vec4 uvTransform;
-                out vec4 extras;
+                #include <gfx/frame.glsl>
 
                 void main() {
-                    mat4 mvp = p_mat * v_mat * inInstanceMatrix;
-                    gl_Position = mvp * vec4(inPosition, 1.0);
-                    fragPos = inPosition;
-                    texCoord = inTexCoord;
-                    vertexColor = inInstanceVertexColor;
-                    uvTransform = inUvTransform;
-                    extras = inExtras;
+                    if(extras.x == 0.0) {
+                        discard;
+                    }
+                    vec2 transformedTexCoord = texCoord * uvTransform.xy + uvTransform.zw;
+                    vec4 texColor = texture(textureSampler, transformedTexCoord);
+                    FragColor = texColor * vertexColor;
+                    GFX_ALPHA_TEST(FragColor.a);
                 }
-            "),
-        };
+            ");
+
+        return ShaderProgram::PreBuild {
+            vertex_shader: vertex_shader,
+            fragment_shader: fragment_shader
+        }
+    }
+
+}
+
+
+/// Draws a `Texture2DBatch` with a texture array. Every instance samples the layer of its texture index.
+pub struct Texture2DArrayBatchShader;
+impl PrebuildShaderProgram for Texture2DArrayBatchShader {
+
+    fn build_shader_program() -> ShaderProgram {
+
+        let vertex_shader = Shader::from_source(TEXTURE2D_BATCH_VERTEX_SHADER);
 
-        let fragment_shader = Shader {
-            source: String::from("
+        let fragment_shader = Shader::from_source("
                 #version 330 core
 
                 in vec3 fragPos;
@@ -154,18 +198,20 @@ impl PrebuildShaderProgram for Texture2DBatchShader {
 
                 out vec4 FragColor;
                 
-                uniform sampler2D textureSampler;
+                uniform sampler2DArray textureSampler;
+
+                #include <gfx/frame.glsl>
 
                 void main() {
                     if(extras.x == 0.0) {
                         discard;
                     }
                     vec2 transformedTexCoord = texCoord * uvTransform.xy + uvTransform.zw;
-                    vec4 texColor = texture(textureSampler, transformedTexCoord);
+                    vec4 texColor = texture(textureSampler, vec3(transformedTexCoord, extras.w));
                     FragColor = texColor * vertexColor;
+                    GFX_ALPHA_TEST(FragColor.a);
                 }
-            ")
-        };
+            ");
 
         return ShaderProgram::PreBuild {
             vertex_shader: vertex_shader,
@@ -181,24 +227,21 @@ impl PrebuildShaderProgram for FontShader {
 
     fn build_shader_program() -> ShaderProgram {
 
-        let vertex_shader = Shader {
-            source: String::from("
+        let vertex_shader = Shader::from_source("
                 #version 330 core
                 layout (location = 0) in vec4 vertex;
                 out vec2 TexCoords;
 
-                uniform mat4 p_mat;
+                #include <gfx/frame.glsl>
 
                 void main()
                 {
                     gl_Position = p_mat * vec4(vertex.xy, 0.0, 1.0);
                     TexCoords = vertex.zw;
                 }  
-            "),
-        };
+            ");
 
-        let fragment_shader = Shader {
-            source: String::from("
+        let fragment_shader = Shader::from_source("
                 #version 330 core
                 in vec2 TexCoords;
                 out vec4 color;
@@ -206,13 +249,15 @@ impl PrebuildShaderProgram for FontShader {
                 uniform sampler2D textureSampler;
                 uniform vec4 vertexColor;
 
+                #include <gfx/frame.glsl>
+
                 void main()
                 {    
                     vec4 sampled = vec4(1.0, 1.0, 1.0, texture(textureSampler, TexCoords).r);
                     color = vertexColor * sampled;
+                    GFX_ALPHA_TEST(color.a);
                 }  
-            ")
-        };
+            ");
 
         return ShaderProgram::PreBuild {
             vertex_shader: vertex_shader,
@@ -227,27 +272,21 @@ impl PrebuildShaderProgram for RectShader {
 
     fn build_shader_program() -> ShaderProgram {
         
-        let vertex_shader = Shader {
-            source: String::from("
+        let vertex_shader = Shader::from_source("
                 #version 410 core
                 layout(location = 0) in vec3 inPosition;
 
                 out vec3 position;
 
-                uniform mat4 p_mat;
-                uniform mat4 v_mat;
-                uniform mat4 m_mat;
+                #include <gfx/mvp.glsl>
                 
                 void main() {
-                    mat4 mvp = p_mat * v_mat * m_mat;
-                    gl_Position = mvp * vec4(inPosition, 1.0);
+                    gl_Position = gfx_mvp_transform(inPosition);
                     position = inPosition;
                 }
-            "),
-        };
+            ");
 
-        let fragment_shader = Shader {
-            source: String::from("
+        let fragment_shader = Shader::from_source("
                 #version 410 core
 
                 out vec4 fragColor; 
@@ -276,8 +315,7 @@ impl PrebuildShaderProgram for RectShader {
                         fragColor = vertexColor;
                    }  
                 }
-            ")
-        };
+            ");
 
         return ShaderProgram::PreBuild {
             vertex_shader: vertex_shader,
@@ -291,8 +329,7 @@ impl PrebuildShaderProgram for MeshShader {
 
     fn build_shader_program() -> ShaderProgram {
         
-        let vertex_shader = Shader {
-            source: String::from("
+        let vertex_shader = Shader::from_source("
                 #version 410 core
                 layout(location = 0) in vec3 inPosition;
                 layout(location = 1) in vec2 inTexCoord;
@@ -302,22 +339,17 @@ impl PrebuildShaderProgram for MeshShader {
                 out vec3 normal;
                 out vec2 texCoord;
 
-                uniform mat4 p_mat;
-                uniform mat4 v_mat;
-                uniform mat4 m_mat;
+                #include <gfx/mvp.glsl>
                 
                 void main() {
-                    mat4 mvp = p_mat * v_mat * m_mat;
-                    gl_Position = mvp * vec4(inPosition, 1.0);
+                    gl_Position = gfx_mvp_transform(inPosition);
                     position = inPosition;
                     normal = inNormal;
                     texCoord = inTexCoord;
                 }
-            "),
-        };
+            ");
 
-        let fragment_shader = Shader {
-            source: String::from("
+        let fragment_shader = Shader::from_source("
                 #version 410 core
 
                 out vec4 FragColor; 
@@ -335,12 +367,351 @@ impl PrebuildShaderProgram for MeshShader {
                     //texColor.rgb = pow(texColor.rgb, vec3(1.0 / gamma));
                     FragColor = texColor;
                 }
-            ")
-        };
+            ");
+
+        return ShaderProgram::PreBuild {
+            vertex_shader: vertex_shader,
+            fragment_shader: fragment_shader
+        }
+    }
+}
+
+pub struct SpriteBatchShader;
+impl PrebuildShaderProgram for SpriteBatchShader {
+
+    fn build_shader_program() -> ShaderProgram {
+
+        let vertex_shader = Shader::from_source("
+                #version 330 core
+                layout(location = 0) in vec3 inPosition;
+                layout(location = 1) in vec2 inTexCoord;
+                layout(location = 2) in vec4 inInstanceVertexColor;
+                layout(location = 3) in mat4 inInstanceMatrix;
+                layout(location = 7) in vec4 inUvTransform;
+                layout(location = 8) in vec4 inExtras;
+
+                #include <gfx/frame.glsl>
+
+                out vec2 texCoord;
+                out vec4 vertexColor;
+                out vec4 uvTransform;
+                out vec2 uvScale;
+
+                void main() {
+                    gl_Position = p_mat * v_mat * inInstanceMatrix * vec4(inPosition, 1.0);
+                    texCoord = inTexCoord;
+                    vertexColor = inInstanceVertexColor;
+                    uvTransform = inUvTransform;
+                    uvScale = inExtras.xy;
+                }
+            ");
+
+        let fragment_shader = Shader::from_source("
+                #version 330 core
+                in vec2 texCoord;
+                in vec4 vertexColor;
+                in vec4 uvTransform;
+                in vec2 uvScale;
+
+                out vec4 fragColor;
+
+                uniform sampler2D textureSampler;
+
+                #include <gfx/frame.glsl>
+
+                void main() {
+                    vec2 localUV = fract(texCoord * uvScale);
+                    vec2 transformedTexCoord = localUV * uvTransform.xy + uvTransform.zw;
+                    fragColor = texture(textureSampler, transformedTexCoord) * vertexColor;
+                    GFX_ALPHA_TEST(fragColor.a);
+                }
+            ");
 
         return ShaderProgram::PreBuild {
             vertex_shader: vertex_shader,
             fragment_shader: fragment_shader
         }
     }
-}
\ No newline at end of file
+}
+
+/// The vertex shader of all post processing passes. Renders the framebuffer shape as a fullscreen quad.
+const POST_PROCESS_VERTEX_SHADER : &str = "
+        #version 330 core
+        layout (location = 0) in vec3 aPos;
+        layout (location = 1) in vec2 aTexCoords;
+
+        out vec2 texCoord;
+
+        void main()
+        {
+            gl_Position = vec4(aPos, 1.0);
+            texCoord = aTexCoords;
+        }
+    ";
+
+/// Creates a post processing program from the given fragment shader.
+/// The fragment shader receives `texCoord`, the input texture `textureSampler` and the size of one texel `texelSize`.
+pub fn post_process_shader_program(fragment_shader : Shader) -> ShaderProgram {
+    return ShaderProgram::PreBuild {
+        vertex_shader: Shader::from_source(POST_PROCESS_VERTEX_SHADER),
+        fragment_shader: fragment_shader
+    }
+}
+
+pub struct CopyShader;
+impl PrebuildShaderProgram for CopyShader {
+
+    fn build_shader_program() -> ShaderProgram {
+        return post_process_shader_program(Shader::from_source("
+                #version 330 core
+                in vec2 texCoord;
+                out vec4 fragColor;
+
+                uniform sampler2D textureSampler;
+
+                void main() {
+                    fragColor = texture(textureSampler, texCoord);
+                }
+            "));
+    }
+}
+
+pub struct GaussianBlurShader;
+impl PrebuildShaderProgram for GaussianBlurShader {
+
+    fn build_shader_program() -> ShaderProgram {
+        return post_process_shader_program(Shader::from_source("
+                #version 330 core
+                in vec2 texCoord;
+                out vec4 fragColor;
+
+                uniform sampler2D textureSampler;
+                uniform vec2 texelSize;
+                uniform vec2 direction;
+                uniform float radius;
+
+                const float weights[5] = float[](0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+
+                void main() {
+                    //4 taps on each side span the radius
+                    vec2 step = direction * texelSize * radius * 0.25;
+                    vec4 color = texture(textureSampler, texCoord) * weights[0];
+                    for (int i = 1; i < 5; i++) {
+                        color += texture(textureSampler, texCoord + step * float(i)) * weights[i];
+                        color += texture(textureSampler, texCoord - step * float(i)) * weights[i];
+                    }
+                    fragColor = color;
+                }
+            "));
+    }
+}
+
+pub struct BloomThresholdShader;
+impl PrebuildShaderProgram for BloomThresholdShader {
+
+    fn build_shader_program() -> ShaderProgram {
+        return post_process_shader_program(Shader::from_source("
+                #version 330 core
+                in vec2 texCoord;
+                out vec4 fragColor;
+
+                uniform sampler2D textureSampler;
+                uniform float threshold;
+
+                void main() {
+                    vec3 color = texture(textureSampler, texCoord).rgb;
+                    float brightness = max(color.r, max(color.g, color.b));
+                    float contribution = max(brightness - threshold, 0.0) / max(brightness, 0.0001);
+                    fragColor = vec4(color * contribution, 1.0);
+                }
+            "));
+    }
+}
+
+pub struct BloomCompositeShader;
+impl PrebuildShaderProgram for BloomCompositeShader {
+
+    fn build_shader_program() -> ShaderProgram {
+        return post_process_shader_program(Shader::from_source("
+                #version 330 core
+                in vec2 texCoord;
+                out vec4 fragColor;
+
+                uniform sampler2D textureSampler;
+                uniform sampler2D bloomSampler;
+                uniform float intensity;
+
+                void main() {
+                    vec4 color = texture(textureSampler, texCoord);
+                    color.rgb += texture(bloomSampler, texCoord).rgb * intensity;
+                    fragColor = color;
+                }
+            "));
+    }
+}
+
+pub struct FxaaShader;
+impl PrebuildShaderProgram for FxaaShader {
+
+    fn build_shader_program() -> ShaderProgram {
+        return post_process_shader_program(Shader::from_source("
+                #version 330 core
+                in vec2 texCoord;
+                out vec4 fragColor;
+
+                uniform sampler2D textureSampler;
+                uniform vec2 texelSize;
+
+                #define FXAA_REDUCE_MIN (1.0 / 128.0)
+                #define FXAA_REDUCE_MUL (1.0 / 8.0)
+                #define FXAA_SPAN_MAX 8.0
+
+                void main() {
+                    vec3 lumaWeights = vec3(0.299, 0.587, 0.114);
+                    vec4 colorM = texture(textureSampler, texCoord);
+                    float lumaNW = dot(texture(textureSampler, texCoord + vec2(-1.0, -1.0) * texelSize).rgb, lumaWeights);
+                    float lumaNE = dot(texture(textureSampler, texCoord + vec2(1.0, -1.0) * texelSize).rgb, lumaWeights);
+                    float lumaSW = dot(texture(textureSampler, texCoord + vec2(-1.0, 1.0) * texelSize).rgb, lumaWeights);
+                    float lumaSE = dot(texture(textureSampler, texCoord + vec2(1.0, 1.0) * texelSize).rgb, lumaWeights);
+                    float lumaM = dot(colorM.rgb, lumaWeights);
+                    float lumaMin = min(lumaM, min(min(lumaNW, lumaNE), min(lumaSW, lumaSE)));
+                    float lumaMax = max(lumaM, max(max(lumaNW, lumaNE), max(lumaSW, lumaSE)));
+
+                    //blur along the edge, perpendicular to the luma gradient
+                    vec2 dir = vec2(-((lumaNW + lumaNE) - (lumaSW + lumaSE)), (lumaNW + lumaSW) - (lumaNE + lumaSE));
+                    float dirReduce = max((lumaNW + lumaNE + lumaSW + lumaSE) * (0.25 * FXAA_REDUCE_MUL), FXAA_REDUCE_MIN);
+                    float rcpDirMin = 1.0 / (min(abs(dir.x), abs(dir.y)) + dirReduce);
+                    dir = clamp(dir * rcpDirMin, vec2(-FXAA_SPAN_MAX), vec2(FXAA_SPAN_MAX)) * texelSize;
+
+                    vec3 colorA = 0.5 * (texture(textureSampler, texCoord + dir * (1.0 / 3.0 - 0.5)).rgb +
+                        texture(textureSampler, texCoord + dir * (2.0 / 3.0 - 0.5)).rgb);
+                    vec3 colorB = colorA * 0.5 + 0.25 * (texture(textureSampler, texCoord - dir * 0.5).rgb +
+                        texture(textureSampler, texCoord + dir * 0.5).rgb);
+                    float lumaB = dot(colorB, lumaWeights);
+                    if (lumaB < lumaMin || lumaB > lumaMax) {
+                        fragColor = vec4(colorA, colorM.a);
+                    }
+                    else {
+                        fragColor = vec4(colorB, colorM.a);
+                    }
+                }
+            "));
+    }
+}
+
+pub struct VignetteShader;
+impl PrebuildShaderProgram for VignetteShader {
+
+    fn build_shader_program() -> ShaderProgram {
+        return post_process_shader_program(Shader::from_source("
+                #version 330 core
+                in vec2 texCoord;
+                out vec4 fragColor;
+
+                uniform sampler2D textureSampler;
+                uniform float intensity;
+                uniform float smoothness;
+
+                void main() {
+                    vec4 color = texture(textureSampler, texCoord);
+                    //0 in the center, 1 in the corners
+                    float dist = length(texCoord - 0.5) * 1.4142135;
+                    float vignette = smoothstep(1.0 - smoothness, 1.0, dist);
+                    color.rgb *= 1.0 - vignette * intensity;
+                    fragColor = color;
+                }
+            "));
+    }
+}
+
+pub struct ColorGradingShader;
+impl PrebuildShaderProgram for ColorGradingShader {
+
+    fn build_shader_program() -> ShaderProgram {
+        return post_process_shader_program(Shader::from_source("
+                #version 330 core
+                in vec2 texCoord;
+                out vec4 fragColor;
+
+                uniform sampler2D textureSampler;
+                uniform sampler3D lutSampler;
+                uniform float lutSize;
+                uniform float intensity;
+
+                void main() {
+                    vec4 color = texture(textureSampler, texCoord);
+                    //sample the centers of the outer texels
+                    vec3 lutCoord = clamp(color.rgb, 0.0, 1.0) * ((lutSize - 1.0) / lutSize) + 0.5 / lutSize;
+                    vec3 graded = texture(lutSampler, lutCoord).rgb;
+                    fragColor = vec4(mix(color.rgb, graded, intensity), color.a);
+                }
+            "));
+    }
+}
+
+pub struct ChromaticAberrationShader;
+impl PrebuildShaderProgram for ChromaticAberrationShader {
+
+    fn build_shader_program() -> ShaderProgram {
+        return post_process_shader_program(Shader::from_source("
+                #version 330 core
+                in vec2 texCoord;
+                out vec4 fragColor;
+
+                uniform sampler2D textureSampler;
+                uniform vec2 texelSize;
+                uniform float offset;
+
+                void main() {
+                    //the channels drift apart towards the edges
+                    vec2 shift = (texCoord - 0.5) * 2.0 * offset * texelSize;
+                    vec4 color = texture(textureSampler, texCoord);
+                    color.r = texture(textureSampler, texCoord + shift).r;
+                    color.b = texture(textureSampler, texCoord - shift).b;
+                    fragColor = color;
+                }
+            "));
+    }
+}
+
+pub struct SkyboxShader;
+impl PrebuildShaderProgram for SkyboxShader {
+
+    fn build_shader_program() -> ShaderProgram {
+
+        let vertex_shader = Shader::from_source("
+                #version 330 core
+                layout(location = 0) in vec3 inPosition;
+
+                #include <gfx/frame.glsl>
+
+                out vec3 direction;
+
+                void main() {
+                    direction = inPosition;
+                    //the translation of the view is removed, so the sky stays around the camera
+                    vec4 position = p_mat * mat4(mat3(v_mat)) * vec4(inPosition, 1.0);
+                    //the sky lies on the far plane, behind everything else
+                    gl_Position = position.xyww;
+                }
+            ");
+
+        let fragment_shader = Shader::from_source("
+                #version 330 core
+                in vec3 direction;
+                out vec4 fragColor;
+
+                uniform samplerCube cubemapSampler;
+                uniform vec4 tint;
+
+                void main() {
+                    fragColor = texture(cubemapSampler, direction) * tint;
+                }
+            ");
+
+        return ShaderProgram::PreBuild {
+            vertex_shader: vertex_shader,
+            fragment_shader: fragment_shader
+        }
+    }
+}
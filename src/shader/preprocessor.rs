@@ -0,0 +1,275 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::error::{GfxError, GfxResult};
+
+use super::{Shader, ShaderDefines};
+
+/// Declares the per-frame uniform block, see `FrameUniforms`.
+const FRAME_LIBRARY : &str = "
+layout(std140) uniform GfxFrame {
+    mat4 p_mat;
+    mat4 v_mat;
+    vec4 gfx_camera_position;
+    vec4 gfx_viewport;
+    vec4 gfx_time;
+    vec4 gfx_render_params;
+};
+
+// Discards the fragment if the alpha is below the cutoff of RenderDevice::set_alpha_cutoff.
+#define GFX_ALPHA_TEST(alpha) if ((alpha) < gfx_render_params.x) { discard; }
+";
+
+/// Declares the camera matrices `p_mat` and `v_mat`.
+const CAMERA_LIBRARY : &str = "
+#include <gfx/frame.glsl>
+";
+
+/// Declares the light uniform block, see `LightUniforms`.
+const LIGHTS_LIBRARY : &str = "
+#define GFX_MAX_LIGHTS 8
+
+struct GfxPointLight {
+    vec4 position;
+    vec4 color;
+};
+
+layout(std140) uniform GfxLights {
+    GfxPointLight gfx_lights[GFX_MAX_LIGHTS];
+    ivec4 gfx_light_count;
+};
+";
+
+/// Declares the model matrix `m_mat` and a helper which transforms a position into clip space.
+const MVP_LIBRARY : &str = "
+#include <gfx/camera.glsl>
+uniform mat4 m_mat;
+
+vec4 gfx_mvp_transform(vec3 position) {
+    return p_mat * v_mat * m_mat * vec4(position, 1.0);
+}
+";
+
+/// The result of preprocessing a shader.
+pub struct PreprocessedShader {
+    /// The expanded source with the defines and all includes.
+    pub source : String,
+    /// The included files together with their modification times.
+    pub includes : Vec<(String, Option<SystemTime>)>,
+}
+
+/// Resolves `#include` directives and injects `#define`s into shader sources.
+///
+/// Includes are resolved in the following order:
+/// 1. Relative to the directory of the including file, if the shader has been loaded from a file.
+/// 2. Within the search paths, in the order they were added.
+/// 3. Within the library of registered sources. The embedded library provides `gfx/frame.glsl`, `gfx/camera.glsl`,
+///    `gfx/mvp.glsl` and `gfx/lights.glsl`.
+///
+/// ### Notes:
+/// - Both `#include "name"` and `#include <name>` are supported. Includes within `//` and `/* */` comments are ignored.
+/// - Every file is included only once per shader, further includes of the same file are ignored.
+/// - The defines are inserted right after the `#version` directive.
+#[derive(Clone)]
+pub struct ShaderPreprocessor {
+    search_paths : Vec<PathBuf>,
+    library : HashMap<String, String>,
+}
+
+impl Default for ShaderPreprocessor {
+    fn default() -> Self {
+        let mut preprocessor = ShaderPreprocessor {
+            search_paths: Vec::new(),
+            library: HashMap::new()
+        };
+        preprocessor.add_library_source("gfx/frame.glsl", FRAME_LIBRARY);
+        preprocessor.add_library_source("gfx/camera.glsl", CAMERA_LIBRARY);
+        preprocessor.add_library_source("gfx/lights.glsl", LIGHTS_LIBRARY);
+        preprocessor.add_library_source("gfx/mvp.glsl", MVP_LIBRARY);
+        return preprocessor;
+    }
+}
+
+impl ShaderPreprocessor {
+
+    /// Adds a directory which is searched for included files.
+    pub fn add_search_path(&mut self, path : &str) {
+        self.search_paths.push(PathBuf::from(path));
+    }
+
+    /// Registers a source which can be included by its name. Replaces a source with the same name.
+    pub fn add_library_source(&mut self, name : &str, source : &str) {
+        self.library.insert(String::from(name), String::from(source));
+    }
+
+    /// Expands the includes and defines of the given shader.
+    ///
+    /// # Errors
+    /// - Returns `GfxError::ShaderPreprocess` if an include cannot be resolved, is malformed or includes itself.
+    pub fn process(&self, shader : &Shader) -> GfxResult<PreprocessedShader> {
+        let base_dir = shader.path.as_ref().and_then(|path| Path::new(path).parent().map(Path::to_path_buf));
+        let mut state = IncludeState {
+            stack: Vec::new(),
+            included: HashSet::new(),
+            includes: Vec::new()
+        };
+        let mut expanded = String::new();
+        self.expand(&shader.source, base_dir.as_deref(), &mut state, &mut expanded)?;
+
+        return Ok(PreprocessedShader {
+            source: inject_defines(&expanded, &shader.defines),
+            includes: state.includes
+        });
+    }
+
+    fn expand(&self, source : &str, base_dir : Option<&Path>, state : &mut IncludeState, output : &mut String) -> GfxResult<()> {
+        let mut in_block_comment = false;
+        for line in source.lines() {
+            let code = strip_comments(line, &mut in_block_comment);
+            let Some(name) = parse_include(&code)? else {
+                output.push_str(line);
+                output.push('\n');
+                continue;
+            };
+
+            let include = self.resolve(name, base_dir)?;
+            if state.stack.contains(&include.key) {
+                return Err(GfxError::ShaderPreprocess(format!("The include {} includes itself", name)));
+            }
+            if !state.included.insert(include.key.clone()) {
+                continue;
+            }
+            if let Some(file) = &include.file {
+                let modified = std::fs::metadata(file).and_then(|metadata| metadata.modified()).ok();
+                state.includes.push((file.to_string_lossy().to_string(), modified));
+            }
+
+            state.stack.push(include.key.clone());
+            let include_dir = include.file.as_ref().and_then(|file| file.parent().map(Path::to_path_buf));
+            self.expand(&include.source, include_dir.as_deref(), state, output)?;
+            state.stack.pop();
+        }
+        return Ok(());
+    }
+
+    fn resolve(&self, name : &str, base_dir : Option<&Path>) -> GfxResult<ResolvedInclude> {
+        let candidates = base_dir.into_iter().chain(self.search_paths.iter().map(PathBuf::as_path));
+        for dir in candidates {
+            let file = dir.join(name);
+            if file.is_file() {
+                let source = std::fs::read_to_string(&file).map_err(|e| GfxError::Io {
+                    path: file.to_string_lossy().to_string(),
+                    source: e
+                })?;
+                let key = file.canonicalize().unwrap_or(file.clone()).to_string_lossy().to_string();
+                return Ok(ResolvedInclude { key, source, file: Some(file) });
+            }
+        }
+
+        if let Some(source) = self.library.get(name) {
+            return Ok(ResolvedInclude {
+                key: format!("library:{}", name),
+                source: source.clone(),
+                file: None
+            });
+        }
+        return Err(GfxError::ShaderPreprocess(format!("Unable to resolve the include {}", name)));
+    }
+}
+
+struct IncludeState {
+    stack : Vec<String>,
+    included : HashSet<String>,
+    includes : Vec<(String, Option<SystemTime>)>,
+}
+
+struct ResolvedInclude {
+    key : String,
+    source : String,
+    file : Option<PathBuf>,
+}
+
+/// Returns the code of a line without its `//` and `/* */` comments, which are replaced by a space.
+/// `in_block_comment` carries a block comment which is still open at the end of the line over to the next line.
+fn strip_comments(line : &str, in_block_comment : &mut bool) -> String {
+    let mut code = String::with_capacity(line.len());
+    let mut rest = line;
+    loop {
+        if *in_block_comment {
+            let Some(end) = rest.find("*/") else {
+                return code;
+            };
+            rest = &rest[end + 2 ..];
+            *in_block_comment = false;
+            code.push(' ');
+        }
+        match (rest.find("//"), rest.find("/*")) {
+            (Some(line_comment), block_comment) if block_comment.is_none_or(|block_comment| line_comment < block_comment) => {
+                code.push_str(&rest[.. line_comment]);
+                return code;
+            }
+            (_, Some(block_comment)) => {
+                code.push_str(&rest[.. block_comment]);
+                rest = &rest[block_comment + 2 ..];
+                *in_block_comment = true;
+            }
+            _ => {
+                code.push_str(rest);
+                return code;
+            }
+        }
+    }
+}
+
+/// Returns the name of an `#include` directive, or `None` if the line is no include.
+fn parse_include(line : &str) -> GfxResult<Option<&str>> {
+    let Some(rest) = line.trim().strip_prefix("#include") else {
+        return Ok(None);
+    };
+    let rest = rest.trim();
+    let name = rest.strip_prefix('"').and_then(|r| r.strip_suffix('"'))
+        .or_else(|| rest.strip_prefix('<').and_then(|r| r.strip_suffix('>')));
+    match name {
+        Some(name) if !name.is_empty() => {
+            return Ok(Some(name));
+        }
+        _ => {
+            return Err(GfxError::ShaderPreprocess(format!("Malformed include directive: {}", line.trim())));
+        }
+    }
+}
+
+/// Inserts the defines after the `#version` directive, or at the start if the source has no version.
+fn inject_defines(source : &str, defines : &ShaderDefines) -> String {
+    if defines.is_empty() {
+        return String::from(source);
+    }
+
+    let mut define_lines = String::new();
+    for (name, value) in defines {
+        define_lines.push_str(&format!("#define {} {}\n", name, value));
+    }
+
+    let mut output = String::with_capacity(source.len() + define_lines.len());
+    let mut injected = false;
+    for line in source.lines() {
+        output.push_str(line);
+        output.push('\n');
+        if !injected && line.trim_start().starts_with("#version") {
+            output.push_str(&define_lines);
+            injected = true;
+        }
+    }
+    if !injected {
+        output.insert_str(0, &define_lines);
+    }
+    return output;
+}
+
+/// Creates a define set from name and value pairs.
+pub fn defines(pairs : &[(&str, &str)]) -> ShaderDefines {
+    return pairs.iter().map(|(name, value)| (String::from(*name), String::from(*value))).collect();
+}
@@ -0,0 +1,221 @@
+use std::ffi::CString;
+
+use gl::types::*;
+
+/// Describes an active uniform of a shader program.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UniformInfo {
+    /// The name of the uniform. Arrays are reported without the `[0]` suffix.
+    pub name : String,
+    /// The OpenGL type of the uniform, e.g. `gl::FLOAT_VEC4` or `gl::SAMPLER_2D`.
+    pub gl_type : u32,
+    /// The number of elements. 1 for non array uniforms.
+    pub size : i32,
+    /// The location of the uniform, or -1 if the uniform is part of a uniform block.
+    pub location : i32,
+    /// The index of the uniform block the uniform belongs to, or -1.
+    pub block_index : i32,
+    /// The byte offset within the uniform block, or -1.
+    pub block_offset : i32,
+}
+
+/// Describes an active vertex attribute of a shader program.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributeInfo {
+    pub name : String,
+    pub gl_type : u32,
+    pub size : i32,
+    pub location : i32,
+}
+
+/// Describes an active uniform block of a shader program.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UniformBlockInfo {
+    pub name : String,
+    /// The index of the block within the program.
+    pub index : u32,
+    /// The binding point the block is assigned to.
+    pub binding : u32,
+    /// The minimum size of the buffer which backs the block, in bytes.
+    pub data_size : i32,
+    /// The names of the uniforms within the block.
+    pub uniforms : Vec<String>,
+}
+
+/// The reflected metadata of a linked shader program.
+///
+/// ### Notes:
+/// - Only active uniforms and attributes are reported. The driver removes everything
+///   which does not contribute to the output of the program.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ShaderReflection {
+    pub uniforms : Vec<UniformInfo>,
+    pub attributes : Vec<AttributeInfo>,
+    pub uniform_blocks : Vec<UniformBlockInfo>,
+}
+
+impl ShaderReflection {
+
+    /// Queries the active uniforms, attributes and uniform blocks of a linked program.
+    pub fn reflect(program_id : u32) -> ShaderReflection {
+        let mut reflection = ShaderReflection::default();
+        unsafe {
+            let mut count = 0;
+            let mut max_length = 0;
+            gl::GetProgramiv(program_id, gl::ACTIVE_UNIFORMS, &mut count);
+            gl::GetProgramiv(program_id, gl::ACTIVE_UNIFORM_MAX_LENGTH, &mut max_length);
+            for index in 0 .. count.max(0) as u32 {
+                let mut size = 0;
+                let mut gl_type = 0;
+                let name = read_name(max_length, |length, written, buffer| {
+                    gl::GetActiveUniform(program_id, index, length, written, &mut size, &mut gl_type, buffer);
+                });
+                let mut block_index = -1;
+                let mut block_offset = -1;
+                gl::GetActiveUniformsiv(program_id, 1, &index, gl::UNIFORM_BLOCK_INDEX, &mut block_index);
+                gl::GetActiveUniformsiv(program_id, 1, &index, gl::UNIFORM_OFFSET, &mut block_offset);
+                let location = match CString::new(name.as_str()) {
+                    Ok(c_name) => gl::GetUniformLocation(program_id, c_name.as_ptr()),
+                    Err(_) => -1
+                };
+                reflection.uniforms.push(UniformInfo {
+                    name: strip_array_suffix(&name).to_string(),
+                    gl_type,
+                    size,
+                    location,
+                    block_index,
+                    block_offset
+                });
+            }
+
+            gl::GetProgramiv(program_id, gl::ACTIVE_ATTRIBUTES, &mut count);
+            gl::GetProgramiv(program_id, gl::ACTIVE_ATTRIBUTE_MAX_LENGTH, &mut max_length);
+            for index in 0 .. count.max(0) as u32 {
+                let mut size = 0;
+                let mut gl_type = 0;
+                let name = read_name(max_length, |length, written, buffer| {
+                    gl::GetActiveAttrib(program_id, index, length, written, &mut size, &mut gl_type, buffer);
+                });
+                let location = match CString::new(name.as_str()) {
+                    Ok(c_name) => gl::GetAttribLocation(program_id, c_name.as_ptr()),
+                    Err(_) => -1
+                };
+                reflection.attributes.push(AttributeInfo {
+                    name: strip_array_suffix(&name).to_string(),
+                    gl_type,
+                    size,
+                    location
+                });
+            }
+
+            gl::GetProgramiv(program_id, gl::ACTIVE_UNIFORM_BLOCKS, &mut count);
+            gl::GetProgramiv(program_id, gl::ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH, &mut max_length);
+            for index in 0 .. count.max(0) as u32 {
+                let name = read_name(max_length, |length, written, buffer| {
+                    gl::GetActiveUniformBlockName(program_id, index, length, written, buffer);
+                });
+                let mut binding = 0;
+                let mut data_size = 0;
+                gl::GetActiveUniformBlockiv(program_id, index, gl::UNIFORM_BLOCK_BINDING, &mut binding);
+                gl::GetActiveUniformBlockiv(program_id, index, gl::UNIFORM_BLOCK_DATA_SIZE, &mut data_size);
+                let uniforms = reflection.uniforms.iter()
+                    .filter(|uniform| uniform.block_index == index as i32)
+                    .map(|uniform| uniform.name.clone())
+                    .collect();
+                reflection.uniform_blocks.push(UniformBlockInfo {
+                    name,
+                    index,
+                    binding: binding as u32,
+                    data_size,
+                    uniforms
+                });
+            }
+        }
+        return reflection;
+    }
+
+    /// Returns the uniform with the given name. Array elements like `weights[2]` resolve to the array.
+    pub fn get_uniform(&self, name : &str) -> Option<&UniformInfo> {
+        let name = strip_array_suffix(name);
+        return self.uniforms.iter().find(|uniform| uniform.name == name);
+    }
+
+    /// Returns the attribute with the given name.
+    pub fn get_attribute(&self, name : &str) -> Option<&AttributeInfo> {
+        return self.attributes.iter().find(|attribute| attribute.name == name);
+    }
+
+    /// Returns the uniform block with the given name.
+    pub fn get_uniform_block(&self, name : &str) -> Option<&UniformBlockInfo> {
+        return self.uniform_blocks.iter().find(|block| block.name == name);
+    }
+}
+
+/// Reads a name of the given maximum length with one of the `glGetActive*` functions.
+unsafe fn read_name<F>(max_length : GLint, read : F) -> String where F : FnOnce(GLsizei, *mut GLsizei, *mut GLchar) {
+    let mut buffer = vec![0u8; max_length.max(1) as usize];
+    let mut written = 0;
+    read(buffer.len() as GLsizei, &mut written, buffer.as_mut_ptr() as *mut GLchar);
+    return String::from_utf8_lossy(&buffer[.. written.max(0) as usize]).to_string();
+}
+
+/// Removes a trailing array index like `[0]` from a uniform name.
+fn strip_array_suffix(name : &str) -> &str {
+    if name.ends_with(']') {
+        if let Some(start) = name.rfind('[') {
+            return &name[.. start];
+        }
+    }
+    return name;
+}
+
+/// Returns true if the OpenGL type is a sampler, which is assigned with an integer texture unit.
+pub fn is_sampler_type(gl_type : u32) -> bool {
+    return matches!(gl_type,
+        gl::SAMPLER_1D | gl::SAMPLER_2D | gl::SAMPLER_3D | gl::SAMPLER_CUBE |
+        gl::SAMPLER_1D_SHADOW | gl::SAMPLER_2D_SHADOW | gl::SAMPLER_CUBE_SHADOW |
+        gl::SAMPLER_1D_ARRAY | gl::SAMPLER_2D_ARRAY | gl::SAMPLER_1D_ARRAY_SHADOW | gl::SAMPLER_2D_ARRAY_SHADOW |
+        gl::SAMPLER_2D_MULTISAMPLE | gl::SAMPLER_2D_MULTISAMPLE_ARRAY | gl::SAMPLER_CUBE_MAP_ARRAY |
+        gl::SAMPLER_BUFFER | gl::SAMPLER_2D_RECT |
+        gl::INT_SAMPLER_2D | gl::INT_SAMPLER_3D | gl::INT_SAMPLER_CUBE | gl::INT_SAMPLER_2D_ARRAY |
+        gl::UNSIGNED_INT_SAMPLER_2D | gl::UNSIGNED_INT_SAMPLER_3D | gl::UNSIGNED_INT_SAMPLER_CUBE | gl::UNSIGNED_INT_SAMPLER_2D_ARRAY
+    );
+}
+
+/// Returns the GLSL name of an OpenGL type, e.g. `vec4` for `gl::FLOAT_VEC4`.
+pub fn gl_type_name(gl_type : u32) -> &'static str {
+    match gl_type {
+        gl::FLOAT => "float",
+        gl::FLOAT_VEC2 => "vec2",
+        gl::FLOAT_VEC3 => "vec3",
+        gl::FLOAT_VEC4 => "vec4",
+        gl::INT => "int",
+        gl::INT_VEC2 => "ivec2",
+        gl::INT_VEC3 => "ivec3",
+        gl::INT_VEC4 => "ivec4",
+        gl::UNSIGNED_INT => "uint",
+        gl::UNSIGNED_INT_VEC2 => "uvec2",
+        gl::UNSIGNED_INT_VEC3 => "uvec3",
+        gl::UNSIGNED_INT_VEC4 => "uvec4",
+        gl::BOOL => "bool",
+        gl::BOOL_VEC2 => "bvec2",
+        gl::BOOL_VEC3 => "bvec3",
+        gl::BOOL_VEC4 => "bvec4",
+        gl::FLOAT_MAT2 => "mat2",
+        gl::FLOAT_MAT3 => "mat3",
+        gl::FLOAT_MAT4 => "mat4",
+        gl::SAMPLER_1D => "sampler1D",
+        gl::SAMPLER_2D => "sampler2D",
+        gl::SAMPLER_3D => "sampler3D",
+        gl::SAMPLER_CUBE => "samplerCube",
+        gl::SAMPLER_2D_SHADOW => "sampler2DShadow",
+        gl::SAMPLER_2D_ARRAY => "sampler2DArray",
+        gl::SAMPLER_2D_MULTISAMPLE => "sampler2DMS",
+        gl::SAMPLER_CUBE_MAP_ARRAY => "samplerCubeArray",
+        gl::SAMPLER_BUFFER => "samplerBuffer",
+        gl::INT_SAMPLER_2D => "isampler2D",
+        gl::UNSIGNED_INT_SAMPLER_2D => "usampler2D",
+        _ if is_sampler_type(gl_type) => "sampler",
+        _ => "unknown"
+    }
+}
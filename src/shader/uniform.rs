@@ -0,0 +1,268 @@
+use nalgebra::{Matrix3, Matrix4, Vector2, Vector3, Vector4};
+
+use super::reflection::is_sampler_type;
+
+/// A value which can be assigned to a uniform of the bound shader program.
+///
+/// Implemented for `f32`, `i32`, `u32`, `bool`, the nalgebra vectors and matrices and slices or arrays of them.
+/// Slices are assigned to uniform arrays, starting at the given location.
+///
+/// # Example
+/// ```ignore
+/// render_device.set_uniform("time", 1.5);
+/// render_device.set_uniform("tint", Vector4::new(1.0, 0.5, 0.5, 1.0));
+/// render_device.set_uniform("weights", &[0.25, 0.5, 0.25]);
+/// ```
+pub trait Uniform {
+    /// Assigns the value to the uniform at the given location of the bound program.
+    fn set_uniform(&self, location : i32);
+
+    /// Returns true if the value can be assigned to a uniform of the given OpenGL type.
+    fn is_compatible(&self, gl_type : u32) -> bool;
+}
+
+impl Uniform for f32 {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::Uniform1f(location, *self);
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::FLOAT;
+    }
+}
+
+impl Uniform for i32 {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::Uniform1i(location, *self);
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::INT || gl_type == gl::BOOL || is_sampler_type(gl_type);
+    }
+}
+
+impl Uniform for u32 {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::Uniform1ui(location, *self);
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::UNSIGNED_INT || gl_type == gl::BOOL;
+    }
+}
+
+impl Uniform for bool {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::Uniform1i(location, *self as i32);
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::BOOL || gl_type == gl::INT;
+    }
+}
+
+impl Uniform for Vector2<f32> {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::Uniform2f(location, self.x, self.y);
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::FLOAT_VEC2;
+    }
+}
+
+impl Uniform for Vector3<f32> {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::Uniform3f(location, self.x, self.y, self.z);
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::FLOAT_VEC3;
+    }
+}
+
+impl Uniform for Vector4<f32> {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::Uniform4f(location, self.x, self.y, self.z, self.w);
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::FLOAT_VEC4;
+    }
+}
+
+impl Uniform for Vector2<i32> {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::Uniform2i(location, self.x, self.y);
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::INT_VEC2 || gl_type == gl::BOOL_VEC2;
+    }
+}
+
+impl Uniform for Vector3<i32> {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::Uniform3i(location, self.x, self.y, self.z);
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::INT_VEC3 || gl_type == gl::BOOL_VEC3;
+    }
+}
+
+impl Uniform for Vector4<i32> {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::Uniform4i(location, self.x, self.y, self.z, self.w);
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::INT_VEC4 || gl_type == gl::BOOL_VEC4;
+    }
+}
+
+impl Uniform for Matrix3<f32> {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::UniformMatrix3fv(location, 1, gl::FALSE, self.as_ptr());
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::FLOAT_MAT3;
+    }
+}
+
+impl Uniform for Matrix4<f32> {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::UniformMatrix4fv(location, 1, gl::FALSE, self.as_ptr());
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::FLOAT_MAT4;
+    }
+}
+
+impl Uniform for [f32] {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::Uniform1fv(location, self.len() as i32, self.as_ptr());
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::FLOAT;
+    }
+}
+
+impl Uniform for [i32] {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::Uniform1iv(location, self.len() as i32, self.as_ptr());
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::INT || gl_type == gl::BOOL || is_sampler_type(gl_type);
+    }
+}
+
+impl Uniform for [Vector2<f32>] {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::Uniform2fv(location, self.len() as i32, self.as_ptr() as *const f32);
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::FLOAT_VEC2;
+    }
+}
+
+impl Uniform for [Vector3<f32>] {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::Uniform3fv(location, self.len() as i32, self.as_ptr() as *const f32);
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::FLOAT_VEC3;
+    }
+}
+
+impl Uniform for [Vector4<f32>] {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::Uniform4fv(location, self.len() as i32, self.as_ptr() as *const f32);
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::FLOAT_VEC4;
+    }
+}
+
+impl Uniform for [Matrix4<f32>] {
+    fn set_uniform(&self, location : i32) {
+        unsafe {
+            gl::UniformMatrix4fv(location, self.len() as i32, gl::FALSE, self.as_ptr() as *const f32);
+        }
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return gl_type == gl::FLOAT_MAT4;
+    }
+}
+
+impl<T, const N : usize> Uniform for [T; N] where [T] : Uniform {
+    fn set_uniform(&self, location : i32) {
+        self.as_slice().set_uniform(location);
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return self.as_slice().is_compatible(gl_type);
+    }
+}
+
+impl<T> Uniform for Vec<T> where [T] : Uniform {
+    fn set_uniform(&self, location : i32) {
+        self.as_slice().set_uniform(location);
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return self.as_slice().is_compatible(gl_type);
+    }
+}
+
+impl<T : Uniform + ?Sized> Uniform for &T {
+    fn set_uniform(&self, location : i32) {
+        (**self).set_uniform(location);
+    }
+
+    fn is_compatible(&self, gl_type : u32) -> bool {
+        return (**self).is_compatible(gl_type);
+    }
+}
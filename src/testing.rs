@@ -0,0 +1,211 @@
+use std::path::PathBuf;
+
+use nalgebra::{Vector2, Vector4};
+
+use crate::graphics::{
+    headless_context::{HeadlessBackend, HeadlessContext},
+    image_texture::{ColorMode, ImageTexture},
+    render_device::RenderDevice,
+    render_target::RenderTarget,
+    viewport::Viewport,
+};
+
+/// The result of comparing a rendered image against a reference image.
+pub struct ImageComparison {
+    /// Whether both images have the same dimensions. If not, no pixels are compared.
+    pub dimensions_match: bool,
+    /// The number of pixels where at least one channel differs by more than the tolerance.
+    pub mismatched_pixels: usize,
+    /// The largest difference of a single channel between both images.
+    pub max_channel_difference: u8,
+    /// An image which highlights the mismatched pixels in red on top of a faded copy of the reference.
+    pub diff_image: Option<ImageTexture>,
+}
+
+impl ImageComparison {
+    /// Returns true if the images have the same size and no pixel exceeds the tolerance.
+    pub fn passed(&self) -> bool {
+        return self.dimensions_match && self.mismatched_pixels == 0;
+    }
+}
+
+/// Reads the pixel at the given index as RGBA, regardless of the color mode of the texture data.
+fn rgba_pixel(data : &[u8], mode : ColorMode, index : usize) -> [u8; 4] {
+    let bpp = mode.bytes_per_pixel();
+    let pixel = &data[index * bpp .. (index + 1) * bpp];
+    let channel = |c : usize| -> u8 {
+        let value = &pixel[c * mode.bytes_per_channel() .. (c + 1) * mode.bytes_per_channel()];
+        match value.len() {
+            2 => (u16::from_ne_bytes([value[0], value[1]]) >> 8) as u8,
+            4 => (f32::from_ne_bytes([value[0], value[1], value[2], value[3]]).clamp(0.0, 1.0) * 255.0).round() as u8,
+            _ => value[0]
+        }
+    };
+    match mode.channels() {
+        1 => [channel(0), channel(0), channel(0), 255],
+        2 => [channel(0), channel(0), channel(0), channel(1)],
+        3 => [channel(0), channel(1), channel(2), 255],
+        _ => [channel(0), channel(1), channel(2), channel(3)]
+    }
+}
+
+/// Compares two preloaded images channel by channel.
+///
+/// # Arguments
+/// - `actual`: The rendered image.
+/// - `expected`: The reference image.
+/// - `tolerance`: The maximum allowed difference per channel.
+///
+/// # Returns
+/// - An `ImageComparison` with the statistics and a diff image.
+pub fn compare_images(actual : &ImageTexture, expected : &ImageTexture, tolerance : u8) -> ImageComparison {
+    let mut comparison = ImageComparison {
+        dimensions_match: false,
+        mismatched_pixels: 0,
+        max_channel_difference: 0,
+        diff_image: None
+    };
+
+    let (ImageTexture::PreLoad { path: _, dimensions: actual_dimensions, data: actual_data, mode: actual_mode },
+         ImageTexture::PreLoad { path: _, dimensions: expected_dimensions, data: expected_data, mode: expected_mode }) = (actual, expected) else {
+        eprintln!("Only preloaded textures can be compared. Read the texture back from the gpu first.");
+        return comparison;
+    };
+
+    if actual_dimensions != expected_dimensions {
+        return comparison;
+    }
+    comparison.dimensions_match = true;
+
+    let pixel_count = actual_dimensions.x as usize * actual_dimensions.y as usize;
+    let mut diff_data = Vec::with_capacity(pixel_count * 4);
+    for i in 0 .. pixel_count {
+        let a = rgba_pixel(actual_data, *actual_mode, i);
+        let e = rgba_pixel(expected_data, *expected_mode, i);
+        let difference = (0 .. 4).map(|c| a[c].abs_diff(e[c])).max().unwrap_or(0);
+        comparison.max_channel_difference = comparison.max_channel_difference.max(difference);
+
+        if difference > tolerance {
+            comparison.mismatched_pixels += 1;
+            diff_data.extend_from_slice(&[255, 0, 0, 255]);
+        }
+        else {
+            let gray = ((e[0] as u32 + e[1] as u32 + e[2] as u32) / 3 / 4) as u8;
+            diff_data.extend_from_slice(&[gray, gray, gray, 255]);
+        }
+    }
+
+    comparison.diff_image = Some(ImageTexture::PreLoad {
+        path: String::new(),
+        dimensions: *actual_dimensions,
+        data: diff_data,
+        mode: ColorMode::RGBA
+    });
+    return comparison;
+}
+
+/// A harness for golden image regression tests.
+///
+/// The harness renders scenes into an offscreen render target of a headless context and compares
+/// the result against reference pngs within the reference directory.
+///
+/// ### Notes:
+/// - A missing reference fails the check. Set `GFX_BLESS=1` to write missing references and overwrite all others.
+/// - On a mismatch `<name>.actual.png` and `<name>.diff.png` are written next to the reference.
+/// - The backend can be selected with `GFX_HEADLESS_BACKEND` (`native`, `egl` or `osmesa`).
+pub struct GoldenHarness {
+    pub context : HeadlessContext,
+    pub render_target : RenderTarget,
+    pub reference_dir : PathBuf,
+    pub tolerance : u8,
+}
+
+impl GoldenHarness {
+
+    /// Creates a new harness with an offscreen render target of the given size.
+    ///
+    /// # Returns
+    /// - `Some(GoldenHarness)`, or `None` if no OpenGL context could be created.
+    pub fn new(width : u32, height : u32, reference_dir : &str) -> Option<GoldenHarness> {
+        let backend = match std::env::var("GFX_HEADLESS_BACKEND").unwrap_or_default().to_lowercase().as_str() {
+            "egl" => HeadlessBackend::Egl,
+            "osmesa" => HeadlessBackend::OsMesa,
+            _ => HeadlessBackend::Native
+        };
+        let mut context = HeadlessContext::new(width, height, backend)?;
+        let render_target = context.render_device.create_render_target(width, height);
+        return Some(GoldenHarness {
+            context,
+            render_target,
+            reference_dir: PathBuf::from(reference_dir),
+            tolerance: 2
+        });
+    }
+
+    /// Returns the render device of the harness.
+    pub fn render_device(&mut self) -> &mut RenderDevice {
+        return &mut self.context.render_device;
+    }
+
+    /// Renders a scene into the offscreen render target and reads the result back.
+    pub fn render<F : FnOnce(&mut RenderDevice)>(&mut self, clear_color : Vector4<f32>, scene : F) -> ImageTexture {
+        let render_target = self.render_target;
+        let device = &mut self.context.render_device;
+        device.bind_render_target(render_target);
+        device.set_viewport(Viewport { size: Vector2::new(render_target.size.x, render_target.size.y) });
+        device.clear_color(clear_color);
+        device.clear();
+        scene(device);
+        device.unbind_render_target();
+        return device.read_render_target(&render_target);
+    }
+
+    /// Compares the image against the reference with the given name.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the image matches or the reference was blessed, otherwise an error message.
+    pub fn check(&self, name : &str, image : &ImageTexture) -> Result<(), String> {
+        let reference_path = self.reference_dir.join(format!("{}.png", name));
+        let reference_file = reference_path.to_string_lossy().to_string();
+        let bless = std::env::var("GFX_BLESS").map(|v| v == "1").unwrap_or(false);
+
+        if bless {
+            std::fs::create_dir_all(&self.reference_dir).map_err(|e| e.to_string())?;
+            image.save_png(&reference_file).map_err(|e| e.to_string())?;
+            println!("Golden image {} written to {}", name, reference_file);
+            return Ok(());
+        }
+        if !reference_path.exists() {
+            return Err(format!("Golden image {} has no reference {}, run the tests with GFX_BLESS=1 to write it", name, reference_file));
+        }
+
+        let reference = ImageTexture::load_from_file(&reference_file, false);
+        let comparison = compare_images(image, &reference, self.tolerance);
+        if comparison.passed() {
+            return Ok(());
+        }
+
+        let actual_file = self.reference_dir.join(format!("{}.actual.png", name)).to_string_lossy().to_string();
+        image.save_png(&actual_file).map_err(|e| e.to_string())?;
+        if !comparison.dimensions_match {
+            return Err(format!("Golden image {} has different dimensions than the reference, actual image written to {}", name, actual_file));
+        }
+
+        let diff_file = self.reference_dir.join(format!("{}.diff.png", name)).to_string_lossy().to_string();
+        if let Some(diff_image) = &comparison.diff_image {
+            diff_image.save_png(&diff_file).map_err(|e| e.to_string())?;
+        }
+        return Err(format!(
+            "Golden image {} differs from the reference: {} pixels above tolerance {} (max difference {}), see {} and {}",
+            name, comparison.mismatched_pixels, self.tolerance, comparison.max_channel_difference, actual_file, diff_file
+        ));
+    }
+
+    /// Renders the scene and panics if it does not match the reference with the given name.
+    pub fn assert_scene<F : FnOnce(&mut RenderDevice)>(&mut self, name : &str, clear_color : Vector4<f32>, scene : F) {
+        let image = self.render(clear_color, scene);
+        if let Err(e) = self.check(name, &image) {
+            panic!("{}", e);
+        }
+    }
+}
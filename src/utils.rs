@@ -6,7 +6,11 @@ use std::{
 use nalgebra::{Vector2, Vector4};
 use uuid::Uuid;
 
-use crate::{graphics::image_texture::ImageTexture, math::Rect};
+use crate::{
+    error::{GfxError, GfxResult},
+    graphics::image_texture::ImageTexture,
+    math::Rect,
+};
 
 /// Loads the content of a file into a string.
 ///
@@ -19,17 +23,28 @@ use crate::{graphics::image_texture::ImageTexture, math::Rect};
 /// # Errors
 /// - Prints an error message to `stderr` if the file cannot be opened or read.
 pub fn load_file_as_string(file: String) -> String {
-    match std::fs::read_to_string(file) {
+    match try_load_file_as_string(&file) {
         Ok(contents) => {
             return contents;
         }
         Err(e) => {
-            eprint!("Error while loading the file {}", e);
+            eprintln!("Error while loading the file {}", e);
             return String::default();
         }
     }
 }
 
+/// Loads the content of a file into a string.
+///
+/// # Errors
+/// - Returns `GfxError::Io` if the file cannot be opened or read.
+pub fn try_load_file_as_string(file: &str) -> GfxResult<String> {
+    return std::fs::read_to_string(file).map_err(|e| GfxError::Io {
+        path: file.to_string(),
+        source: e,
+    });
+}
+
 /// Converts a Rust `String` to a C-style string (`CString`).
 ///
 /// # Arguments
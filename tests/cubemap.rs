@@ -0,0 +1,55 @@
+use gfx::graphics::{cubemap::{Cubemap, CubemapFace, CUBEMAP_FACES}, image_texture::{ColorMode, ImageTexture}};
+use nalgebra::{Vector2, Vector3};
+
+#[test]
+fn faces_follow_the_opengl_order() {
+    assert_eq!(CUBEMAP_FACES[0].target(), gl::TEXTURE_CUBE_MAP_POSITIVE_X);
+    assert_eq!(CUBEMAP_FACES[5].target(), gl::TEXTURE_CUBE_MAP_NEGATIVE_Z);
+    assert_eq!(CubemapFace::PositiveY.direction(0.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+    //the first row of a side face is its top
+    assert!(CubemapFace::NegativeZ.direction(0.0, -1.0).y > 0.0);
+}
+
+#[test]
+fn cubemap_requires_six_matching_square_images() {
+    let face = |size : u32, mode : ColorMode| ImageTexture::load_from_data_with_mode(vec![0; (size * size) as usize * mode.bytes_per_pixel()], Vector2::new(size, size), mode);
+
+    let cubemap = Cubemap::from_images(&vec![face(4, ColorMode::RGB); 6]).unwrap();
+    let Cubemap::PreLoad { size, mode, faces } = &cubemap else {
+        panic!("expected a preloaded cubemap");
+    };
+    assert_eq!((*size, *mode, faces.len()), (4, ColorMode::RGB, 6));
+
+    assert!(Cubemap::from_images(&vec![face(4, ColorMode::RGB); 5]).is_err());
+    let mut mixed = vec![face(4, ColorMode::RGB); 6];
+    mixed[3] = face(4, ColorMode::RGBA);
+    assert!(Cubemap::from_images(&mixed).is_err());
+    let not_square = ImageTexture::load_from_data_with_mode(vec![0; 24], Vector2::new(4, 2), ColorMode::RGB);
+    let mut mixed = vec![face(4, ColorMode::RGB); 6];
+    mixed[0] = not_square;
+    assert!(Cubemap::from_images(&mixed).is_err());
+}
+
+#[test]
+fn equirectangular_panorama_is_projected_onto_the_faces() {
+    //the upper half of the hdr panorama is bright, the lower half dark
+    let (width, height) = (16, 8);
+    let data : Vec<u8> = (0 .. width * height)
+        .flat_map(|pixel| if pixel / width < height / 2 { 4.0f32 } else { 0.0f32 }.to_ne_bytes())
+        .collect();
+    let panorama = ImageTexture::load_from_data_with_mode(data, Vector2::new(width as u32, height as u32), ColorMode::R32F);
+
+    let Cubemap::PreLoad { size, mode, faces } = Cubemap::from_equirectangular(&panorama, 4).unwrap() else {
+        panic!("expected a preloaded cubemap");
+    };
+    assert_eq!((size, mode), (4, ColorMode::R32F));
+    let values = |face : usize| -> Vec<f32> { faces[face].chunks_exact(4).map(|value| f32::from_ne_bytes([value[0], value[1], value[2], value[3]])).collect() };
+
+    //top and bottom face
+    assert!(values(2).iter().all(|value| *value == 4.0));
+    assert!(values(3).iter().all(|value| *value == 0.0));
+    //the upper rows of a side face are bright, the lower rows dark
+    let front = values(4);
+    assert_eq!(front[0], 4.0);
+    assert_eq!(front[15], 0.0);
+}
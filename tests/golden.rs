@@ -0,0 +1,139 @@
+use gfx::{
+    core::transform::{Transform2D, Transform3D},
+    graphics::{
+        camera::{OrthographicCamera, PerspectiveCamera},
+        image_texture::ImageTexture,
+        material::Material,
+        mesh::Mesh,
+        render_device::RenderDevice,
+        TextAlignment,
+    },
+    math::{Vector2, Vector3, Vector4},
+    shader::prebuild_shader::{FontShader, MeshShader, PrebuildShaderProgram, RectShader, Texture2DShader},
+    testing::{compare_images, GoldenHarness},
+};
+
+const WIDTH : u32 = 128;
+const HEIGHT : u32 = 128;
+
+/// Creates a 4x4 checkerboard texture with red, green, blue and white cells.
+fn checker_texture() -> ImageTexture {
+    let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255], [255, 255, 255, 255]];
+    let mut data = Vec::new();
+    for y in 0 .. 4 {
+        for x in 0 .. 4 {
+            data.extend_from_slice(&colors[(x + y) % 4]);
+        }
+    }
+    return ImageTexture::load_from_data(data, Vector2::new(4, 4));
+}
+
+fn ortho_camera() -> OrthographicCamera {
+    OrthographicCamera {
+        position: Vector3::new(0.0, 0.0, 0.0),
+        size: Vector3::new(WIDTH as f32, HEIGHT as f32, 0.0),
+        near: 0.1,
+        far: 100.0
+    }
+}
+
+fn scene_rects(device : &mut RenderDevice) {
+    let mut shader = RectShader::build_shader_program();
+    device.build_shader_program(&mut shader);
+    device.bind_shader_program(&mut shader);
+    device.set_camera(&mut ortho_camera());
+    device.fill_rect(Transform2D::new(Vector2::new(-20.0, 0.0), 0.0, Vector2::new(60.0, 40.0)), Vector4::new(1.0, 0.5, 0.0, 1.0));
+    device.draw_rect(Transform2D::new(Vector2::new(20.0, 20.0), 45.0, Vector2::new(50.0, 50.0)), 10.0, Vector4::new(0.0, 0.5, 1.0, 1.0));
+    device.unbind_shader_program();
+    device.dispose_shader_program(&mut shader);
+}
+
+fn scene_textures(device : &mut RenderDevice) {
+    let mut shader = Texture2DShader::build_shader_program();
+    device.build_shader_program(&mut shader);
+    device.bind_shader_program(&mut shader);
+    device.set_camera(&mut ortho_camera());
+    let mut texture = checker_texture();
+    device.load_texture(&mut texture);
+    device.draw_texture2d(Transform2D::new(Vector2::new(-30.0, 0.0), 0.0, Vector2::new(48.0, 48.0)), &mut texture, Vector4::new(1.0, 1.0, 1.0, 1.0));
+    device.draw_sub_texture2d(Transform2D::new(Vector2::new(30.0, 0.0), 30.0, Vector2::new(48.0, 48.0)), Vector2::new(0.0, 0.0), Vector2::new(0.5, 0.5), &mut texture, Vector4::new(1.0, 1.0, 1.0, 0.5));
+    device.unbind_shader_program();
+    device.dispose_image_texture(&mut texture);
+    device.dispose_shader_program(&mut shader);
+}
+
+fn scene_mesh(device : &mut RenderDevice) {
+    let mut shader = MeshShader::build_shader_program();
+    device.build_shader_program(&mut shader);
+    device.bind_shader_program(&mut shader);
+    let mut camera = PerspectiveCamera::new(Vector3::new(0.0, 0.0, 3.0), Vector3::new(0.0, 0.0, 0.0), Vector3::new(WIDTH as f32, HEIGHT as f32, 0.0), 0.1, 100.0, 60.0);
+    device.set_camera(&mut camera);
+
+    let mut mesh = Mesh {
+        vertices: vec![-1.0, -1.0, 0.0, -1.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, -1.0, 0.0],
+        uv_cords: vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0],
+        normals: vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0],
+        indicies: vec![0, 1, 3, 3, 1, 2],
+        material: Material {
+            base_color_texture: Some(checker_texture()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    device.init_mesh(&mut mesh);
+
+    let mut transform = Transform3D::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(30.0, 20.0, 0.0), Vector3::new(1.0, 1.0, 1.0));
+    device.draw_mesh(&mut transform, &mut mesh, false);
+    device.unbind_shader_program();
+    device.dispose_mesh(&mut mesh, true);
+    device.dispose_shader_program(&mut shader);
+}
+
+fn scene_text(device : &mut RenderDevice, font_file : &str) {
+    let mut shader = FontShader::build_shader_program();
+    device.build_shader_program(&mut shader);
+    device.bind_shader_program(&mut shader);
+    device.set_camera(&mut ortho_camera());
+    let mut font = device.load_font(font_file, 24);
+    device.draw_text2d(Vector2::new(0.0, 0.0), "Gfx", 1.0, Vector4::new(1.0, 1.0, 1.0, 1.0), &mut font, TextAlignment::MiddleCenter);
+    device.unbind_shader_program();
+    device.dispose_font(&mut font);
+    device.dispose_shader_program(&mut shader);
+}
+
+/// Renders all scenes with one harness, since glfw must not be initialized from several test threads at once.
+#[test]
+fn golden_scenes() {
+    let reference_dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+    let Some(mut harness) = GoldenHarness::new(WIDTH, HEIGHT, reference_dir) else {
+        if std::env::var("GFX_SKIP_GL").is_ok() {
+            eprintln!("Skipping golden image tests, no OpenGL context available");
+            return;
+        }
+        panic!("No OpenGL context available, set GFX_SKIP_GL=1 to skip the golden image tests");
+    };
+
+    let background = Vector4::new(0.1, 0.1, 0.1, 1.0);
+    harness.assert_scene("rects", background, scene_rects);
+    harness.assert_scene("textures", background, scene_textures);
+    //batched sprites must look exactly like immediate draws, the reference is never blessed from the batched render
+    let batched = harness.render(background, |device| {
+        device.enable_sprite_batching();
+        device.begin_frame(0.0, 0.0);
+        scene_textures(device);
+        device.end_frame();
+        assert_eq!(device.get_sprite_batch_stats().draw_calls, 1);
+        assert_eq!(device.get_sprite_batch_stats().sprites, 2);
+        device.disable_sprite_batching();
+    });
+    let reference = ImageTexture::load_from_file(&format!("{}/textures.png", reference_dir), false);
+    let comparison = compare_images(&batched, &reference, harness.tolerance);
+    assert!(comparison.passed(), "Batched sprites differ from the immediate draws: {} pixels above tolerance {} (max difference {})",
+        comparison.mismatched_pixels, harness.tolerance, comparison.max_channel_difference);
+    harness.assert_scene("mesh", background, scene_mesh);
+
+    //the glyphs depend on the font, so the text scene only runs with an explicit font file
+    if let Ok(font_file) = std::env::var("GFX_TEST_FONT") {
+        harness.assert_scene("text", background, |device| scene_text(device, &font_file));
+    }
+}
@@ -0,0 +1,81 @@
+use gfx::graphics::image_texture::{ColorMode, ImageTexture};
+use nalgebra::Vector2;
+
+fn temp_file(name : &str) -> String {
+    return std::env::temp_dir().join(format!("gfx_image_texture_{}_{}", std::process::id(), name)).to_string_lossy().into_owned();
+}
+
+#[test]
+fn color_modes_describe_their_data() {
+    assert_eq!(ColorMode::from_channels(1, 1), Some(ColorMode::R));
+    assert_eq!(ColorMode::from_channels(2, 2), Some(ColorMode::RG16));
+    assert_eq!(ColorMode::from_channels(3, 4), Some(ColorMode::RGB32F));
+    assert_eq!(ColorMode::from_channels(5, 1), None);
+
+    assert_eq!(ColorMode::RG.bytes_per_pixel(), 2);
+    assert_eq!(ColorMode::RGBA16.bytes_per_pixel(), 8);
+    assert_eq!(ColorMode::RGB32F.bytes_per_pixel(), 12);
+    assert_eq!(ColorMode::R.pixel_format(), gl::RED);
+    assert_eq!(ColorMode::RGBA16.pixel_type(), gl::UNSIGNED_SHORT);
+    assert_eq!(ColorMode::RGB32F.pixel_type(), gl::FLOAT);
+    assert!(ColorMode::RGB32F.is_float());
+    assert!(ColorMode::R.swizzle().is_some());
+    assert!(ColorMode::RGB.swizzle().is_none());
+
+    assert_eq!(ColorMode::from_internal_format(gl::RGBA16), Some(ColorMode::RGBA16));
+    assert_eq!(ColorMode::from_internal_format(gl::SRGB8), Some(ColorMode::SRGB));
+    assert_eq!(ColorMode::from_internal_format(gl::RGBA16F), None);
+
+    //the original modes keep their OpenGL values
+    assert_eq!(ColorMode::RGBA as u32, gl::RGBA);
+    assert_eq!(ColorMode::RGB as u32, gl::RGB);
+    assert_eq!(ColorMode::default(), ColorMode::RGBA);
+}
+
+#[test]
+fn srgb_flag_only_applies_to_8_bit_color() {
+    assert_eq!(ColorMode::RGB.with_srgb(true), ColorMode::SRGB);
+    assert_eq!(ColorMode::SRGBA.with_srgb(false), ColorMode::RGBA);
+    assert_eq!(ColorMode::RGBA16.with_srgb(true), ColorMode::RGBA16);
+    assert_eq!(ColorMode::SRGBA.internal_format(), gl::SRGB8_ALPHA8);
+    assert_eq!(ColorMode::SRGB.pixel_format(), gl::RGB);
+
+    let ImageTexture::PreLoad { mode, .. } = ImageTexture::load_from_data(vec![0; 4], Vector2::new(1, 1)).with_srgb(true) else {
+        panic!("expected a preloaded texture");
+    };
+    assert_eq!(mode, ColorMode::SRGBA);
+}
+
+#[test]
+fn texture_data_must_match_the_dimensions() {
+    assert!(ImageTexture::try_load_from_data_with_mode(vec![0; 16], Vector2::new(2, 1), ColorMode::RGBA16).is_ok());
+    assert!(ImageTexture::try_load_from_data_with_mode(vec![0; 8], Vector2::new(2, 1), ColorMode::RGBA16).is_err());
+    assert!(ImageTexture::try_load_from_data_with_mode(vec![0; 24], Vector2::new(2, 1), ColorMode::RGB32F).is_ok());
+}
+
+#[test]
+fn grayscale_and_16_bit_pngs_keep_their_format() {
+    let grayscale_file = temp_file("gray.png");
+    let grayscale = ImageTexture::load_from_data_with_mode(vec![0, 64, 128, 255, 32, 16], Vector2::new(3, 2), ColorMode::R);
+    grayscale.save_png(&grayscale_file).unwrap();
+    let ImageTexture::PreLoad { dimensions, data, mode, .. } = ImageTexture::try_load_from_file(&grayscale_file, false).unwrap() else {
+        panic!("expected a preloaded texture");
+    };
+    assert_eq!(mode, ColorMode::R);
+    assert_eq!(dimensions, Vector2::new(3, 2));
+    assert_eq!(data, vec![0, 64, 128, 255, 32, 16]);
+
+    let deep_file = temp_file("deep.png");
+    let values : [u16; 4] = [0, 1000, 40000, 65535];
+    let deep_data : Vec<u8> = values.iter().flat_map(|value| value.to_ne_bytes()).collect();
+    let deep = ImageTexture::load_from_data_with_mode(deep_data.clone(), Vector2::new(2, 1), ColorMode::RG16);
+    deep.save_png(&deep_file).unwrap();
+    let ImageTexture::PreLoad { data, mode, .. } = ImageTexture::try_load_from_file(&deep_file, false).unwrap() else {
+        panic!("expected a preloaded texture");
+    };
+    assert_eq!(mode, ColorMode::RG16);
+    assert_eq!(data, deep_data);
+
+    let _ = std::fs::remove_file(grayscale_file);
+    let _ = std::fs::remove_file(deep_file);
+}
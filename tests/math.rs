@@ -0,0 +1,17 @@
+use gfx::math::Rect;
+
+#[test]
+fn intersection_of_overlapping_rects() {
+    let a = Rect { x: 0, y: 0, widht: 100, height: 50 };
+    let b = Rect { x: 20, y: 10, widht: 200, height: 20 };
+    let clip = a.intersection(&b);
+    assert_eq!((clip.x, clip.y, clip.widht, clip.height), (20, 10, 80, 20));
+}
+
+#[test]
+fn intersection_of_disjoint_rects_is_empty() {
+    let a = Rect { x: 0.0, y: 0.0, widht: 10.0, height: 10.0 };
+    let b = Rect { x: 20.0, y: 5.0, widht: 10.0, height: 10.0 };
+    let clip = a.intersection(&b);
+    assert_eq!((clip.widht, clip.height), (0.0, 0.0));
+}
@@ -0,0 +1,52 @@
+use gfx::graphics::{
+    image_texture::{ColorMode, ImageTexture},
+    post_process::{ColorLut, PostProcessChain, PostProcessEffect},
+    render_target::RenderTargetFormat,
+};
+use nalgebra::Vector2;
+
+#[test]
+fn identity_lut_maps_colors_to_themselves() {
+    let ColorLut::PreLoad { size, data } = ColorLut::identity(4) else {
+        panic!("expected a preloaded lut");
+    };
+    assert_eq!(size, 4);
+    assert_eq!(data.len(), 4 * 4 * 4 * 3);
+    assert_eq!(&data[0 .. 3], &[0, 0, 0]);
+    //red varies fastest, followed by green and blue
+    assert_eq!(&data[3 .. 6], &[85, 0, 0]);
+    assert_eq!(&data[4 * 3 .. 4 * 3 + 3], &[0, 85, 0]);
+    assert_eq!(&data[data.len() - 3 ..], &[255, 255, 255]);
+}
+
+#[test]
+fn strip_images_are_converted_into_luts() {
+    //2x2x2 lut: slice b=0 on the left, b=1 on the right
+    let mut data = Vec::new();
+    for green in 0 .. 2u8 {
+        for blue in 0 .. 2u8 {
+            for red in 0 .. 2u8 {
+                data.extend_from_slice(&[red * 10, green * 10, blue * 10, 255]);
+            }
+        }
+    }
+    let image = ImageTexture::PreLoad { path: String::new(), dimensions: Vector2::new(4, 2), data, mode: ColorMode::RGBA };
+    let ColorLut::PreLoad { size, data } = ColorLut::from_image(&image).unwrap() else {
+        panic!("expected a preloaded lut");
+    };
+    assert_eq!(size, 2);
+    assert_eq!(data, vec![0, 0, 0, 10, 0, 0, 0, 10, 0, 10, 10, 0, 0, 0, 10, 10, 0, 10, 0, 10, 10, 10, 10, 10]);
+
+    let invalid = ImageTexture::PreLoad { path: String::new(), dimensions: Vector2::new(3, 2), data: vec![0; 24], mode: ColorMode::RGBA };
+    assert!(ColorLut::from_image(&invalid).is_err());
+}
+
+#[test]
+fn chains_collect_effects_in_order() {
+    let mut chain = PostProcessChain::new(RenderTargetFormat::RGBA16F)
+        .with_effect(PostProcessEffect::Bloom { threshold: 1.0, intensity: 0.5, radius: 4.0 });
+    assert_eq!(chain.add_effect(PostProcessEffect::Fxaa), 1);
+    assert!(matches!(chain.effects[0], PostProcessEffect::Bloom { .. }));
+    assert_eq!(chain.format(), RenderTargetFormat::RGBA16F);
+    assert!(!chain.is_loaded());
+}
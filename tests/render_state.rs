@@ -0,0 +1,29 @@
+use gfx::graphics::render_state::{BlendMode, ColorWriteMask, CullMode, DepthFunction, RenderState};
+
+#[test]
+fn default_state_matches_the_initialized_device() {
+    let state = RenderState::default();
+    assert_eq!(state.blend_mode, BlendMode::Alpha);
+    assert!(state.depth_test && state.depth_write);
+    assert_eq!(state.depth_function, DepthFunction::Less);
+    assert_eq!(state.cull_mode, CullMode::None);
+    assert_eq!(state.color_write_mask, ColorWriteMask::ALL);
+}
+
+#[test]
+fn blend_modes_use_the_expected_factors() {
+    assert_eq!(BlendMode::Disabled.blend_factors(), None);
+    assert_eq!(BlendMode::Alpha.blend_factors().unwrap().0, gl::SRC_ALPHA);
+    let (src, dst, _, _) = BlendMode::Premultiplied.blend_factors().unwrap();
+    assert_eq!((src, dst), (gl::ONE, gl::ONE_MINUS_SRC_ALPHA));
+    let (src, dst, _, _) = BlendMode::Additive.blend_factors().unwrap();
+    assert_eq!((src, dst), (gl::SRC_ALPHA, gl::ONE));
+}
+
+#[test]
+fn builders_only_change_their_field() {
+    let state = RenderState::default().with_blend_mode(BlendMode::Screen).with_depth_write(false);
+    assert_eq!(state.blend_mode, BlendMode::Screen);
+    assert!(!state.depth_write);
+    assert_eq!(state.with_blend_mode(BlendMode::Alpha).with_depth_write(true), RenderState::default());
+}
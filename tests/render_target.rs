@@ -0,0 +1,60 @@
+use gfx::graphics::{image_texture::ColorMode, render_target::{DepthAttachment, RenderTarget, RenderTargetDescriptor, RenderTargetFormat}};
+
+#[test]
+fn default_descriptor_matches_create_render_target() {
+    let descriptor = RenderTargetDescriptor::new(320, 240);
+    assert_eq!(descriptor.size.x, 320);
+    assert_eq!(descriptor.size.y, 240);
+    assert_eq!(descriptor.color_formats, vec![RenderTargetFormat::RGBA8]);
+    assert_eq!(descriptor.depth_attachment, DepthAttachment::Renderbuffer);
+}
+
+#[test]
+fn float_formats_use_float_internal_formats() {
+    assert_eq!(RenderTargetFormat::RGBA8.internal_format(), gl::RGBA8);
+    assert_eq!(RenderTargetFormat::RGBA16F.internal_format(), gl::RGBA16F);
+    assert_eq!(RenderTargetFormat::RGBA32F.pixel_format(), (gl::RGBA, gl::FLOAT));
+    assert_eq!(RenderTargetFormat::R11G11B10F.pixel_format(), (gl::RGB, gl::FLOAT));
+    assert!(!RenderTargetFormat::RGBA8.is_float());
+    assert!(RenderTargetFormat::R11G11B10F.is_float());
+    assert_eq!(RenderTargetFormat::RGBA8.read_color_mode(), ColorMode::RGBA);
+    assert_eq!(RenderTargetFormat::RGBA16F.read_color_mode(), ColorMode::RGBA32F);
+    assert_eq!(RenderTargetFormat::R11G11B10F.read_color_mode(), ColorMode::RGB32F);
+}
+
+#[test]
+fn render_target_reports_its_descriptor() {
+    let descriptor = RenderTargetDescriptor::new(64, 32)
+        .with_color_formats(&[RenderTargetFormat::RGBA16F, RenderTargetFormat::RGBA32F])
+        .with_depth_attachment(DepthAttachment::Texture);
+    let mut render_target = RenderTarget {
+        size: descriptor.size,
+        color_attachment_count: 2,
+        depth_attachment: descriptor.depth_attachment,
+        depth_texture_id: 7,
+        ..Default::default()
+    };
+    render_target.color_textures[.. 2].copy_from_slice(&[3, 4]);
+    render_target.color_formats[.. 2].copy_from_slice(&descriptor.color_formats);
+
+    assert_eq!(render_target.descriptor(), descriptor);
+    assert_eq!(render_target.color_texture_ids(), &[3, 4]);
+    assert_eq!(render_target.color_texture(1), Some(4));
+    assert_eq!(render_target.color_texture(2), None);
+    assert_eq!(render_target.depth_texture(), Some(7));
+}
+
+#[test]
+fn multisampled_targets_use_multisample_textures() {
+    let render_target = RenderTarget {
+        size: nalgebra::Vector2::new(16, 16),
+        color_attachment_count: 1,
+        samples: 4,
+        ..Default::default()
+    };
+    assert!(render_target.is_multisampled());
+    assert_eq!(render_target.texture_target(), gl::TEXTURE_2D_MULTISAMPLE);
+    assert_eq!(render_target.descriptor(), RenderTargetDescriptor::new(16, 16).with_samples(4));
+    assert_eq!(render_target.descriptor().with_samples(0).samples, 0);
+    assert_eq!(RenderTarget::default().texture_target(), gl::TEXTURE_2D);
+}
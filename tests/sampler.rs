@@ -0,0 +1,37 @@
+use gfx::graphics::sampler::{MipmapFilter, SamplerDescriptor, TextureFilter, TextureWrap};
+
+#[test]
+fn default_sampler_matches_previous_load_texture() {
+    let sampler = SamplerDescriptor::default();
+    assert_eq!(sampler.gl_min_filter(), gl::LINEAR);
+    assert_eq!(sampler.gl_mag_filter(), gl::LINEAR);
+    assert_eq!(sampler.wrap_u, TextureWrap::Repeat);
+    assert_eq!(sampler.wrap_v, TextureWrap::Repeat);
+    assert!(!sampler.has_mipmaps());
+    assert_eq!(sampler.max_anisotropy, 1.0);
+}
+
+#[test]
+fn nearest_sampler_keeps_pixel_art_sharp() {
+    let sampler = SamplerDescriptor::nearest();
+    assert_eq!(sampler.gl_min_filter(), gl::NEAREST);
+    assert_eq!(sampler.gl_mag_filter(), gl::NEAREST);
+    //only the filter differs from the default sampler
+    assert_eq!(sampler.wrap_u, TextureWrap::Repeat);
+    assert_eq!(sampler.wrap_w, TextureWrap::Repeat);
+}
+
+#[test]
+fn mipmap_filter_combines_with_min_filter() {
+    let sampler = SamplerDescriptor::default().with_mipmaps(MipmapFilter::Linear);
+    assert!(sampler.has_mipmaps());
+    assert_eq!(sampler.gl_min_filter(), gl::LINEAR_MIPMAP_LINEAR);
+    assert_eq!(sampler.gl_mag_filter(), gl::LINEAR);
+
+    let sampler = sampler.with_filter(TextureFilter::Nearest, TextureFilter::Nearest).with_mipmaps(MipmapFilter::Nearest);
+    assert_eq!(sampler.gl_min_filter(), gl::NEAREST_MIPMAP_NEAREST);
+
+    let sampler = sampler.with_wrap_uv(TextureWrap::MirroredRepeat, TextureWrap::ClampToBorder);
+    assert_eq!(sampler.wrap_u as u32, gl::MIRRORED_REPEAT);
+    assert_eq!(sampler.wrap_v as u32, gl::CLAMP_TO_BORDER);
+}
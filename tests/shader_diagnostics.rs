@@ -0,0 +1,16 @@
+use gfx::shader::diagnostics::{format_source_lines, parse_error_lines};
+
+#[test]
+fn parses_driver_log_formats() {
+    assert_eq!(parse_error_lines("0:12(5): error: `foo' undeclared"), vec![12]);
+    assert_eq!(parse_error_lines("0(7) : error C1008: undefined variable \"foo\""), vec![7]);
+    assert_eq!(parse_error_lines("ERROR: 0:3: 'foo' : undeclared identifier\nERROR: 0:3: '' : compilation terminated"), vec![3]);
+    assert!(parse_error_lines("Link error: no main function").is_empty());
+}
+
+#[test]
+fn formats_referenced_lines_with_context() {
+    let source = "#version 330 core\nout vec4 color;\nvoid main() {\n    color = foo;\n}\n";
+    let formatted = format_source_lines(source, &[4], 1);
+    assert_eq!(formatted, "     3 | void main() {\n>    4 |     color = foo;\n     5 | }\n");
+}
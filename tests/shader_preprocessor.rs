@@ -0,0 +1,83 @@
+use gfx::{
+    error::GfxError,
+    shader::{preprocessor::{defines, ShaderPreprocessor}, Shader, ShaderProgram, ShaderVariants},
+};
+
+#[test]
+fn resolves_library_includes_once() {
+    let preprocessor = ShaderPreprocessor::default();
+    let shader = Shader::from_source("#version 330 core\n#include <gfx/camera.glsl>\n#include \"gfx/mvp.glsl\"\nvoid main() {}\n");
+    let processed = preprocessor.process(&shader).unwrap();
+    assert_eq!(processed.source.matches("uniform GfxFrame").count(), 1);
+    assert!(processed.source.contains("vec4 gfx_mvp_transform(vec3 position)"));
+    assert!(!processed.source.contains("#include"));
+    assert!(processed.includes.is_empty());
+}
+
+#[test]
+fn frame_library_provides_the_alpha_test() {
+    let preprocessor = ShaderPreprocessor::default();
+    let shader = Shader::from_source("#version 330 core\n#include <gfx/frame.glsl>\nvoid main() { GFX_ALPHA_TEST(1.0); }\n");
+    let processed = preprocessor.process(&shader).unwrap();
+    assert!(processed.source.contains("vec4 gfx_render_params;"));
+    assert!(processed.source.contains("#define GFX_ALPHA_TEST(alpha)"));
+}
+
+#[test]
+fn ignores_includes_in_comments() {
+    let preprocessor = ShaderPreprocessor::default();
+    let source = "#version 330 core\n// #include <missing.glsl>\n/*\n#include <missing.glsl>\n*/ /* #include <missing.glsl> */\n#include <gfx/mvp.glsl> // the camera\nvoid main() {}\n";
+    let processed = preprocessor.process(&Shader::from_source(source)).unwrap();
+    assert!(processed.source.contains("vec4 gfx_mvp_transform(vec3 position)"));
+    assert!(processed.source.contains("// #include <missing.glsl>"));
+    assert!(!processed.source.contains("#include <gfx/mvp.glsl>"));
+}
+
+#[test]
+fn injects_defines_after_version() {
+    let preprocessor = ShaderPreprocessor::default();
+    let shader = Shader::from_source("\n    #version 330 core\nvoid main() {}\n").with_define("NORMAL_MAP", "1");
+    let processed = preprocessor.process(&shader).unwrap();
+    assert_eq!(processed.source, "\n    #version 330 core\n#define NORMAL_MAP 1\nvoid main() {}\n");
+}
+
+#[test]
+fn resolves_file_includes_relative_to_the_shader() {
+    let dir = std::env::temp_dir().join(format!("gfx_shader_preprocessor_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("common")).unwrap();
+    std::fs::write(dir.join("common/light.glsl"), "#include \"color.glsl\"\nfloat light() { return 1.0; }\n").unwrap();
+    std::fs::write(dir.join("common/color.glsl"), "vec4 tint() { return vec4(1.0); }\n").unwrap();
+    std::fs::write(dir.join("shader.frag"), "#version 330 core\n#include \"common/light.glsl\"\n").unwrap();
+    std::fs::write(dir.join("cycle.glsl"), "#include \"cycle.glsl\"\n").unwrap();
+
+    let mut preprocessor = ShaderPreprocessor::default();
+    let shader = Shader::from_file(dir.join("shader.frag").to_str().unwrap()).unwrap();
+    let processed = preprocessor.process(&shader).unwrap();
+    assert_eq!(processed.source, "#version 330 core\nvec4 tint() { return vec4(1.0); }\nfloat light() { return 1.0; }\n");
+    assert_eq!(processed.includes.len(), 2);
+
+    let missing = preprocessor.process(&Shader::from_source("#include \"light.glsl\"\n"));
+    assert!(matches!(missing, Err(GfxError::ShaderPreprocess(_))));
+    preprocessor.add_search_path(dir.join("common").to_str().unwrap());
+    assert!(preprocessor.process(&Shader::from_source("#include \"light.glsl\"\n")).is_ok());
+
+    preprocessor.add_search_path(dir.to_str().unwrap());
+    let cycle = preprocessor.process(&Shader::from_source("#include \"cycle.glsl\"\n"));
+    assert!(matches!(cycle, Err(GfxError::ShaderPreprocess(_))));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn creates_variants_per_define_set() {
+    let variants = ShaderVariants::new(
+        Shader::from_source("#version 330 core\n").with_define("MAX_LIGHTS", "4"),
+        Shader::from_source("#version 330 core\n")
+    );
+    let ShaderProgram::PreBuild { fragment_shader, vertex_shader } = variants.create_program(&defines(&[("NORMAL_MAP", "1")])) else {
+        panic!("Variants must be created in the PreBuild state");
+    };
+    assert_eq!(vertex_shader.defines, defines(&[("MAX_LIGHTS", "4"), ("NORMAL_MAP", "1")]));
+    assert_eq!(fragment_shader.defines, defines(&[("NORMAL_MAP", "1")]));
+    assert!(variants.is_empty());
+}
@@ -0,0 +1,45 @@
+use gfx::{
+    graphics::{
+        headless_context::{HeadlessBackend, HeadlessContext},
+        uniform_buffer::{FrameUniforms, FRAME_BLOCK_BINDING},
+    },
+    shader::prebuild_shader::{PrebuildShaderProgram, Texture2DShader},
+};
+
+#[test]
+fn reflects_uniforms_and_attributes() {
+    let Some(mut context) = HeadlessContext::new(16, 16, HeadlessBackend::Native) else {
+        if std::env::var("GFX_SKIP_GL").is_ok() {
+            eprintln!("Skipping shader reflection test, no OpenGL context available");
+            return;
+        }
+        panic!("No OpenGL context available, set GFX_SKIP_GL=1 to skip the shader reflection test");
+    };
+
+    let device = &mut context.render_device;
+    let mut shader = Texture2DShader::build_shader_program();
+    device.build_shader_program(&mut shader);
+    let reflection = shader.get_reflection().expect("the program should be built").clone();
+
+    let m_mat = reflection.get_uniform("m_mat").expect("m_mat should be active");
+    assert_eq!(m_mat.gl_type, gl::FLOAT_MAT4);
+    assert_eq!(m_mat.size, 1);
+    assert!(m_mat.location >= 0);
+    assert_eq!(reflection.get_uniform("uvScale").map(|u| u.gl_type), Some(gl::FLOAT_VEC2));
+    assert_eq!(reflection.get_uniform("textureSampler").map(|u| u.gl_type), Some(gl::SAMPLER_2D));
+    assert!(reflection.get_uniform("unknown").is_none());
+
+    let position = reflection.get_attribute("inPosition").expect("inPosition should be active");
+    assert_eq!(position.gl_type, gl::FLOAT_VEC3);
+    assert_eq!(position.location, 0);
+
+    let frame_block = reflection.get_uniform_block("GfxFrame").expect("the frame block should be active");
+    assert_eq!(frame_block.binding, FRAME_BLOCK_BINDING);
+    assert_eq!(frame_block.data_size as usize, std::mem::size_of::<FrameUniforms>());
+    let p_mat = reflection.get_uniform("p_mat").expect("p_mat should be active");
+    assert_eq!(p_mat.location, -1);
+    assert_eq!(p_mat.block_index, frame_block.index as i32);
+    assert_eq!(p_mat.block_offset, 0);
+
+    device.dispose_shader_program(&mut shader);
+}
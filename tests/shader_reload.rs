@@ -0,0 +1,57 @@
+use std::{fs::File, io::Write, time::{Duration, SystemTime}};
+
+use gfx::shader::{Shader, ShaderProgram};
+
+fn write_shader(path : &std::path::Path, source : &str, modified : SystemTime) {
+    let mut file = File::create(path).unwrap();
+    file.write_all(source.as_bytes()).unwrap();
+    file.set_modified(modified).unwrap();
+}
+
+#[test]
+fn detects_modified_shader_files() {
+    let dir = std::env::temp_dir().join(format!("gfx_shader_reload_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let vertex_file = dir.join("shader.vert");
+    let fragment_file = dir.join("shader.frag");
+    let loaded_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    write_shader(&vertex_file, "void main() {}", loaded_at);
+    write_shader(&fragment_file, "void main() {}", loaded_at);
+
+    let program = ShaderProgram::from_files(vertex_file.to_str().unwrap(), fragment_file.to_str().unwrap()).unwrap();
+    assert!(!program.has_changed());
+
+    write_shader(&fragment_file, "void main() { discard; }", loaded_at + Duration::from_secs(5));
+    assert!(program.has_changed());
+
+    let mut fragment_shader = Shader::from_file(fragment_file.to_str().unwrap()).unwrap();
+    fragment_shader.source.clear();
+    assert!(!fragment_shader.has_changed());
+    write_shader(&fragment_file, "void main() { discard; }", loaded_at + Duration::from_secs(10));
+    fragment_shader.reload().unwrap();
+    assert_eq!(fragment_shader.source, "void main() { discard; }");
+    assert!(!Shader::from_source("void main() {}").has_changed());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn unreadable_revision_is_not_reported_again() {
+    let dir = std::env::temp_dir().join(format!("gfx_shader_reload_unreadable_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let fragment_file = dir.join("shader.frag");
+    let loaded_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    write_shader(&fragment_file, "void main() {}", loaded_at);
+    let mut fragment_shader = Shader::from_file(fragment_file.to_str().unwrap()).unwrap();
+
+    //invalid utf-8 cannot be read as source
+    let mut file = File::create(&fragment_file).unwrap();
+    file.write_all(&[0xff, 0xfe]).unwrap();
+    file.set_modified(loaded_at + Duration::from_secs(5)).unwrap();
+    assert!(fragment_shader.has_changed());
+    assert!(fragment_shader.reload().is_err());
+    assert_eq!(fragment_shader.source, "void main() {}");
+    assert!(!fragment_shader.has_changed());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
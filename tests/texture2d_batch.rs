@@ -0,0 +1,149 @@
+use gfx::graphics::{Texture2DBatch, Texture2DInstance, Texture2DInstanceData, Texture2DInstances, LAYER_DEPTH_STEP};
+use nalgebra::{Matrix4, Vector2, Vector3, Vector4};
+
+fn instance(id : f32) -> Texture2DInstance {
+    return Texture2DInstance::new(Matrix4::identity(), Vector4::new(id, 0.0, 0.0, 1.0), Texture2DInstance::default_uv_transform(), true);
+}
+
+fn ids(instances : &Texture2DInstances) -> Vec<f32> {
+    return instances.as_slice().iter().map(|instance| instance.color.x).collect();
+}
+
+#[test]
+fn handles_survive_removal_and_reordering() {
+    let mut instances = Texture2DInstances::new();
+    let a = instances.insert(instance(1.0));
+    let b = instances.insert(instance(2.0));
+    let c = instances.insert(instance(3.0));
+    let d = instances.insert(instance(4.0));
+
+    assert_eq!(instances.remove(a).unwrap().color.x, 1.0);
+    assert_eq!(ids(&instances), vec![4.0, 2.0, 3.0]);
+    assert_eq!(instances.get(d).unwrap().color.x, 4.0);
+    assert!(instances.get(a).is_none());
+
+    assert!(instances.move_to(d, 2));
+    assert_eq!(ids(&instances), vec![2.0, 3.0, 4.0]);
+    assert!(instances.swap(b, d));
+    assert_eq!(ids(&instances), vec![4.0, 3.0, 2.0]);
+    assert_eq!(instances.remove_ordered(d).unwrap().color.x, 4.0);
+    assert_eq!(ids(&instances), vec![3.0, 2.0]);
+    assert_eq!(instances.index_of(b), Some(1));
+    assert_eq!(instances.index_of(c), Some(0));
+}
+
+#[test]
+fn reused_slots_invalidate_old_handles() {
+    let mut instances = Texture2DInstances::new();
+    let old = instances.insert(instance(1.0));
+    instances.remove(old);
+    let new = instances.insert(instance(2.0));
+
+    assert_ne!(old, new);
+    assert!(!instances.contains(old));
+    assert!(!instances.set(old, instance(3.0)));
+    assert_eq!(instances.get(new).unwrap().color.x, 2.0);
+}
+
+#[test]
+fn loaded_batches_accept_new_instances() {
+    let mut batch = Texture2DBatch::new();
+    assert_eq!(batch.add_instance(Matrix4::identity(), Vector4::repeat(1.0), Texture2DInstance::default_uv_transform(), true), 0);
+    let instances = std::mem::take(batch.instances_mut());
+    batch = Texture2DBatch::Loaded { instances, instance_buffer: 0, capacity: 16, sorted: false };
+
+    let handle = batch.insert_instance(instance(5.0)).unwrap();
+    assert_eq!(batch.instances().len(), 2);
+    assert!(batch.instances().is_dirty());
+    assert_eq!(batch.remove_instance(handle).unwrap().color.x, 5.0);
+
+    let mut disposed = Texture2DBatch::Disposed { instances: Texture2DInstances::new() };
+    assert!(disposed.insert_instance(instance(1.0)).is_none());
+}
+
+#[test]
+fn modifications_extend_the_dirty_range() {
+    let mut instances = Texture2DInstances::from((0 .. 8).map(|id| instance(id as f32)).collect::<Vec<_>>());
+    assert_eq!(instances.take_dirty_range(), Some(0 .. 8));
+    assert!(!instances.is_dirty());
+
+    assert!(instances.set_range(2, &[instance(10.0), instance(11.0)]));
+    assert!(!instances.set_range(7, &[instance(10.0), instance(11.0)]));
+    assert_eq!(instances.dirty_range(), Some(2 .. 4));
+    let handle = instances.handle_at(5).unwrap();
+    instances.set(handle, instance(12.0));
+    assert_eq!(instances.take_dirty_range(), Some(2 .. 6));
+
+    //removing the last instance leaves nothing to upload
+    instances.remove(instances.handle_at(7).unwrap());
+    assert_eq!(instances.dirty_range(), None);
+    instances.remove(instances.handle_at(1).unwrap());
+    assert_eq!(instances.take_dirty_range(), Some(1 .. 2));
+}
+
+#[test]
+fn instance_data_is_interleaved() {
+    assert_eq!(std::mem::size_of::<Texture2DInstanceData>(), 128);
+    let data = Texture2DBatch::create_instance_buffer(&[instance(1.0), Texture2DInstance { visible: false, ..instance(2.0) }]);
+    assert_eq!(data[0].extras.x, 1.0);
+    assert_eq!(data[1].color.x, 2.0);
+    assert_eq!(data[1].extras.x, 0.0);
+}
+
+#[test]
+fn instance_data_contains_pivot_flip_layer_and_custom_data() {
+    let instance = Texture2DInstance::new(Matrix4::new_translation(&Vector3::new(10.0, 20.0, 0.0)), Vector4::repeat(1.0), Texture2DInstance::default_uv_transform(), true)
+        .with_pivot(Vector2::new(-0.5, -0.5))
+        .with_flip(false, true)
+        .with_layer(3.0)
+        .with_custom(Vector4::new(1.0, 2.0, 3.0, 4.0));
+    let data = instance.create_instance_data();
+
+    //the bottom left corner of the quad is placed at the origin of the transform
+    let corner = data.transform.transform_point(&nalgebra::Point3::new(-0.5, -0.5, 0.0));
+    assert_eq!(corner, nalgebra::Point3::new(10.0, 20.0, 3.0 * LAYER_DEPTH_STEP));
+    assert_eq!(data.extras, Vector4::new(1.0, 2.0, 3.0, 0.0));
+    assert_eq!(data.custom, Vector4::new(1.0, 2.0, 3.0, 4.0));
+}
+
+#[test]
+fn sorting_by_layer_is_stable_and_keeps_handles() {
+    let mut instances = Texture2DInstances::new();
+    let front = instances.insert(instance(1.0).with_layer(2.0));
+    let first = instances.insert(instance(2.0).with_layer(0.0));
+    let second = instances.insert(instance(3.0).with_layer(0.0));
+    instances.take_dirty_range();
+
+    assert!(instances.sort_by_layer());
+    assert_eq!(ids(&instances), vec![2.0, 3.0, 1.0]);
+    assert_eq!(instances.index_of(first), Some(0));
+    assert_eq!(instances.index_of(second), Some(1));
+    assert_eq!(instances.index_of(front), Some(2));
+    assert_eq!(instances.take_dirty_range(), Some(0 .. 3));
+
+    //sorted instances are left untouched
+    assert!(!instances.sort_by_layer());
+    assert!(!instances.is_dirty());
+}
+
+#[test]
+fn layer_order_leaves_the_stored_order_alone() {
+    let mut instances = Texture2DInstances::new();
+    instances.insert(instance(1.0).with_layer(2.0));
+    instances.insert(instance(2.0).with_layer(0.0));
+    instances.insert(instance(3.0).with_layer(1.0));
+    instances.take_dirty_range();
+
+    assert_eq!(instances.layer_order(), Some(vec![1, 2, 0]));
+    assert_eq!(ids(&instances), vec![1.0, 2.0, 3.0]);
+    assert!(!instances.is_dirty());
+
+    assert!(instances.sort_by_layer());
+    assert_eq!(instances.layer_order(), None);
+}
+
+#[test]
+fn layers_move_instances_towards_the_camera() {
+    let data = instance(1.0).with_layer(3.0).create_instance_data();
+    assert_eq!(data.transform[(2, 3)], 3.0 * LAYER_DEPTH_STEP);
+}
@@ -0,0 +1,30 @@
+use gfx::graphics::{image_texture::{ColorMode, ImageTexture}, texture_array::TextureArray, Texture2DInstance};
+use nalgebra::{Matrix4, Vector2, Vector4};
+
+fn image(width : u32, height : u32) -> ImageTexture {
+    return ImageTexture::load_from_data(vec![255; (width * height * 4) as usize], Vector2::new(width, height));
+}
+
+#[test]
+fn images_become_layers() {
+    let texture_array = TextureArray::from_images(&[image(4, 4), image(4, 4), image(4, 4)]).unwrap();
+    assert_eq!(texture_array.layer_count(), 3);
+}
+
+#[test]
+fn layers_must_match_the_array() {
+    assert!(TextureArray::from_images(&[image(4, 4), image(8, 4)]).is_err());
+    assert!(TextureArray::from_images(&[]).is_err());
+
+    let mut texture_array = TextureArray::new(Vector2::new(2, 2), ColorMode::RGBA);
+    assert!(texture_array.add_layer(Vector2::new(2, 2), ColorMode::RGB, vec![0; 12]).is_err());
+    assert!(texture_array.add_layer(Vector2::new(2, 2), ColorMode::RGBA, vec![0; 15]).is_err());
+    assert_eq!(texture_array.add_layer(Vector2::new(2, 2), ColorMode::RGBA, vec![0; 16]).unwrap(), 0);
+}
+
+#[test]
+fn instances_pass_their_texture_index() {
+    let instance = Texture2DInstance::new(Matrix4::identity(), Vector4::repeat(1.0), Texture2DInstance::default_uv_transform(), true)
+        .with_texture_index(5);
+    assert_eq!(instance.create_instance_data().extras.w, 5.0);
+}